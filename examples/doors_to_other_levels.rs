@@ -5,8 +5,8 @@ use bevy::math::Affine3A;
 use bevy::platform::collections::HashSet;
 use bevy::prelude::*;
 use bevy_egui::{EguiPlugin, egui};
+use bevy_yoleck::prelude::*;
 use bevy_yoleck::vpeol::prelude::*;
-use bevy_yoleck::{YoleckEditableLevels, prelude::*};
 use serde::{Deserialize, Serialize};
 
 fn main() {
@@ -67,7 +67,7 @@ fn main() {
             .with::<Doorway>()
     });
     app.add_yoleck_edit_system(edit_doorway_rotation);
-    app.add_yoleck_edit_system(edit_doorway);
+    app.add_yoleck_auto_edit::<Doorway>();
     app.add_systems(YoleckSchedule::Populate, populate_doorway);
     app.add_systems(Update, set_doorways_sprite_index);
 
@@ -208,39 +208,22 @@ fn populate_text(mut populate: YoleckPopulate<&TextContent>, asset_server: Res<A
     });
 }
 
-#[derive(Default, Clone, PartialEq, Serialize, Deserialize, Component, YoleckComponent, Debug)]
+#[derive(
+    Default,
+    Clone,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    Component,
+    YoleckComponent,
+    YoleckAutoEdit,
+    Debug,
+)]
 struct Doorway {
-    target_level: String,
+    target_level: YoleckLevelPath,
     marker: String,
 }
 
-fn edit_doorway(
-    mut ui: ResMut<YoleckUi>,
-    mut edit: YoleckEdit<&mut Doorway>,
-    levels: Res<YoleckEditableLevels>,
-) {
-    let Ok(mut doorway) = edit.single_mut() else {
-        return;
-    };
-
-    ui.horizontal(|ui| {
-        egui::ComboBox::from_id_salt("doorway-level")
-            .selected_text(
-                Some(doorway.target_level.as_str())
-                    .filter(|l| !l.is_empty())
-                    .unwrap_or("<target level>"),
-            )
-            .show_ui(ui, |ui| {
-                for level in levels.names() {
-                    ui.selectable_value(&mut doorway.target_level, level.to_owned(), level);
-                }
-            });
-        egui::TextEdit::singleline(&mut doorway.marker)
-            .hint_text("<marker>")
-            .show(ui);
-    });
-}
-
 fn edit_doorway_rotation(
     mut ui: ResMut<YoleckUi>,
     mut edit: YoleckEdit<(&Vpeol2dPosition, &mut Vpeol2dRotatation), With<Doorway>>,
@@ -378,7 +361,7 @@ fn handle_door_opening(
                 commands.entity(door_entity).insert(DoorIsOpen);
                 commands.spawn((
                     YoleckLoadLevel(
-                        asset_server.load(format!("levels_doors/{}", doorway.target_level)),
+                        asset_server.load(doorway.target_level.asset_path("levels_doors")),
                     ),
                     LevelFromOpenedDoor {
                         exit_door: door_entity,
@@ -416,7 +399,7 @@ fn position_level_from_opened_door(
             })
             .expect(&format!(
                 "Cannot find a door marked as {:?} in {:?}",
-                exit_doorway.marker, exit_doorway.target_level
+                exit_doorway.marker, exit_doorway.target_level.filename
             ));
         let entry_door_affine = Affine3A::from_rotation_translation(
             Quat::from_rotation_z(entry_door_rotation.0),
@@ -41,6 +41,7 @@ fn main() {
         ));
         app.add_plugins(Vpeol2dPluginForEditor);
         app.add_plugins(VpeolSelectionCuePlugin::default());
+        app.add_plugins(VpeolEntityRefLinesPlugin::default());
         #[cfg(target_arch = "wasm32")]
         app.add_systems(
             Startup,
@@ -82,9 +83,10 @@ fn main() {
             .with_uuid()
             .with::<Vpeol2dPosition>()
             .with::<FruitType>()
+            .with_marker::<IsFruit>()
     });
     app.add_yoleck_edit_system(duplicate_fruit);
-    app.add_yoleck_edit_system(edit_fruit_type);
+    app.add_yoleck_component_edit_system::<FruitType, _>(edit_fruit_type);
     app.add_systems(YoleckSchedule::Populate, populate_fruit);
     app.add_yoleck_entity_upgrade(1, |type_name, data| {
         if type_name != "Fruit" {
@@ -297,7 +299,7 @@ fn control_player(
 // Fruit
 // ============================================================================
 
-#[derive(Component)]
+#[derive(Component, Default)]
 struct IsFruit;
 
 #[derive(
@@ -409,11 +411,7 @@ fn populate_fruit(
 ) {
     populate.populate(|_ctx, mut cmd, fruit| {
         marking.despawn_marked(&mut cmd);
-        cmd.insert((
-            Visibility::default(),
-            VpeolWillContainClickableChildren,
-            IsFruit,
-        ));
+        cmd.insert((Visibility::default(), VpeolWillContainClickableChildren));
         cmd.with_children(|commands| {
             let mut child = commands.spawn(marking.marker());
             child.insert((Sprite {
@@ -471,8 +469,7 @@ pub struct TextContent {
     Debug,
 )]
 struct TextLaserPointer {
-    #[yoleck(entity_ref = "Fruit")]
-    target: YoleckEntityRef,
+    target: YoleckEntityRefTo<IsFruit>,
 }
 
 fn populate_text(mut populate: YoleckPopulate<&TextContent>, assets: Res<GameAssets>) {
@@ -579,7 +576,7 @@ fn populate_triangle(
 
 fn draw_laser_pointers(
     query: Query<(&TextLaserPointer, &GlobalTransform)>,
-    targets_query: Query<&GlobalTransform>,
+    targets_query: Query<&GlobalTransform, With<IsFruit>>,
     mut gizmos: Gizmos,
 ) {
     for (laser_pointer, source_transform) in query.iter() {
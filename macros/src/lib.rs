@@ -1,6 +1,8 @@
-use proc_macro2::TokenStream;
+use std::collections::HashMap;
 
-use quote::quote;
+use proc_macro2::{Span, TokenStream, TokenTree};
+
+use quote::{format_ident, quote};
 use syn::{Data, DeriveInput, Error, Field, Fields, LitStr, Token, Type};
 
 #[proc_macro_derive(YoleckComponent)]
@@ -36,6 +38,18 @@ struct YoleckFieldAttrs {
     asset_extensions: Option<Vec<String>>,
     entity_filter: Option<String>,
     speed: Option<f64>,
+    min_len: Option<usize>,
+    max_len: Option<usize>,
+    group: Option<String>,
+    visible_if: Option<LitStr>,
+    enabled_if: Option<LitStr>,
+    min: Option<f64>,
+    max: Option<f64>,
+    non_empty: bool,
+    regex: Option<LitStr>,
+    validate: Option<syn::Path>,
+    widget: Option<syn::Path>,
+    registered: bool,
 }
 
 fn parse_number(expr: &syn::Expr) -> syn::Result<f64> {
@@ -82,6 +96,40 @@ fn parse_field_attrs(field: &Field) -> Result<YoleckFieldAttrs, Error> {
                 attrs.color_picker = true;
                 return Ok(());
             }
+            if meta.path.is_ident("non_empty") {
+                attrs.non_empty = true;
+                return Ok(());
+            }
+            if meta.path.is_ident("registered") {
+                attrs.registered = true;
+                return Ok(());
+            }
+            if meta.path.is_ident("min") {
+                let value: syn::Expr = meta.value()?.parse()?;
+                attrs.min = Some(parse_number(&value)?);
+                return Ok(());
+            }
+            if meta.path.is_ident("max") {
+                let value: syn::Expr = meta.value()?.parse()?;
+                attrs.max = Some(parse_number(&value)?);
+                return Ok(());
+            }
+            if meta.path.is_ident("regex") {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                if let Err(err) = regex::Regex::new(&value.value()) {
+                    return Err(Error::new_spanned(value, err));
+                }
+                attrs.regex = Some(value);
+                return Ok(());
+            }
+            if meta.path.is_ident("validate") {
+                attrs.validate = Some(meta.value()?.parse()?);
+                return Ok(());
+            }
+            if meta.path.is_ident("widget") {
+                attrs.widget = Some(meta.value()?.parse()?);
+                return Ok(());
+            }
 
             if meta.path.is_ident("label") {
                 let value: syn::LitStr = meta.value()?.parse()?;
@@ -94,8 +142,35 @@ fn parse_field_attrs(field: &Field) -> Result<YoleckFieldAttrs, Error> {
                 return Ok(());
             }
             if meta.path.is_ident("step") {
-                let value: syn::LitFloat = meta.value()?.parse()?;
-                attrs.step = Some(value.base10_parse()?);
+                let value: syn::Expr = meta.value()?.parse()?;
+                attrs.step = Some(parse_number(&value)?);
+                return Ok(());
+            }
+            if meta.path.is_ident("group") {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                attrs.group = Some(value.value());
+                return Ok(());
+            }
+            if meta.path.is_ident("visible_if") {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                value.parse::<syn::Expr>()?;
+                attrs.visible_if = Some(value);
+                return Ok(());
+            }
+            if meta.path.is_ident("enabled_if") {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                value.parse::<syn::Expr>()?;
+                attrs.enabled_if = Some(value);
+                return Ok(());
+            }
+            if meta.path.is_ident("min_len") {
+                let value: syn::LitInt = meta.value()?.parse()?;
+                attrs.min_len = Some(value.base10_parse()?);
+                return Ok(());
+            }
+            if meta.path.is_ident("max_len") {
+                let value: syn::LitInt = meta.value()?.parse()?;
+                attrs.max_len = Some(value.base10_parse()?);
                 return Ok(());
             }
             if meta.path.is_ident("speed") {
@@ -177,44 +252,552 @@ where
     }
 }
 
-fn generate_field_ui(field: &Field, attrs: &YoleckFieldAttrs) -> TokenStream {
-    let field_name = field.ident.as_ref().unwrap();
-    let field_name_str = attrs
-        .label
-        .clone()
-        .unwrap_or_else(|| field_name.to_string().replace('_', " "));
+/// Bring into scope the traits that `YoleckFieldEditor` resolves through for a field.
+///
+/// Only fields with `#[yoleck(widget = ...)]` or `#[yoleck(registered)]` fall back to the widgets
+/// registered at runtime (and hidden fields, which are never edited). The type of any other field
+/// must implement `YoleckAutoEdit`.
+fn field_editor_traits(attrs: &YoleckFieldAttrs) -> TokenStream {
+    if attrs.hidden || attrs.registered || attrs.widget.is_some() {
+        quote! {
+            use bevy_yoleck::auto_edit::{
+                YoleckFieldViaAutoEdit as _, YoleckFieldViaElementRegistry as _,
+                YoleckFieldViaRegistry as _,
+            };
+        }
+    } else {
+        quote! {
+            use bevy_yoleck::auto_edit::YoleckFieldViaAutoEdit as _;
+        }
+    }
+}
+
+/// Generate the call that edits a field - with its `#[yoleck(widget = ...)]` if it has one, with
+/// its `YoleckAutoEdit` implementation if its type has one, and with the widget registered for its
+/// type if it is marked with `#[yoleck(registered)]`.
+///
+/// `field_place` is an expression of type `&mut FieldType`.
+fn generate_field_edit_call(
+    field_place: TokenStream,
+    field_ty: &syn::Type,
+    label: TokenStream,
+    field_attrs: TokenStream,
+    attrs: &YoleckFieldAttrs,
+) -> TokenStream {
+    if let Some(widget) = &attrs.widget {
+        quote! {
+            bevy_yoleck::auto_edit::edit_with_widget(#field_place, ui, #label, #field_attrs, #widget);
+        }
+    } else {
+        let field_editor_traits = field_editor_traits(attrs);
+        quote! {
+            {
+                #field_editor_traits
+                (&&&bevy_yoleck::auto_edit::YoleckFieldEditor::<#field_ty>(std::marker::PhantomData))
+                    .edit_field(#field_place, ui, #label, #field_attrs);
+            }
+        }
+    }
+}
+
+/// Generate the UI of a single field.
+///
+/// `field_place` is an expression of type `&mut FieldType`. `field_name` is the name of the field
+/// (or its index), which the ids of its widgets are derived from.
+fn generate_field_ui(
+    field_place: TokenStream,
+    field_ty: &syn::Type,
+    field_name: &str,
+    default_label: String,
+    attrs: &YoleckFieldAttrs,
+) -> TokenStream {
+    let field_name_str = attrs.label.clone().unwrap_or(default_label);
+    let field_attrs = generate_field_attrs(attrs);
+
+    // Make the compiler reject attributes that the field's type would ignore, and fields that have
+    // no way to be edited.
+    let mut assertions = Vec::new();
+    if !attrs.registered && attrs.widget.is_none() {
+        assertions.push(quote! {
+            fn assert_auto_edit<T: bevy_yoleck::auto_edit::YoleckAutoEdit>(_: &T) {}
+            assert_auto_edit(#field_place);
+        });
+    }
+    if attrs.step.is_some() {
+        assertions.push(quote! {
+            fn assert_supports_step<T: bevy_yoleck::auto_edit::YoleckSupportsStep + ?Sized>(_: &T) {}
+            assert_supports_step(#field_place);
+        });
+    }
+    if attrs.min_len.is_some() || attrs.max_len.is_some() {
+        assertions.push(quote! {
+            fn assert_supports_length_limits<
+                T: bevy_yoleck::auto_edit::YoleckSupportsLengthLimits + ?Sized,
+            >(_: &T) {}
+            assert_supports_length_limits(#field_place);
+        });
+    }
+    if attrs.color_picker {
+        assertions.push(quote! {
+            fn assert_supports_color_picker<
+                T: bevy_yoleck::auto_edit::YoleckSupportsColorPicker + ?Sized,
+            >(_: &T) {}
+            assert_supports_color_picker(#field_place);
+        });
+    }
+
+    // Errors are shown under the field.
+    let inline_errors = generate_field_checks(quote! { &*(#field_place) }, attrs).map(|checks| {
+        quote! {
+            let mut __yoleck_messages: Vec<String> = Vec::new();
+            #checks
+            for message in __yoleck_messages {
+                ui.colored_label(egui::Color32::RED, message);
+            }
+        }
+    });
+
+    let edit_call = generate_field_edit_call(
+        field_place.clone(),
+        field_ty,
+        quote! { #field_name_str },
+        quote! { &attrs },
+        attrs,
+    );
+
+    quote! {
+        bevy_yoleck::auto_edit::multi_edit_field_scope(
+            ui,
+            #field_name,
+            bevy_yoleck::auto_edit::YoleckPartAddress::of(&*(#field_place)),
+            |ui| {
+            #(#assertions)*
+            let attrs = #field_attrs;
+            #edit_call
+            #inline_errors
+            },
+        );
+    }
+}
+
+/// Generate the checks of a field's validation attributes, which push the error messages into
+/// `__yoleck_messages`. Returns `None` if the field has no validation attributes.
+///
+/// `field_ref` is an expression of type `&FieldType`.
+fn generate_field_checks(field_ref: TokenStream, attrs: &YoleckFieldAttrs) -> Option<TokenStream> {
+    let mut checks = Vec::new();
+    if let Some(min) = attrs.min {
+        checks.push(quote! {
+            if bevy_yoleck::validation::YoleckValidateBounds::validation_value(#field_ref) < #min {
+                __yoleck_messages.push(format!("Must be at least {}", #min));
+            }
+        });
+    }
+    if let Some(max) = attrs.max {
+        checks.push(quote! {
+            if #max < bevy_yoleck::validation::YoleckValidateBounds::validation_value(#field_ref) {
+                __yoleck_messages.push(format!("Must be at most {}", #max));
+            }
+        });
+    }
+    if attrs.non_empty {
+        checks.push(quote! {
+            if bevy_yoleck::validation::YoleckValidateNonEmpty::is_empty_for_validation(#field_ref) {
+                __yoleck_messages.push("Must not be empty".to_owned());
+            }
+        });
+    }
+    if let Some(regex) = &attrs.regex {
+        checks.push(quote! {
+            static REGEX: bevy_yoleck::validation::YoleckLazyRegex =
+                bevy_yoleck::validation::YoleckLazyRegex::new(#regex);
+            if !REGEX.is_match(AsRef::<str>::as_ref(#field_ref)) {
+                __yoleck_messages.push(format!("Must match `{}`", #regex));
+            }
+        });
+    }
+    if let Some(validate) = &attrs.validate {
+        checks.push(quote! {
+            if let Err(message) = #validate(#field_ref) {
+                __yoleck_messages.push(ToString::to_string(&message));
+            }
+        });
+    }
+    if checks.is_empty() {
+        None
+    } else {
+        Some(quote! { #({ #checks })* })
+    }
+}
+
+/// Generate the validation of a single field, for `auto_edit_validate`.
+///
+/// `field_ref` is an expression of type `&FieldType`. The validation of the field's own type only
+/// runs for fields that are not hidden, since hidden fields don't need to implement
+/// `YoleckAutoEdit`.
+fn generate_field_validation(
+    field_ref: TokenStream,
+    field_ty: &syn::Type,
+    field_name: &str,
+    attrs: &YoleckFieldAttrs,
+    places: &HashMap<String, TokenStream>,
+) -> Result<TokenStream, Error> {
+    let checks = generate_field_checks(field_ref.clone(), attrs).map(|checks| {
+        quote! {
+            let mut __yoleck_messages: Vec<String> = Vec::new();
+            #checks
+            errors.extend(__yoleck_messages.into_iter().map(|message| {
+                bevy_yoleck::validation::YoleckValidationError {
+                    field: #field_name.to_owned(),
+                    message,
+                }
+            }));
+        }
+    });
+    let nested = (!attrs.hidden).then(|| {
+        let field_editor_traits = field_editor_traits(attrs);
+        quote! {
+            #field_editor_traits
+            (&&&bevy_yoleck::auto_edit::YoleckFieldEditor::<#field_ty>(std::marker::PhantomData))
+                .validate_field(#field_ref, #field_name, ctx, errors);
+        }
+    });
+    let mut validation = quote! {
+        #checks
+        #nested
+    };
+    // Fields that are not shown cannot be fixed, so they are not validated.
+    if let Some(visible_if) = &attrs.visible_if {
+        let visible_if = rewrite_condition_fields(visible_if.parse()?, places);
+        validation = quote! {
+            let visible: bool = #visible_if;
+            if visible {
+                #validation
+            }
+        };
+    }
+    Ok(quote! {{ #validation }})
+}
+
+/// Generate the visiting of the level paths in a single field, for `auto_edit_level_paths`.
+///
+/// `field_ref` is an expression of type `&mut FieldType`. Unlike the validation, hidden fields are
+/// visited too, since their level paths still refer to the levels.
+fn generate_field_level_paths(
+    field_ref: TokenStream,
+    field_ty: &syn::Type,
+    attrs: &YoleckFieldAttrs,
+) -> TokenStream {
+    let field_editor_traits = field_editor_traits(attrs);
+    quote! {{
+        #field_editor_traits
+        (&&&bevy_yoleck::auto_edit::YoleckFieldEditor::<#field_ty>(std::marker::PhantomData))
+            .field_level_paths(#field_ref, visit);
+    }}
+}
+
+/// Generate the setting of a single field from the serialized data, for `auto_edit_apply_json`.
+/// Returns `None` for fields that cannot be set from the serialized data.
+///
+/// `field_place` is an expression of type `&mut FieldType`. The field is matched with the changed
+/// path by its path in the serialized data, which is found at runtime so that it follows serde's
+/// renaming. Fields without a path of their own (e.g. flattened fields) may still have the changed
+/// path inside them.
+fn generate_field_json_applier(
+    field_place: TokenStream,
+    field: &Field,
+    attrs: &YoleckFieldAttrs,
+) -> Result<Option<TokenStream>, Error> {
+    let serde_attrs = parse_serde_field_attrs(&field.attrs)?;
+    if serde_attrs.skip_deserializing || serde_attrs.custom_deserialization {
+        return Ok(None);
+    }
+    let field_ty = &field.ty;
+    let field_editor_traits = field_editor_traits(attrs);
+    let field_editor = quote! {
+        (&&&bevy_yoleck::auto_edit::YoleckFieldEditor::<#field_ty>(std::marker::PhantomData))
+    };
+    Ok(Some(quote! {{
+        #field_editor_traits
+        match bevy_yoleck::auto_edit::changed_field(&*(#field_place), json, path, paths) {
+            bevy_yoleck::auto_edit::YoleckChangedField::Whole(field_json) => {
+                return #field_editor.deserialize_field(#field_place, field_json);
+            }
+            bevy_yoleck::auto_edit::YoleckChangedField::Inside(field_json) => {
+                return #field_editor.apply_field_json(#field_place, json, path, paths)
+                    || #field_editor.deserialize_field(#field_place, field_json);
+            }
+            bevy_yoleck::auto_edit::YoleckChangedField::Unknown => {
+                if #field_editor.apply_field_json(#field_place, json, path, paths) {
+                    return true;
+                }
+            }
+            bevy_yoleck::auto_edit::YoleckChangedField::Unrelated => {}
+        }
+    }}))
+}
 
+/// Replace the names of fields in a `visible_if`/`enabled_if` condition with the places of these
+/// fields.
+///
+/// Identifiers that are part of a path (`Mode::Patrol`), a member access (`other.field`) or a
+/// struct field (`Patrol { speed: 1.0 }`) are left alone.
+fn rewrite_condition_fields(
+    tokens: TokenStream,
+    places: &HashMap<String, TokenStream>,
+) -> TokenStream {
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+    let is_punct = |token: Option<&TokenTree>, chars: &[char]| matches!(token, Some(TokenTree::Punct(punct)) if chars.contains(&punct.as_char()));
+    let mut result = TokenStream::new();
+    for (index, token) in tokens.iter().enumerate() {
+        match token {
+            TokenTree::Group(group) => {
+                let mut new_group = proc_macro2::Group::new(
+                    group.delimiter(),
+                    rewrite_condition_fields(group.stream(), places),
+                );
+                new_group.set_span(group.span());
+                result.extend([TokenTree::Group(new_group)]);
+            }
+            TokenTree::Ident(ident)
+                if !is_punct(
+                    index.checked_sub(1).and_then(|i| tokens.get(i)),
+                    &['.', ':'],
+                ) && !is_punct(tokens.get(index + 1), &[':']) =>
+            {
+                if let Some(place) = places.get(&ident.to_string()) {
+                    result.extend(place.clone());
+                } else {
+                    result.extend([token.clone()]);
+                }
+            }
+            _ => result.extend([token.clone()]),
+        }
+    }
+    result
+}
+
+/// Wrap a field's UI with the checks of its `visible_if` and `enabled_if` attributes.
+///
+/// `places` maps the names of the sibling fields to expressions of their places.
+fn apply_field_conditions(
+    field_ui: TokenStream,
+    attrs: &YoleckFieldAttrs,
+    places: &HashMap<String, TokenStream>,
+) -> Result<TokenStream, Error> {
+    let condition = |lit: &LitStr| -> Result<TokenStream, Error> {
+        Ok(rewrite_condition_fields(lit.parse()?, places))
+    };
+    let mut field_ui = field_ui;
+    if let Some(enabled_if) = &attrs.enabled_if {
+        let enabled_if = condition(enabled_if)?;
+        field_ui = quote! {
+            let enabled: bool = #enabled_if;
+            ui.add_enabled_ui(enabled, |ui| {
+                #field_ui
+            });
+        };
+    }
+    if let Some(visible_if) = &attrs.visible_if {
+        let visible_if = condition(visible_if)?;
+        field_ui = quote! {
+            let visible: bool = #visible_if;
+            if visible {
+                #field_ui
+            }
+        };
+    }
+    Ok(quote! {{ #field_ui }})
+}
+
+/// Put the UIs of fields with the same `#[yoleck(group = "...")]` under a single collapsing header,
+/// placed where the first field of the group is.
+fn group_field_uis(
+    id_salt: &str,
+    field_uis: Vec<(Option<String>, TokenStream)>,
+) -> Vec<TokenStream> {
+    let mut sections: Vec<(Option<String>, Vec<TokenStream>)> = Vec::new();
+    for (group, field_ui) in field_uis {
+        if let Some(group) = &group
+            && let Some((_, section)) = sections
+                .iter_mut()
+                .find(|(section_group, _)| section_group.as_ref() == Some(group))
+        {
+            section.push(field_ui);
+            continue;
+        }
+        sections.push((group, vec![field_ui]));
+    }
+    sections
+        .into_iter()
+        .map(|(group, field_uis)| match group {
+            None => quote! { #(#field_uis)* },
+            Some(group) => quote! {
+                egui::CollapsingHeader::new(#group)
+                    .id_salt((#id_salt, #group))
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        #(#field_uis)*
+                    });
+            },
+        })
+        .collect()
+}
+
+/// Generate a `FieldAttrs` expression from the field's attributes.
+fn generate_field_attrs(attrs: &YoleckFieldAttrs) -> TokenStream {
     let range = quote_option(&attrs.range, |(min, max)| quote! { (#min, #max) });
     let speed = quote_option(&attrs.speed, |s| quote! { #s });
     let label_opt = quote_option(&attrs.label, |l| quote! { #l.to_string() });
     let tooltip = quote_option(&attrs.tooltip, |t| quote! { #t.to_string() });
     let entity_filter = quote_option(&attrs.entity_filter, |f| quote! { #f.to_string() });
+    let asset_extensions = quote_option(&attrs.asset_extensions, |extensions| {
+        quote! { vec![#(#extensions.to_string()),*] }
+    });
 
+    let step = quote_option(&attrs.step, |s| quote! { #s });
     let readonly = attrs.readonly;
     let multiline = attrs.multiline;
+    let color_picker = attrs.color_picker;
+    let min_len = quote_option(&attrs.min_len, |l| quote! { #l });
+    let max_len = quote_option(&attrs.max_len, |l| quote! { #l });
 
     quote! {
-        {
-            use bevy_yoleck::auto_edit::{YoleckAutoEdit, FieldAttrs};
-            let attrs = FieldAttrs {
-                label: #label_opt,
-                tooltip: #tooltip,
-                range: #range,
-                speed: #speed,
-                readonly: #readonly,
-                multiline: #multiline,
-                entity_filter: #entity_filter,
-            };
-            YoleckAutoEdit::auto_edit_with_label_and_attrs(
-                &mut value.#field_name,
-                ui,
-                #field_name_str,
-                &attrs,
-            );
+        bevy_yoleck::auto_edit::FieldAttrs {
+            label: #label_opt,
+            tooltip: #tooltip,
+            range: #range,
+            step: #step,
+            speed: #speed,
+            readonly: #readonly,
+            multiline: #multiline,
+            entity_filter: #entity_filter,
+            asset_extensions: #asset_extensions,
+            color_picker: #color_picker,
+            min_len: #min_len,
+            max_len: #max_len,
         }
     }
 }
 
+#[derive(Default)]
+struct YoleckContainerAttrs {
+    summary: Option<syn::Path>,
+    /// The methods from `#[yoleck(button = ...)]`, with their labels.
+    buttons: Vec<(syn::Path, String)>,
+}
+
+fn parse_container_attrs(input: &DeriveInput) -> Result<YoleckContainerAttrs, Error> {
+    let mut attrs = YoleckContainerAttrs::default();
+    for attr in &input.attrs {
+        if !attr.path().is_ident("yoleck") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("summary") {
+                attrs.summary = Some(meta.value()?.parse()?);
+                return Ok(());
+            }
+            if meta.path.is_ident("button") {
+                // Either `button = path` or `button(path, label = "...")`
+                let (action, label) = if meta.input.peek(syn::token::Paren) {
+                    let content;
+                    syn::parenthesized!(content in meta.input);
+                    let action: syn::Path = content.parse()?;
+                    let mut label = None;
+                    if content.parse::<Option<syn::Token![,]>>()?.is_some() {
+                        let key: syn::Ident = content.parse()?;
+                        if key != "label" {
+                            return Err(Error::new_spanned(key, "expected `label = \"...\"`"));
+                        }
+                        content.parse::<syn::Token![=]>()?;
+                        label = Some(content.parse::<LitStr>()?.value());
+                    }
+                    (action, label)
+                } else {
+                    (meta.value()?.parse::<syn::Path>()?, None)
+                };
+                let label = label.unwrap_or_else(|| {
+                    let method = action.segments.last().expect("paths are not empty");
+                    method.ident.to_string().replace('_', " ")
+                });
+                attrs.buttons.push((action, label));
+                return Ok(());
+            }
+            Err(meta.error("unknown yoleck attribute for type"))
+        })?;
+    }
+    Ok(attrs)
+}
+
+/// The serde attributes of a field that prevent setting it from its serialized value.
+#[derive(Default)]
+struct SerdeFieldAttrs {
+    /// `skip` or `skip_deserializing` - the field cannot be set from the serialized data.
+    skip_deserializing: bool,
+    /// `with` or `deserialize_with` - the field is not serialized as its own type.
+    custom_deserialization: bool,
+}
+
+fn parse_serde_field_attrs(attrs: &[syn::Attribute]) -> Result<SerdeFieldAttrs, Error> {
+    let mut serde_attrs = SerdeFieldAttrs::default();
+    for attr in attrs {
+        if !attr.path().is_ident("serde") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("skip") || meta.path.is_ident("skip_deserializing") {
+                serde_attrs.skip_deserializing = true;
+            } else if meta.path.is_ident("with") || meta.path.is_ident("deserialize_with") {
+                meta.value()?.parse::<syn::Expr>()?;
+                serde_attrs.custom_deserialization = true;
+            } else if meta.input.peek(Token![=]) {
+                meta.value()?.parse::<syn::Expr>()?;
+            } else if meta.input.peek(syn::token::Paren) {
+                let content;
+                syn::parenthesized!(content in meta.input);
+                content.parse::<TokenStream>()?;
+            }
+            Ok(())
+        })?;
+    }
+    Ok(serde_attrs)
+}
+
+/// Generate an `auto_edit_summary` override that uses the function from `#[yoleck(summary = ...)]`.
+fn generate_summary_impl(container_attrs: &YoleckContainerAttrs) -> Option<TokenStream> {
+    let summary = container_attrs.summary.as_ref()?;
+    Some(quote! {
+        fn auto_edit_summary(value: &Self) -> Option<String> {
+            Some(#summary(value))
+        }
+    })
+}
+
+/// Generate an `auto_edit_buttons` override for the methods from `#[yoleck(button = ...)]`.
+fn generate_buttons_impl(container_attrs: &YoleckContainerAttrs) -> Option<TokenStream> {
+    if container_attrs.buttons.is_empty() {
+        return None;
+    }
+    let buttons = container_attrs.buttons.iter().map(|(action, label)| {
+        quote! {
+            bevy_yoleck::auto_edit::YoleckButton::new(#label, #action)
+        }
+    });
+    Some(quote! {
+        fn auto_edit_buttons() -> Vec<bevy_yoleck::auto_edit::YoleckButton<Self>> {
+            vec![#(#buttons),*]
+        }
+    })
+}
+
+fn field_member(field: &Field, index: usize) -> syn::Member {
+    match &field.ident {
+        Some(ident) => syn::Member::Named(ident.clone()),
+        None => syn::Member::Unnamed(syn::Index::from(index)),
+    }
+}
+
 #[proc_macro_derive(YoleckAutoEdit, attributes(yoleck))]
 pub fn derive_yoleck_auto_edit(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = syn::parse_macro_input!(input as DeriveInput);
@@ -227,51 +810,116 @@ pub fn derive_yoleck_auto_edit(input: proc_macro::TokenStream) -> proc_macro::To
 fn impl_yoleck_auto_edit_derive(input: DeriveInput) -> Result<TokenStream, Error> {
     let name = &input.ident;
     let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let container_attrs = parse_container_attrs(&input)?;
 
-    let fields = if let Data::Struct(data) = &input.data {
-        if let Fields::Named(fields) = &data.fields {
-            &fields.named
-        } else {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        Data::Enum(data) => {
+            return impl_yoleck_auto_edit_derive_for_enum(&input, data, &container_attrs);
+        }
+        Data::Union(_) => {
             return Err(Error::new_spanned(
                 &input,
-                "YoleckAutoEdit only supports structs with named fields",
+                "YoleckAutoEdit only supports structs and enums",
             ));
         }
-    } else {
-        return Err(Error::new_spanned(
-            &input,
-            "YoleckAutoEdit only supports structs",
-        ));
     };
+    let is_newtype = matches!(fields, Fields::Unnamed(fields) if fields.unnamed.len() == 1);
+
+    let field_places: HashMap<String, TokenStream> = fields
+        .iter()
+        .filter_map(|field| {
+            let field_ident = field.ident.as_ref()?;
+            Some((field_ident.to_string(), quote! { (value.#field_ident) }))
+        })
+        .collect();
 
     let mut field_uis = Vec::new();
-    for field in fields {
+    let mut field_validations = Vec::new();
+    let mut field_level_paths = Vec::new();
+    let mut field_json_appliers = Vec::new();
+    // Newtypes are edited as their inner field, so when they are used as a field of another
+    // struct they get that field's label instead of a nested one.
+    let mut newtype_field_impl = None;
+    for (index, field) in fields.iter().enumerate() {
         let attrs = parse_field_attrs(field)?;
+        let member = field_member(field, index);
+        let field_name = match &field.ident {
+            Some(field_ident) => field_ident.to_string(),
+            None if is_newtype => String::new(),
+            None => index.to_string(),
+        };
+        field_validations.push(generate_field_validation(
+            quote! { &value.#member },
+            &field.ty,
+            &field_name,
+            &attrs,
+            &field_places,
+        )?);
+        field_level_paths.push(generate_field_level_paths(
+            quote! { &mut value.#member },
+            &field.ty,
+            &attrs,
+        ));
+        field_json_appliers.extend(generate_field_json_applier(
+            quote! { &mut value.#member },
+            field,
+            &attrs,
+        )?);
         if attrs.hidden {
             continue;
         }
-        field_uis.push(generate_field_ui(field, &attrs));
+        let default_label = match &field.ident {
+            Some(field_ident) => field_ident.to_string().replace('_', " "),
+            None if is_newtype => String::new(),
+            None => index.to_string(),
+        };
+        let field_ui = generate_field_ui(
+            quote! { &mut value.#member },
+            &field.ty,
+            &field_name,
+            default_label,
+            &attrs,
+        );
+        field_uis.push((
+            attrs.group.clone(),
+            apply_field_conditions(field_ui, &attrs, &field_places)?,
+        ));
+        if is_newtype {
+            let field_attrs = generate_field_attrs(&attrs);
+            let edit_call = generate_field_edit_call(
+                quote! { &mut value.#member },
+                &field.ty,
+                quote! { label },
+                quote! { &attrs.merged_with(&#field_attrs) },
+                &attrs,
+            );
+            newtype_field_impl = Some(quote! {
+                fn auto_edit_field_impl(
+                    value: &mut Self,
+                    ui: &mut bevy_yoleck::egui::Ui,
+                    label: &str,
+                    attrs: &bevy_yoleck::auto_edit::FieldAttrs,
+                ) {
+                    #edit_call
+                }
+            });
+        }
     }
 
     let mut entity_ref_fields = Vec::new();
     let mut entity_ref_field_names = Vec::new();
-    for field in fields {
-        if let Some(info) = parse_entity_ref_attrs(field)? {
+    for (index, field) in fields.iter().enumerate() {
+        if let Some(info) = parse_entity_ref_attrs(field, index)? {
+            entity_ref_field_names.push(info.field_ident.clone());
             entity_ref_fields.push(info);
-            entity_ref_field_names.push(
-                field
-                    .ident
-                    .as_ref()
-                    .expect("fields are taken from a named struct variant"),
-            );
         }
     }
 
     let fields_array: Vec<TokenStream> = entity_ref_fields
         .iter()
         .map(|info| {
-            let field_ident = &info.field_ident;
-            let field_ident_str = LitStr::new(&field_ident.to_string(), field_ident.span());
+            let field_ident_str = LitStr::new(&info.field_name, Span::call_site());
             let filter = match &info.filter {
                 Some(f) => quote! { Some(#f) },
                 None => quote! { None },
@@ -285,27 +933,69 @@ fn impl_yoleck_auto_edit_derive(input: DeriveInput) -> Result<TokenStream, Error
         .iter()
         .map(|info| {
             let field_ident = &info.field_ident;
-            let field_ident_str = LitStr::new(&field_ident.to_string(), field_ident.span());
+            let field_ident_str = LitStr::new(&info.field_name, Span::call_site());
 
-            quote! {
-                #field_ident_str => &mut self.#field_ident
+            if info.target.is_some() {
+                quote! {
+                    #field_ident_str => self.#field_ident.as_untyped_mut()
+                }
+            } else {
+                quote! {
+                    #field_ident_str => &mut self.#field_ident
+                }
             }
         })
         .collect();
 
+    let ref_match_arms: Vec<TokenStream> = entity_ref_fields
+        .iter()
+        .map(|info| {
+            let field_ident = &info.field_ident;
+            let field_ident_str = LitStr::new(&info.field_name, Span::call_site());
+
+            if info.target.is_some() {
+                quote! {
+                    #field_ident_str => Some(self.#field_ident.as_untyped())
+                }
+            } else {
+                quote! {
+                    #field_ident_str => Some(&self.#field_ident)
+                }
+            }
+        })
+        .collect();
+
+    let entity_ref_marker_impl = generate_entity_ref_marker_impl(
+        entity_ref_fields
+            .iter()
+            .filter_map(|info| Some((info.field_name.as_str(), info.target.as_ref()?))),
+    );
+
     let fields_count = entity_ref_fields.len();
+    let field_uis = group_field_uis(&name.to_string(), field_uis);
+    let summary_impl = generate_summary_impl(&container_attrs);
+    let buttons_impl = generate_buttons_impl(&container_attrs);
 
-    let get_entity_ref_mut_body = if entity_ref_fields.is_empty() {
-        quote! {
+    let (get_entity_ref_body, get_entity_ref_mut_body) = if entity_ref_fields.is_empty() {
+        let body = quote! {
             panic!("No entity ref fields in {}", stringify!(#name))
-        }
+        };
+        (body.clone(), body)
     } else {
-        quote! {
-            match field_name {
-                #(#match_arms,)*
-                _ => panic!("Unknown entity ref field: {}", field_name),
-            }
-        }
+        (
+            quote! {
+                match field_name {
+                    #(#ref_match_arms,)*
+                    _ => panic!("Unknown entity ref field: {}", field_name),
+                }
+            },
+            quote! {
+                match field_name {
+                    #(#match_arms,)*
+                    _ => panic!("Unknown entity ref field: {}", field_name),
+                }
+            },
+        )
     };
 
     let result = quote! {
@@ -314,6 +1004,40 @@ fn impl_yoleck_auto_edit_derive(input: DeriveInput) -> Result<TokenStream, Error
                 use bevy_yoleck::egui;
                 #(#field_uis)*
             }
+
+            #newtype_field_impl
+
+            #summary_impl
+            #buttons_impl
+
+            fn auto_edit_validate(
+                value: &Self,
+                ctx: &bevy_yoleck::validation::YoleckValidationContext,
+                errors: &mut Vec<bevy_yoleck::validation::YoleckValidationError>,
+            ) {
+                #(#field_validations)*
+            }
+
+            fn auto_edit_level_paths(
+                value: &mut Self,
+                visit: &mut dyn FnMut(&mut bevy_yoleck::prelude::YoleckLevelPath),
+            ) {
+                #(#field_level_paths)*
+            }
+
+            fn auto_edit_apply_json(
+                value: &mut Self,
+                json: &bevy_yoleck::serde_json::Value,
+                path: &[String],
+                paths: &bevy_yoleck::auto_edit::YoleckSerializedPaths,
+            ) -> bool {
+                use bevy_yoleck::auto_edit::{
+                    YoleckFieldNotDeserializable as _, YoleckFieldViaDeserialize as _,
+                };
+                #(#field_json_appliers)*
+                let _ = (value, json, path, paths);
+                false
+            }
         }
 
         impl #impl_generics bevy_yoleck::entity_ref::YoleckEntityRefAccessor for #name #ty_generics #where_clause {
@@ -324,10 +1048,16 @@ fn impl_yoleck_auto_edit_derive(input: DeriveInput) -> Result<TokenStream, Error
                 &FIELDS
             }
 
+            fn get_entity_ref(&self, field_name: &str) -> Option<&bevy_yoleck::entity_ref::YoleckEntityRef> {
+                #get_entity_ref_body
+            }
+
             fn get_entity_ref_mut(&mut self, field_name: &str) -> &mut bevy_yoleck::entity_ref::YoleckEntityRef {
                 #get_entity_ref_mut_body
             }
 
+            #entity_ref_marker_impl
+
             fn resolve_entity_refs(&mut self, registry: &bevy_yoleck::prelude::YoleckUuidRegistry) {
                 #(
                     let _ = self.#entity_ref_field_names.resolve(registry);
@@ -339,28 +1069,440 @@ fn impl_yoleck_auto_edit_derive(input: DeriveInput) -> Result<TokenStream, Error
     Ok(result)
 }
 
+fn impl_yoleck_auto_edit_derive_for_enum(
+    input: &DeriveInput,
+    data: &syn::DataEnum,
+    container_attrs: &YoleckContainerAttrs,
+) -> Result<TokenStream, Error> {
+    let name = &input.ident;
+    let name_str = name.to_string();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let mut variant_names = Vec::new();
+    let mut variant_patterns = Vec::new();
+    let mut variant_defaults = Vec::new();
+    let mut variant_field_uis = Vec::new();
+    let mut variant_validations = Vec::new();
+    let mut variant_level_paths = Vec::new();
+    // The entity reference fields of all the variants, as `(field_name, filter, target)`. Variants
+    // may share the name of a field, as long as it refers to the same entity type or marker.
+    let mut entity_ref_fields: Vec<(String, TokenStream, Option<Type>)> = Vec::new();
+    let mut entity_ref_arms = Vec::new();
+    let mut entity_ref_mut_arms = Vec::new();
+    let mut entity_ref_resolves = Vec::new();
+
+    for variant in data.variants.iter() {
+        let variant_ident = &variant.ident;
+        variant_names
+            .push(parse_variant_label(variant)?.unwrap_or_else(|| variant_ident.to_string()));
+
+        let bindings: Vec<_> = variant
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(index, field)| match &field.ident {
+                Some(field_ident) => format_ident!("__yoleck_field_{}", field_ident),
+                None => format_ident!("__yoleck_field_{}", index),
+            })
+            .collect();
+        let field_places: HashMap<String, TokenStream> = variant
+            .fields
+            .iter()
+            .zip(&bindings)
+            .filter_map(|(field, binding)| {
+                Some((field.ident.as_ref()?.to_string(), quote! { (*#binding) }))
+            })
+            .collect();
+
+        let mut field_uis = Vec::new();
+        let mut field_validations = Vec::new();
+        let mut field_level_paths = Vec::new();
+        for ((index, field), binding) in variant.fields.iter().enumerate().zip(&bindings) {
+            if let Some(info) = parse_entity_ref_attrs(field, index)? {
+                let filter = match &info.filter {
+                    Some(f) => quote! { Some(#f) },
+                    None => quote! { None },
+                };
+                let typed = info.target.is_some();
+                let target_str = info
+                    .target
+                    .as_ref()
+                    .map(|target| quote! { #target }.to_string());
+                match entity_ref_fields
+                    .iter()
+                    .find(|(field_name, _, _)| *field_name == info.field_name)
+                {
+                    Some((_, other_filter, other_target)) => {
+                        let other_target_str = other_target
+                            .as_ref()
+                            .map(|target| quote! { #target }.to_string());
+                        if other_filter.to_string() != filter.to_string()
+                            || other_target_str != target_str
+                        {
+                            return Err(Error::new_spanned(
+                                field,
+                                "entity reference fields with the same name in different variants \
+                                 must refer to the same entity type or marker",
+                            ));
+                        }
+                    }
+                    None => entity_ref_fields.push((
+                        info.field_name.clone(),
+                        filter,
+                        info.target.clone(),
+                    )),
+                }
+                let member = &info.field_ident;
+                let field_name_str = LitStr::new(&info.field_name, Span::call_site());
+                let pattern = quote! {
+                    Self::#variant_ident { #member: __yoleck_entity_ref, .. }
+                };
+                let (as_untyped, as_untyped_mut) = if typed {
+                    (
+                        quote! { __yoleck_entity_ref.as_untyped() },
+                        quote! { __yoleck_entity_ref.as_untyped_mut() },
+                    )
+                } else {
+                    (
+                        quote! { __yoleck_entity_ref },
+                        quote! { __yoleck_entity_ref },
+                    )
+                };
+                entity_ref_arms.push(quote! {
+                    (#field_name_str, #pattern) => Some(#as_untyped)
+                });
+                entity_ref_mut_arms.push(quote! {
+                    (#field_name_str, #pattern) => Some(#as_untyped_mut)
+                });
+                entity_ref_resolves.push(quote! {
+                    if let #pattern = self {
+                        let _ = __yoleck_entity_ref.resolve(registry);
+                    }
+                });
+            }
+            let attrs = parse_field_attrs(field)?;
+            let field_name = match &field.ident {
+                Some(field_ident) => field_ident.to_string(),
+                None => index.to_string(),
+            };
+            field_validations.push(generate_field_validation(
+                quote! { #binding },
+                &field.ty,
+                &field_name,
+                &attrs,
+                &field_places,
+            )?);
+            field_level_paths.push(generate_field_level_paths(
+                quote! { #binding },
+                &field.ty,
+                &attrs,
+            ));
+            if attrs.hidden {
+                continue;
+            }
+            let default_label = match &field.ident {
+                Some(field_ident) => field_ident.to_string().replace('_', " "),
+                None => index.to_string(),
+            };
+            let field_ui = generate_field_ui(
+                quote! { &mut *#binding },
+                &field.ty,
+                &field_name,
+                default_label,
+                &attrs,
+            );
+            field_uis.push((
+                attrs.group.clone(),
+                apply_field_conditions(field_ui, &attrs, &field_places)?,
+            ));
+        }
+        let field_uis = group_field_uis(&format!("{name}::{variant_ident}"), field_uis);
+
+        let (pattern, binding_pattern, default) = match &variant.fields {
+            Fields::Named(fields) => {
+                let field_idents: Vec<_> = fields
+                    .named
+                    .iter()
+                    .map(|field| field.ident.as_ref().expect("named fields have idents"))
+                    .collect();
+                (
+                    quote! { Self::#variant_ident { .. } },
+                    quote! { Self::#variant_ident { #(#field_idents: #bindings),* } },
+                    quote! {
+                        Self::#variant_ident { #(#field_idents: Default::default()),* }
+                    },
+                )
+            }
+            Fields::Unnamed(fields) => {
+                let defaults = fields.unnamed.iter().map(|_| quote! { Default::default() });
+                (
+                    quote! { Self::#variant_ident(..) },
+                    quote! { Self::#variant_ident(#(#bindings),*) },
+                    quote! { Self::#variant_ident(#(#defaults),*) },
+                )
+            }
+            Fields::Unit => (
+                quote! { Self::#variant_ident },
+                quote! { Self::#variant_ident },
+                quote! { Self::#variant_ident },
+            ),
+        };
+        variant_patterns.push(pattern);
+        variant_defaults.push(default);
+        variant_validations.push(quote! {
+            #binding_pattern => {
+                #(#field_validations)*
+            }
+        });
+        variant_level_paths.push(quote! {
+            #binding_pattern => {
+                #(#field_level_paths)*
+            }
+        });
+        variant_field_uis.push(if field_uis.is_empty() {
+            quote! { #binding_pattern => {} }
+        } else {
+            quote! {
+                #binding_pattern => {
+                    ui.indent(label, |ui| {
+                        #(#field_uis)*
+                    });
+                }
+            }
+        });
+    }
+
+    let entity_ref_accessor_impl = if entity_ref_fields.is_empty() {
+        quote! {
+            impl #impl_generics bevy_yoleck::entity_ref::YoleckEntityRefAccessor for #name #ty_generics #where_clause {
+                fn entity_ref_fields() -> &'static [(&'static str, Option<&'static str>)] {
+                    &[]
+                }
+
+                fn get_entity_ref(&self, _field_name: &str) -> Option<&bevy_yoleck::entity_ref::YoleckEntityRef> {
+                    None
+                }
+
+                fn get_entity_ref_mut(&mut self, field_name: &str) -> &mut bevy_yoleck::entity_ref::YoleckEntityRef {
+                    panic!("No entity ref fields in {}", stringify!(#name))
+                }
+
+                fn resolve_entity_refs(&mut self, _registry: &bevy_yoleck::prelude::YoleckUuidRegistry) {}
+            }
+        }
+    } else {
+        let fields_count = entity_ref_fields.len();
+        let fields_array = entity_ref_fields.iter().map(|(field_name, filter, _)| {
+            let field_name_str = LitStr::new(field_name, Span::call_site());
+            quote! { (#field_name_str, #filter) }
+        });
+        let entity_ref_marker_impl =
+            generate_entity_ref_marker_impl(entity_ref_fields.iter().filter_map(
+                |(field_name, _, target)| Some((field_name.as_str(), target.as_ref()?)),
+            ));
+        quote! {
+            impl #impl_generics bevy_yoleck::entity_ref::YoleckEntityRefAccessor for #name #ty_generics #where_clause {
+                fn entity_ref_fields() -> &'static [(&'static str, Option<&'static str>)] {
+                    static FIELDS: [(&'static str, Option<&'static str>); #fields_count] = [
+                        #(#fields_array),*
+                    ];
+                    &FIELDS
+                }
+
+                fn get_entity_ref(&self, field_name: &str) -> Option<&bevy_yoleck::entity_ref::YoleckEntityRef> {
+                    match (field_name, self) {
+                        #(#entity_ref_arms,)*
+                        _ => None,
+                    }
+                }
+
+                fn try_get_entity_ref_mut(&mut self, field_name: &str) -> Option<&mut bevy_yoleck::entity_ref::YoleckEntityRef> {
+                    match (field_name, self) {
+                        #(#entity_ref_mut_arms,)*
+                        _ => None,
+                    }
+                }
+
+                fn get_entity_ref_mut(&mut self, field_name: &str) -> &mut bevy_yoleck::entity_ref::YoleckEntityRef {
+                    self.try_get_entity_ref_mut(field_name).unwrap_or_else(|| {
+                        panic!(
+                            "The current variant of {} has no entity ref field {}",
+                            stringify!(#name),
+                            field_name
+                        )
+                    })
+                }
+
+                #entity_ref_marker_impl
+
+                fn resolve_entity_refs(&mut self, registry: &bevy_yoleck::prelude::YoleckUuidRegistry) {
+                    #(#entity_ref_resolves)*
+                }
+            }
+        }
+    };
+
+    let buttons_impl = generate_buttons_impl(container_attrs);
+    let summary_impl = generate_summary_impl(container_attrs).unwrap_or_else(|| {
+        quote! {
+            fn auto_edit_summary(value: &Self) -> Option<String> {
+                Some(match value {
+                    #(#variant_patterns => #variant_names,)*
+                }.to_owned())
+            }
+        }
+    });
+
+    let result = quote! {
+        impl #impl_generics bevy_yoleck::auto_edit::YoleckAutoEdit for #name #ty_generics #where_clause {
+            fn auto_edit(value: &mut Self, ui: &mut bevy_yoleck::egui::Ui) {
+                Self::auto_edit_field_impl(value, ui, #name_str, &Default::default());
+            }
+
+            #summary_impl
+            #buttons_impl
+
+            fn auto_edit_validate(
+                value: &Self,
+                ctx: &bevy_yoleck::validation::YoleckValidationContext,
+                errors: &mut Vec<bevy_yoleck::validation::YoleckValidationError>,
+            ) {
+                match value {
+                    #(#variant_validations)*
+                }
+            }
+
+            fn auto_edit_level_paths(
+                value: &mut Self,
+                visit: &mut dyn FnMut(&mut bevy_yoleck::prelude::YoleckLevelPath),
+            ) {
+                match value {
+                    #(#variant_level_paths)*
+                }
+            }
+
+            fn auto_edit_field_impl(
+                value: &mut Self,
+                ui: &mut bevy_yoleck::egui::Ui,
+                label: &str,
+                attrs: &bevy_yoleck::auto_edit::FieldAttrs,
+            ) {
+                use bevy_yoleck::egui;
+                ui.horizontal(|ui| {
+                    ui.label(label);
+                    let selected_text = match value {
+                        #(#variant_patterns => #variant_names,)*
+                    };
+                    let response = egui::ComboBox::from_id_salt(("yoleck_enum_variant", label))
+                        .selected_text(selected_text)
+                        .show_ui(ui, |ui| {
+                            #(
+                                let is_selected = matches!(value, #variant_patterns);
+                                if ui.selectable_label(is_selected, #variant_names).clicked()
+                                    && !is_selected
+                                {
+                                    *value = #variant_defaults;
+                                }
+                            )*
+                        })
+                        .response;
+                    if let Some(tooltip) = &attrs.tooltip {
+                        response.on_hover_text(tooltip);
+                    }
+                });
+                match value {
+                    #(#variant_field_uis)*
+                }
+            }
+        }
+
+        #entity_ref_accessor_impl
+    };
+
+    Ok(result)
+}
+
+fn parse_variant_label(variant: &syn::Variant) -> Result<Option<String>, Error> {
+    let mut label = None;
+    for attr in &variant.attrs {
+        if !attr.path().is_ident("yoleck") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("label") {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                label = Some(value.value());
+                return Ok(());
+            }
+            Err(meta.error("unknown yoleck attribute for enum variant"))
+        })?;
+    }
+    Ok(label)
+}
+
+/// Generate `YoleckEntityRefAccessor::entity_ref_marker` for the `YoleckEntityRefTo` fields, given
+/// by their names and marker types.
+fn generate_entity_ref_marker_impl<'a>(
+    typed_fields: impl Iterator<Item = (&'a str, &'a Type)>,
+) -> Option<TokenStream> {
+    let arms: Vec<TokenStream> = typed_fields
+        .map(|(field_name, target)| {
+            let field_name = LitStr::new(field_name, Span::call_site());
+            quote! {
+                #field_name => Some((
+                    std::any::TypeId::of::<#target>(),
+                    std::any::type_name::<#target>(),
+                ))
+            }
+        })
+        .collect();
+    if arms.is_empty() {
+        return None;
+    }
+    Some(quote! {
+        fn entity_ref_marker(field_name: &str) -> Option<(std::any::TypeId, &'static str)> {
+            match field_name {
+                #(#arms,)*
+                _ => None,
+            }
+        }
+    })
+}
+
 #[derive(Debug)]
 struct EntityRefFieldInfo {
-    field_ident: syn::Ident,
+    field_ident: syn::Member,
+    field_name: String,
     filter: Option<String>,
+    /// The marker type of `YoleckEntityRefTo` fields.
+    target: Option<Type>,
 }
 
-fn parse_entity_ref_attrs(field: &Field) -> Result<Option<EntityRefFieldInfo>, Error> {
+fn parse_entity_ref_attrs(
+    field: &Field,
+    index: usize,
+) -> Result<Option<EntityRefFieldInfo>, Error> {
     let type_name = get_type_name(&field.ty);
 
-    if type_name != "YoleckEntityRef" {
-        return Ok(None);
-    }
+    let target = match type_name.as_str() {
+        "YoleckEntityRef" => None,
+        "YoleckEntityRefTo" => Some(get_single_generic_argument(&field.ty).ok_or_else(|| {
+            Error::new_spanned(&field.ty, "YoleckEntityRefTo must have a marker type")
+        })?),
+        _ => return Ok(None),
+    };
 
-    let field_ident = field
-        .ident
-        .as_ref()
-        .ok_or_else(|| Error::new_spanned(field, "Expected named field"))?
-        .clone();
+    let field_ident = field_member(field, index);
+    let field_name = match &field_ident {
+        syn::Member::Named(ident) => ident.to_string(),
+        syn::Member::Unnamed(index) => index.index.to_string(),
+    };
 
     let mut info = EntityRefFieldInfo {
         field_ident,
+        field_name,
         filter: None,
+        target,
     };
 
     for attr in &field.attrs {
@@ -372,6 +1514,12 @@ fn parse_entity_ref_attrs(field: &Field) -> Result<Option<EntityRefFieldInfo>, E
             if meta.path.is_ident("entity_ref") {
                 if meta.input.peek(Token![=]) {
                     let value: syn::LitStr = meta.value()?.parse()?;
+                    if info.target.is_some() {
+                        return Err(Error::new_spanned(
+                            value,
+                            "YoleckEntityRefTo is restricted by its marker type",
+                        ));
+                    }
                     info.filter = Some(value.value());
                 }
                 return Ok(());
@@ -382,3 +1530,17 @@ fn parse_entity_ref_attrs(field: &Field) -> Result<Option<EntityRefFieldInfo>, E
 
     Ok(Some(info))
 }
+
+fn get_single_generic_argument(ty: &Type) -> Option<Type> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let syn::PathArguments::AngleBracketed(arguments) = &type_path.path.segments.last()?.arguments
+    else {
+        return None;
+    };
+    match arguments.args.first()? {
+        syn::GenericArgument::Type(ty) if arguments.args.len() == 1 => Some(ty.clone()),
+        _ => None,
+    }
+}
@@ -0,0 +1,138 @@
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// The path for the Bevy assets directory, used for listing the files in asset path pickers.
+///
+/// [The plugin](crate::YoleckPluginForEditor) sets it to `./assets/`, but it can be set to other
+/// values:
+/// ```no_run
+/// # use std::path::Path;
+/// # use bevy::prelude::*;
+/// # use bevy_yoleck::YoleckEditorAssetsDirectoryPath;
+/// # let mut app = App::new();
+/// app.insert_resource(YoleckEditorAssetsDirectoryPath(
+///     Path::new(".").join("some").join("other").join("path"),
+/// ));
+/// ```
+#[derive(Resource)]
+pub struct YoleckEditorAssetsDirectoryPath(pub PathBuf);
+
+/// A path to an asset, saved in the level file as a string.
+///
+/// Unlike a plain `String` with `#[yoleck(asset = "...")]`, this type knows the type of the asset
+/// so it can load it:
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_yoleck::prelude::*;
+/// # use serde::{Deserialize, Serialize};
+/// # #[derive(Component)]
+/// # struct DecorationImage(Handle<Image>);
+/// #[derive(Component, YoleckComponent, YoleckAutoEdit, Serialize, Deserialize, Clone, PartialEq, Default)]
+/// struct Decoration {
+///     #[yoleck(asset = "png,jpg")]
+///     image: YoleckAssetPath<Image>,
+/// }
+///
+/// fn populate_decoration(
+///     mut populate: YoleckPopulate<&Decoration>,
+///     asset_server: Res<AssetServer>,
+/// ) {
+///     populate.populate(|_ctx, mut cmd, decoration| {
+///         if let Some(image) = decoration.image.load(&asset_server) {
+///             cmd.insert(DecorationImage(image));
+///         }
+///     });
+/// }
+/// ```
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct YoleckAssetPath<T: Asset> {
+    pub path: String,
+    #[serde(skip)]
+    _phantom_data: PhantomData<fn() -> T>,
+}
+
+impl<T: Asset> YoleckAssetPath<T> {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            _phantom_data: PhantomData,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.path.is_empty()
+    }
+
+    /// Load the asset, or `None` if the path is empty.
+    ///
+    /// The asset server only loads each path once, so this can be called every time the entity is
+    /// populated.
+    pub fn load(&self, asset_server: &AssetServer) -> Option<Handle<T>> {
+        if self.path.is_empty() {
+            None
+        } else {
+            Some(asset_server.load(&self.path))
+        }
+    }
+}
+
+// Implemented manually because the derives would require `T` to implement these traits too.
+
+impl<T: Asset> Default for YoleckAssetPath<T> {
+    fn default() -> Self {
+        Self::new(String::new())
+    }
+}
+
+impl<T: Asset> Clone for YoleckAssetPath<T> {
+    fn clone(&self) -> Self {
+        Self::new(self.path.clone())
+    }
+}
+
+impl<T: Asset> PartialEq for YoleckAssetPath<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.path == other.path
+    }
+}
+
+impl<T: Asset> Eq for YoleckAssetPath<T> {}
+
+impl<T: Asset> std::fmt::Debug for YoleckAssetPath<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("YoleckAssetPath").field(&self.path).finish()
+    }
+}
+
+/// List all the files under the assets directory, as asset paths (relative to the assets
+/// directory and separated by `/`)
+pub(crate) fn list_asset_files(assets_directory: &Path) -> io::Result<Vec<String>> {
+    fn visit(directory: &Path, prefix: &str, files: &mut Vec<String>) -> io::Result<()> {
+        for entry in fs::read_dir(directory)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let asset_path = if prefix.is_empty() {
+                name
+            } else {
+                format!("{prefix}/{name}")
+            };
+            if entry.file_type()?.is_dir() {
+                visit(&entry.path(), &asset_path, files)?;
+            } else {
+                files.push(asset_path);
+            }
+        }
+        Ok(())
+    }
+
+    let mut files = Vec::new();
+    visit(assets_directory, "", &mut files)?;
+    files.sort();
+    Ok(files)
+}
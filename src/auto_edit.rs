@@ -2,30 +2,146 @@ use bevy::prelude::*;
 use bevy_egui::egui;
 
 use crate::YoleckInternalSchedule;
+use crate::editor::component_edit_ui;
 use crate::entity_ref::resolve_entity_refs;
 
-use crate::entity_ref::validate_entity_ref_requirements_for;
-
-use crate::entity_ref::YoleckEntityRef;
+use crate::entity_ref::{YoleckEntityRef, YoleckEntityRefHandlers, YoleckEntityRefTo};
 use crate::prelude::YoleckUuidRegistry;
 
-use std::collections::HashMap;
+use std::any::{Any, TypeId};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::hash::{BuildHasher, Hash};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::asset_path::{YoleckAssetPath, YoleckEditorAssetsDirectoryPath, list_asset_files};
+use crate::level_path::{YoleckLevelPath, YoleckLevelPathHandlers, validate_level_path};
+use crate::validation::{
+    YoleckValidationContext, YoleckValidationError, YoleckValidationHandlers, validate_nested,
+};
 
 /// Attributes that can be applied to fields for customizing their UI
+///
+/// With the `bevy_reflect` feature, this is also the custom reflect attribute that customizes
+/// fields edited by [`reflect_edit`](crate::reflect_edit).
 #[derive(Default, Clone)]
+#[cfg_attr(feature = "bevy_reflect", derive(Reflect))]
 pub struct FieldAttrs {
     pub label: Option<String>,
     pub tooltip: Option<String>,
     pub range: Option<(f64, f64)>,
+    /// Quantize numeric values to multiples of this step.
+    pub step: Option<f64>,
     pub speed: Option<f64>,
     pub readonly: bool,
     pub multiline: bool,
     pub entity_filter: Option<String>,
+    /// Show an asset picker that lists the files with these extensions.
+    pub asset_extensions: Option<Vec<String>>,
+    /// Edit the value as a color.
+    pub color_picker: bool,
+    /// The minimal number of elements in a list.
+    pub min_len: Option<usize>,
+    /// The maximal number of elements in a list.
+    pub max_len: Option<usize>,
+}
+
+impl FieldAttrs {
+    /// Use the attributes of `self`, falling back to the ones in `defaults` for the attributes
+    /// that are not set in `self`.
+    pub fn merged_with(&self, defaults: &FieldAttrs) -> FieldAttrs {
+        FieldAttrs {
+            label: self.label.clone().or_else(|| defaults.label.clone()),
+            tooltip: self.tooltip.clone().or_else(|| defaults.tooltip.clone()),
+            range: self.range.or(defaults.range),
+            step: self.step.or(defaults.step),
+            speed: self.speed.or(defaults.speed),
+            readonly: self.readonly || defaults.readonly,
+            multiline: self.multiline || defaults.multiline,
+            entity_filter: self
+                .entity_filter
+                .clone()
+                .or_else(|| defaults.entity_filter.clone()),
+            asset_extensions: self
+                .asset_extensions
+                .clone()
+                .or_else(|| defaults.asset_extensions.clone()),
+            color_picker: self.color_picker || defaults.color_picker,
+            min_len: self.min_len.or(defaults.min_len),
+            max_len: self.max_len.or(defaults.max_len),
+        }
+    }
 }
 
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` does not implement `YoleckAutoEdit`",
+    note = "edit the field with `#[yoleck(widget = ...)]`, or with the widget registered for its type with `#[yoleck(registered)]`"
+)]
 pub trait YoleckAutoEdit: Send + Sync + 'static {
     fn auto_edit(value: &mut Self, ui: &mut egui::Ui);
 
+    /// A short description of the value, shown in the header of list elements (which can then be
+    /// collapsed)
+    ///
+    /// `#[derive(YoleckAutoEdit)]` can set it with `#[yoleck(summary = function_name)]` on the
+    /// type, where the function takes `&Self` and returns a `String`. Enums default to the label
+    /// of the variant.
+    fn auto_edit_summary(_value: &Self) -> Option<String> {
+        None
+    }
+
+    /// Check the value, adding the problems found to `errors`.
+    ///
+    /// `#[derive(YoleckAutoEdit)]` generates it from the validation attributes of the fields (see
+    /// the [`validation`](crate::validation) module), and runs the validation of the fields' own
+    /// types.
+    fn auto_edit_validate(
+        _value: &Self,
+        _ctx: &YoleckValidationContext,
+        _errors: &mut Vec<YoleckValidationError>,
+    ) {
+    }
+
+    /// Call `visit` with every [`YoleckLevelPath`] in the value, so that the references to a level
+    /// can be updated when the level is renamed.
+    ///
+    /// `#[derive(YoleckAutoEdit)]` generates it from the fields of the type.
+    fn auto_edit_level_paths(_value: &mut Self, _visit: &mut dyn FnMut(&mut YoleckLevelPath)) {}
+
+    /// Set the part of the value at `path` (a list of serialized field names and element indices)
+    /// to the one in `json`, which is the serialized form of the value after that part was
+    /// changed. The rest of the value - including fields that are not serialized - is left as is.
+    /// `paths` has the paths of the parts of the value (see [`YoleckSerializedPaths`]), which are
+    /// relative to the same root as `json` and `path`.
+    ///
+    /// Returns `false` if the value cannot set that part on its own, in which case the whole value
+    /// needs to be deserialized from `json`. This is used when editing several entities at once,
+    /// for writing the fields the user changed onto the other entities.
+    ///
+    /// `#[derive(YoleckAutoEdit)]` generates it for structs.
+    fn auto_edit_apply_json(
+        _value: &mut Self,
+        _json: &serde_json::Value,
+        _path: &[String],
+        _paths: &YoleckSerializedPaths,
+    ) -> bool {
+        false
+    }
+
+    /// Buttons shown under the component when it is edited with
+    /// [`add_yoleck_auto_edit`](YoleckAutoEditExt::add_yoleck_auto_edit).
+    ///
+    /// `#[derive(YoleckAutoEdit)]` generates them from `#[yoleck(button = method)]` (or
+    /// `#[yoleck(button(method, label = "..."))]`) on the type. See [`YoleckButton`].
+    fn auto_edit_buttons() -> Vec<YoleckButton<Self>>
+    where
+        Self: Sized,
+    {
+        Vec::new()
+    }
+
     /// Auto-edit with field-level attributes (label, tooltip, range, etc.)
     /// Default implementation wraps auto_edit with label and common decorations
     fn auto_edit_with_label_and_attrs(
@@ -74,10 +190,16 @@ impl YoleckAutoEdit for f32 {
         ui.horizontal(|ui| {
             ui.label(label);
             let response = if let Some((min, max)) = attrs.range {
-                ui.add(egui::Slider::new(value, min as f32..=max as f32))
+                step_slider(ui, value, min as f32..=max as f32, attrs.step)
             } else {
                 let speed = attrs.speed.unwrap_or(0.1) as f32;
-                ui.add(egui::DragValue::new(value).speed(speed))
+                let response = ui.add(egui::DragValue::new(value).speed(speed));
+                if let Some(step) = attrs.step
+                    && response.changed()
+                {
+                    *value = quantize(*value as f64, step) as f32;
+                }
+                response
             };
 
             if let Some(tooltip) = &attrs.tooltip {
@@ -96,10 +218,16 @@ impl YoleckAutoEdit for f64 {
         ui.horizontal(|ui| {
             ui.label(label);
             let response = if let Some((min, max)) = attrs.range {
-                ui.add(egui::Slider::new(value, min..=max))
+                step_slider(ui, value, min..=max, attrs.step)
             } else {
                 let speed = attrs.speed.unwrap_or(0.1);
-                ui.add(egui::DragValue::new(value).speed(speed))
+                let response = ui.add(egui::DragValue::new(value).speed(speed));
+                if let Some(step) = attrs.step
+                    && response.changed()
+                {
+                    *value = quantize(*value, step);
+                }
+                response
             };
 
             if let Some(tooltip) = &attrs.tooltip {
@@ -118,13 +246,22 @@ macro_rules! impl_auto_edit_for_integer {
                 }
 
                 fn auto_edit_field_impl(value: &mut Self, ui: &mut egui::Ui, label: &str, attrs: &FieldAttrs) {
+                    if attrs.color_picker && color_picker_field(value, ui, label, attrs) {
+                        return;
+                    }
                     ui.horizontal(|ui| {
                         ui.label(label);
                         let response = if let Some((min, max)) = attrs.range {
-                            ui.add(egui::Slider::new(value, min as $ty..=max as $ty))
+                            step_slider(ui, value, min as $ty..=max as $ty, attrs.step)
                         } else {
                             let speed = attrs.speed.unwrap_or(1.0) as f32;
-                            ui.add(egui::DragValue::new(value).speed(speed))
+                            let response = ui.add(egui::DragValue::new(value).speed(speed));
+                            if let Some(step) = attrs.step
+                                && response.changed()
+                            {
+                                *value = quantize(*value as f64, step) as $ty;
+                            }
+                            response
                         };
 
                         if let Some(tooltip) = &attrs.tooltip {
@@ -137,7 +274,152 @@ macro_rules! impl_auto_edit_for_integer {
     };
 }
 
-impl_auto_edit_for_integer!(i32, i64, u32, u64, usize, isize);
+impl_auto_edit_for_integer!(u8, i8, u16, i16, i32, i64, u32, u64, usize, isize);
+
+/// Types that honor `#[yoleck(step = ...)]`.
+#[diagnostic::on_unimplemented(message = "`#[yoleck(step = ...)]` is not supported for `{Self}`")]
+pub trait YoleckSupportsStep {}
+
+impl YoleckSupportsStep for f32 {}
+impl YoleckSupportsStep for f64 {}
+impl YoleckSupportsStep for u8 {}
+impl YoleckSupportsStep for i8 {}
+impl YoleckSupportsStep for u16 {}
+impl YoleckSupportsStep for i16 {}
+impl YoleckSupportsStep for i32 {}
+impl YoleckSupportsStep for i64 {}
+impl YoleckSupportsStep for u32 {}
+impl YoleckSupportsStep for u64 {}
+impl YoleckSupportsStep for usize {}
+impl YoleckSupportsStep for isize {}
+impl YoleckSupportsStep for Vec2 {}
+impl YoleckSupportsStep for Vec3 {}
+impl YoleckSupportsStep for Vec4 {}
+impl YoleckSupportsStep for IVec2 {}
+impl YoleckSupportsStep for IVec3 {}
+impl YoleckSupportsStep for IVec4 {}
+impl YoleckSupportsStep for UVec2 {}
+impl YoleckSupportsStep for UVec3 {}
+impl YoleckSupportsStep for UVec4 {}
+impl YoleckSupportsStep for Duration {}
+
+/// Types that honor `#[yoleck(min_len = ...)]` and `#[yoleck(max_len = ...)]`.
+#[diagnostic::on_unimplemented(
+    message = "`#[yoleck(min_len = ...)]` and `#[yoleck(max_len = ...)]` are not supported for `{Self}`"
+)]
+pub trait YoleckSupportsLengthLimits {}
+
+impl<T> YoleckSupportsLengthLimits for Vec<T> {}
+
+/// Types that honor `#[yoleck(color_picker)]`.
+#[diagnostic::on_unimplemented(
+    message = "`#[yoleck(color_picker)]` is not supported for `{Self}`",
+    note = "`color_picker` supports `[f32; 3]`, `[f32; 4]`, `[u8; 3]`, `[u8; 4]` and `u32` (as `0xRRGGBB`)"
+)]
+pub trait YoleckSupportsColorPicker {}
+
+impl YoleckSupportsColorPicker for [f32; 3] {}
+impl YoleckSupportsColorPicker for [f32; 4] {}
+impl YoleckSupportsColorPicker for [u8; 3] {}
+impl YoleckSupportsColorPicker for [u8; 4] {}
+impl YoleckSupportsColorPicker for u32 {}
+
+fn quantize(value: f64, step: f64) -> f64 {
+    if step <= 0.0 {
+        return value;
+    }
+    (value / step).round() * step
+}
+
+fn step_slider<N: egui::emath::Numeric>(
+    ui: &mut egui::Ui,
+    value: &mut N,
+    range: std::ops::RangeInclusive<N>,
+    step: Option<f64>,
+) -> egui::Response {
+    let slider = egui::Slider::new(value, range);
+    if let Some(step) = step {
+        // With the default clamping the slider snaps the value to the step on every frame, even
+        // when it is not being edited.
+        ui.add(slider.step_by(step).clamping(egui::SliderClamping::Edits))
+    } else {
+        ui.add(slider)
+    }
+}
+
+fn drag_component<N: egui::emath::Numeric>(
+    ui: &mut egui::Ui,
+    value: &mut N,
+    prefix: &str,
+    speed: f64,
+    step: Option<f64>,
+) -> egui::Response {
+    let response = ui.add(egui::DragValue::new(value).prefix(prefix).speed(speed));
+    if let Some(step) = step
+        && response.changed()
+    {
+        *value = N::from_f64(quantize(value.to_f64(), step));
+    }
+    response
+}
+
+/// Edit the types supported by [`YoleckSupportsColorPicker`] with a color editor. Returns `false`
+/// if the type of `value` is not supported.
+fn color_picker_field(
+    value: &mut dyn Any,
+    ui: &mut egui::Ui,
+    label: &str,
+    attrs: &FieldAttrs,
+) -> bool {
+    // The color buttons convert the color back and forth even when it is not being edited, which
+    // may change it slightly, so they edit a copy that only gets written back when changed.
+    fn edit_copy<T: Copy>(
+        value: &mut T,
+        edit: impl FnOnce(&mut T) -> egui::Response,
+    ) -> egui::Response {
+        let mut new_value = *value;
+        let response = edit(&mut new_value);
+        if response.changed() {
+            *value = new_value;
+        }
+        response
+    }
+
+    let response = ui.horizontal(|ui| {
+        ui.label(label);
+        if let Some(rgb) = value.downcast_mut::<[f32; 3]>() {
+            Some(edit_copy(rgb, |rgb| ui.color_edit_button_rgb(rgb)))
+        } else if let Some(rgba) = value.downcast_mut::<[f32; 4]>() {
+            Some(edit_copy(rgba, |rgba| {
+                ui.color_edit_button_rgba_unmultiplied(rgba)
+            }))
+        } else if let Some(rgb) = value.downcast_mut::<[u8; 3]>() {
+            Some(edit_copy(rgb, |rgb| ui.color_edit_button_srgb(rgb)))
+        } else if let Some(rgba) = value.downcast_mut::<[u8; 4]>() {
+            Some(edit_copy(rgba, |rgba| {
+                ui.color_edit_button_srgba_unmultiplied(rgba)
+            }))
+        } else if let Some(hex) = value.downcast_mut::<u32>() {
+            let [_, r, g, b] = hex.to_be_bytes();
+            let mut rgb = [r, g, b];
+            let response = ui.color_edit_button_srgb(&mut rgb);
+            if response.changed() {
+                *hex = (*hex & 0xff000000) | u32::from_be_bytes([0, rgb[0], rgb[1], rgb[2]]);
+            }
+            ui.label(format!("#{:06x}", *hex & 0xffffff));
+            Some(response)
+        } else {
+            None
+        }
+    });
+    let Some(response) = response.inner else {
+        return false;
+    };
+    if let Some(tooltip) = &attrs.tooltip {
+        response.on_hover_text(tooltip);
+    }
+    true
+}
 
 impl YoleckAutoEdit for bool {
     fn auto_edit(value: &mut Self, ui: &mut egui::Ui) {
@@ -161,7 +443,9 @@ impl YoleckAutoEdit for String {
     }
 
     fn auto_edit_field_impl(value: &mut Self, ui: &mut egui::Ui, label: &str, attrs: &FieldAttrs) {
-        if attrs.multiline {
+        if let Some(extensions) = &attrs.asset_extensions {
+            asset_path_field(value, ui, label, attrs.tooltip.as_deref(), extensions);
+        } else if attrs.multiline {
             ui.label(label);
             let response = ui.text_edit_multiline(value);
 
@@ -181,6 +465,202 @@ impl YoleckAutoEdit for String {
     }
 }
 
+impl YoleckAutoEdit for PathBuf {
+    fn auto_edit(value: &mut Self, ui: &mut egui::Ui) {
+        let mut text = value.to_string_lossy().into_owned();
+        if ui.text_edit_singleline(&mut text).changed() {
+            *value = text.into();
+        }
+    }
+
+    fn auto_edit_field_impl(value: &mut Self, ui: &mut egui::Ui, label: &str, attrs: &FieldAttrs) {
+        let mut text = value.to_string_lossy().into_owned();
+        String::auto_edit_field_impl(&mut text, ui, label, attrs);
+        if *value.to_string_lossy() != text {
+            *value = text.into();
+        }
+    }
+}
+
+impl<T: Asset> YoleckAutoEdit for YoleckAssetPath<T> {
+    fn auto_edit(value: &mut Self, ui: &mut egui::Ui) {
+        asset_path_field(&mut value.path, ui, "", None, &[]);
+    }
+
+    fn auto_edit_field_impl(value: &mut Self, ui: &mut egui::Ui, label: &str, attrs: &FieldAttrs) {
+        asset_path_field(
+            &mut value.path,
+            ui,
+            label,
+            attrs.tooltip.as_deref(),
+            attrs.asset_extensions.as_deref().unwrap_or_default(),
+        );
+    }
+}
+
+/// A text box for an asset path, with a button for picking it from the files in the assets
+/// directory that have one of the `extensions` (or all the files, if `extensions` is empty)
+fn asset_path_field(
+    value: &mut String,
+    ui: &mut egui::Ui,
+    label: &str,
+    tooltip: Option<&str>,
+    extensions: &[String],
+) {
+    let files_id = egui::Id::new("yoleck_asset_files");
+    let assets_directory = ui
+        .ctx()
+        .data(|data| data.get_temp::<Arc<PathBuf>>(egui::Id::new("yoleck_assets_directory")));
+    let load_files = |ui: &egui::Ui| {
+        let Some(assets_directory) = assets_directory.as_ref() else {
+            return;
+        };
+        let files = match list_asset_files(assets_directory) {
+            Ok(files) => files,
+            Err(err) => {
+                warn!("Cannot list asset files in {assets_directory:?}: {err}");
+                Vec::new()
+            }
+        };
+        ui.ctx()
+            .data_mut(|data| data.insert_temp(files_id, Arc::new(files)));
+    };
+    if ui
+        .ctx()
+        .data(|data| data.get_temp::<Arc<Vec<String>>>(files_id))
+        .is_none()
+    {
+        load_files(ui);
+    }
+    let files = ui
+        .ctx()
+        .data(|data| data.get_temp::<Arc<Vec<String>>>(files_id))
+        .unwrap_or_default();
+    let has_extension = |path: &str| {
+        extensions.is_empty()
+            || Path::new(path).extension().is_some_and(|extension| {
+                extensions
+                    .iter()
+                    .any(|wanted| extension.eq_ignore_ascii_case(wanted.trim_start_matches('.')))
+            })
+    };
+
+    ui.horizontal(|ui| {
+        ui.label(label);
+        let response = ui.text_edit_singleline(value);
+        if let Some(tooltip) = tooltip {
+            response.on_hover_text(tooltip);
+        }
+
+        let button_response = ui.button("📂").on_hover_text("Pick an asset");
+        egui::Popup::menu(&button_response)
+            .close_behavior(egui::PopupCloseBehavior::CloseOnClickOutside)
+            .show(|ui| {
+                let search_id = button_response.id.with("search");
+                let mut search = ui
+                    .ctx()
+                    .data_mut(|data| data.get_temp_mut_or_default::<String>(search_id).clone());
+                ui.horizontal(|ui| {
+                    ui.add(egui::TextEdit::singleline(&mut search).hint_text("Search"));
+                    if ui
+                        .button("⟳")
+                        .on_hover_text("Reload the file list")
+                        .clicked()
+                    {
+                        load_files(ui);
+                    }
+                });
+                let search_lowercase = search.to_lowercase();
+                ui.ctx()
+                    .data_mut(|data| data.insert_temp(search_id, search));
+                egui::ScrollArea::vertical()
+                    .max_height(300.0)
+                    .show(ui, |ui| {
+                        for file in files.iter() {
+                            if !has_extension(file)
+                                || !file.to_lowercase().contains(&search_lowercase)
+                            {
+                                continue;
+                            }
+                            if ui.selectable_label(file == value, file).clicked() {
+                                *value = file.clone();
+                                ui.close();
+                            }
+                        }
+                    });
+            });
+
+        if !value.is_empty() && assets_directory.is_some() && !files.contains(value) {
+            ui.colored_label(egui::Color32::YELLOW, "⚠")
+                .on_hover_text("This file does not exist in the assets directory");
+        } else if !value.is_empty() && !has_extension(value) {
+            ui.colored_label(egui::Color32::YELLOW, "⚠")
+                .on_hover_text(format!("Expected one of: {}", extensions.join(", ")));
+        }
+    });
+}
+
+impl YoleckAutoEdit for YoleckLevelPath {
+    fn auto_edit(value: &mut Self, ui: &mut egui::Ui) {
+        level_path_field(value, ui, "", None);
+    }
+
+    fn auto_edit_field_impl(value: &mut Self, ui: &mut egui::Ui, label: &str, attrs: &FieldAttrs) {
+        level_path_field(value, ui, label, attrs.tooltip.as_deref());
+    }
+
+    fn auto_edit_validate(
+        value: &Self,
+        ctx: &YoleckValidationContext,
+        errors: &mut Vec<YoleckValidationError>,
+    ) {
+        validate_level_path(value, ctx, errors);
+    }
+
+    fn auto_edit_level_paths(value: &mut Self, visit: &mut dyn FnMut(&mut YoleckLevelPath)) {
+        visit(value);
+    }
+}
+
+/// A combo box of the level files in the levels directory.
+fn level_path_field(
+    value: &mut YoleckLevelPath,
+    ui: &mut egui::Ui,
+    label: &str,
+    tooltip: Option<&str>,
+) {
+    let levels = ui
+        .ctx()
+        .data(|data| data.get_temp::<Arc<Vec<String>>>(egui::Id::new("yoleck_editable_levels")));
+    ui.horizontal(|ui| {
+        ui.label(label);
+        let response = egui::ComboBox::from_id_salt(ui.id().with(("yoleck_level_path", label)))
+            .selected_text(if value.is_empty() {
+                "<none>"
+            } else {
+                value.filename.as_str()
+            })
+            .show_ui(ui, |ui| {
+                ui.selectable_value(value, YoleckLevelPath::default(), "<none>");
+                for level in levels.iter().flat_map(|levels| levels.iter()) {
+                    ui.selectable_value(value, YoleckLevelPath::new(level.clone()), level);
+                }
+            })
+            .response;
+        if let Some(tooltip) = tooltip {
+            response.on_hover_text(tooltip);
+        }
+
+        if let Some(levels) = &levels
+            && !value.is_empty()
+            && !levels.contains(&value.filename)
+        {
+            ui.colored_label(egui::Color32::YELLOW, "⚠")
+                .on_hover_text("This file does not exist in the levels directory");
+        }
+    });
+}
+
 impl YoleckAutoEdit for Vec2 {
     fn auto_edit(value: &mut Self, ui: &mut egui::Ui) {
         ui.horizontal(|ui| {
@@ -190,20 +670,12 @@ impl YoleckAutoEdit for Vec2 {
     }
 
     fn auto_edit_field_impl(value: &mut Self, ui: &mut egui::Ui, label: &str, attrs: &FieldAttrs) {
-        let speed = attrs.speed.unwrap_or(0.1) as f32;
+        let speed = attrs.speed.unwrap_or(0.1);
         let response = ui
             .horizontal(|ui| {
                 ui.label(label);
-                ui.add(
-                    egui::DragValue::new(&mut value.x)
-                        .prefix("x: ")
-                        .speed(speed),
-                );
-                ui.add(
-                    egui::DragValue::new(&mut value.y)
-                        .prefix("y: ")
-                        .speed(speed),
-                );
+                drag_component(ui, &mut value.x, "x: ", speed, attrs.step);
+                drag_component(ui, &mut value.y, "y: ", speed, attrs.step);
             })
             .response;
 
@@ -223,25 +695,13 @@ impl YoleckAutoEdit for Vec3 {
     }
 
     fn auto_edit_field_impl(value: &mut Self, ui: &mut egui::Ui, label: &str, attrs: &FieldAttrs) {
-        let speed = attrs.speed.unwrap_or(0.1) as f32;
+        let speed = attrs.speed.unwrap_or(0.1);
         let response = ui
             .horizontal(|ui| {
                 ui.label(label);
-                ui.add(
-                    egui::DragValue::new(&mut value.x)
-                        .prefix("x: ")
-                        .speed(speed),
-                );
-                ui.add(
-                    egui::DragValue::new(&mut value.y)
-                        .prefix("y: ")
-                        .speed(speed),
-                );
-                ui.add(
-                    egui::DragValue::new(&mut value.z)
-                        .prefix("z: ")
-                        .speed(speed),
-                );
+                drag_component(ui, &mut value.x, "x: ", speed, attrs.step);
+                drag_component(ui, &mut value.y, "y: ", speed, attrs.step);
+                drag_component(ui, &mut value.z, "z: ", speed, attrs.step);
             })
             .response;
 
@@ -262,30 +722,14 @@ impl YoleckAutoEdit for Vec4 {
     }
 
     fn auto_edit_field_impl(value: &mut Self, ui: &mut egui::Ui, label: &str, attrs: &FieldAttrs) {
-        let speed = attrs.speed.unwrap_or(0.1) as f32;
+        let speed = attrs.speed.unwrap_or(0.1);
         let response = ui
             .horizontal(|ui| {
                 ui.label(label);
-                ui.add(
-                    egui::DragValue::new(&mut value.x)
-                        .prefix("x: ")
-                        .speed(speed),
-                );
-                ui.add(
-                    egui::DragValue::new(&mut value.y)
-                        .prefix("y: ")
-                        .speed(speed),
-                );
-                ui.add(
-                    egui::DragValue::new(&mut value.z)
-                        .prefix("z: ")
-                        .speed(speed),
-                );
-                ui.add(
-                    egui::DragValue::new(&mut value.w)
-                        .prefix("w: ")
-                        .speed(speed),
-                );
+                drag_component(ui, &mut value.x, "x: ", speed, attrs.step);
+                drag_component(ui, &mut value.y, "y: ", speed, attrs.step);
+                drag_component(ui, &mut value.z, "z: ", speed, attrs.step);
+                drag_component(ui, &mut value.w, "w: ", speed, attrs.step);
             })
             .response;
 
@@ -426,214 +870,2650 @@ impl YoleckAutoEdit for Color {
     }
 }
 
-impl<T: YoleckAutoEdit + Default> YoleckAutoEdit for Option<T> {
-    fn auto_edit(value: &mut Self, ui: &mut egui::Ui) {
-        ui.horizontal(|ui| {
-            let mut has_value = value.is_some();
-            if ui.checkbox(&mut has_value, "").changed() {
-                if has_value {
-                    *value = Some(T::default());
-                } else {
-                    *value = None;
+macro_rules! impl_auto_edit_for_integer_vector {
+    ($($ty:ty { $($component:ident),* }),* $(,)?) => {
+        $(
+            impl YoleckAutoEdit for $ty {
+                fn auto_edit(value: &mut Self, ui: &mut egui::Ui) {
+                    ui.horizontal(|ui| {
+                        $(
+                            drag_component(ui, &mut value.$component, concat!(stringify!($component), ": "), 1.0, None);
+                        )*
+                    });
                 }
-            }
-            if let Some(inner) = value.as_mut() {
-                T::auto_edit(inner, ui);
-            }
-        });
-    }
 
-    fn auto_edit_field_impl(value: &mut Self, ui: &mut egui::Ui, label: &str, attrs: &FieldAttrs) {
-        let response = ui
-            .horizontal(|ui| {
-                ui.label(label);
-                let mut has_value = value.is_some();
-                if ui.checkbox(&mut has_value, "").changed() {
-                    if has_value {
-                        *value = Some(T::default());
-                    } else {
-                        *value = None;
+                fn auto_edit_field_impl(value: &mut Self, ui: &mut egui::Ui, label: &str, attrs: &FieldAttrs) {
+                    let speed = attrs.speed.unwrap_or(1.0);
+                    let response = ui
+                        .horizontal(|ui| {
+                            ui.label(label);
+                            $(
+                                drag_component(ui, &mut value.$component, concat!(stringify!($component), ": "), speed, attrs.step);
+                            )*
+                        })
+                        .response;
+
+                    if let Some(tooltip) = &attrs.tooltip {
+                        response.on_hover_text(tooltip);
                     }
                 }
-                if let Some(inner) = value.as_mut() {
-                    T::auto_edit(inner, ui);
-                }
-            })
-            .response;
+            }
+        )*
+    };
+}
 
-        if let Some(tooltip) = &attrs.tooltip {
-            response.on_hover_text(tooltip);
-        }
+impl_auto_edit_for_integer_vector!(
+    IVec2 { x, y },
+    IVec3 { x, y, z },
+    IVec4 { x, y, z, w },
+    UVec2 { x, y },
+    UVec3 { x, y, z },
+    UVec4 { x, y, z, w },
+);
+
+/// Show the UI of a composite value inside a collapsing header.
+pub(crate) fn collapsing_field(
+    ui: &mut egui::Ui,
+    label: &str,
+    attrs: &FieldAttrs,
+    add_contents: impl FnOnce(&mut egui::Ui),
+) {
+    let response = ui.collapsing(label, add_contents);
+
+    if let Some(tooltip) = &attrs.tooltip {
+        response.header_response.on_hover_text(tooltip);
     }
 }
 
-impl<T: YoleckAutoEdit + Default> YoleckAutoEdit for Vec<T> {
-    fn auto_edit(value: &mut Self, ui: &mut egui::Ui) {
-        let mut to_remove = None;
-        for (idx, item) in value.iter_mut().enumerate() {
-            ui.horizontal(|ui| {
-                ui.label(format!("[{}]", idx));
-                T::auto_edit(item, ui);
-                if ui.small_button("−").clicked() {
-                    to_remove = Some(idx);
+/// Implement `YoleckAutoEdit` for structs by editing each of the listed fields with its own
+/// `YoleckAutoEdit` implementation.
+macro_rules! impl_auto_edit_for_struct_fields {
+    ($($ty:ty { $($field:ident),* }),* $(,)?) => {
+        $(
+            impl YoleckAutoEdit for $ty {
+                fn auto_edit(value: &mut Self, ui: &mut egui::Ui) {
+                    ui.vertical(|ui| {
+                        $(
+                            YoleckAutoEdit::auto_edit_field_impl(
+                                &mut value.$field,
+                                ui,
+                                stringify!($field),
+                                &FieldAttrs::default(),
+                            );
+                        )*
+                    });
                 }
-            });
-        }
-        if let Some(idx) = to_remove {
-            value.remove(idx);
-        }
-        if ui.small_button("+").clicked() {
-            value.push(T::default());
-        }
+
+                fn auto_edit_field_impl(value: &mut Self, ui: &mut egui::Ui, label: &str, attrs: &FieldAttrs) {
+                    collapsing_field(ui, label, attrs, |ui| Self::auto_edit(value, ui));
+                }
+            }
+        )*
+    };
+}
+
+impl_auto_edit_for_struct_fields!(
+    Rect { min, max },
+    IRect { min, max },
+    URect { min, max },
+    Transform {
+        translation,
+        rotation,
+        scale
+    },
+    Circle { radius },
+    Ellipse { half_size },
+    Annulus {
+        inner_circle,
+        outer_circle
+    },
+    Rhombus { half_diagonals },
+    Rectangle { half_size },
+    RegularPolygon {
+        circumcircle,
+        sides
+    },
+    Capsule2d {
+        radius,
+        half_length
+    },
+    Segment2d { vertices },
+    Triangle2d { vertices },
+    Sphere { radius },
+    Cuboid { half_size },
+    Cylinder {
+        radius,
+        half_height
+    },
+    Capsule3d {
+        radius,
+        half_length
+    },
+    Cone { radius, height },
+    ConicalFrustum {
+        radius_top,
+        radius_bottom,
+        height
+    },
+    Torus {
+        minor_radius,
+        major_radius
+    },
+    Segment3d { vertices },
+    Triangle3d { vertices },
+    Tetrahedron { vertices },
+);
+
+impl YoleckAutoEdit for Duration {
+    fn auto_edit(value: &mut Self, ui: &mut egui::Ui) {
+        Self::auto_edit_field_impl(value, ui, "", &FieldAttrs::default());
     }
 
     fn auto_edit_field_impl(value: &mut Self, ui: &mut egui::Ui, label: &str, attrs: &FieldAttrs) {
-        let response = ui.collapsing(label, |ui| {
-            let mut to_remove = None;
-            for (idx, item) in value.iter_mut().enumerate() {
-                ui.horizontal(|ui| {
-                    ui.label(format!("[{}]", idx));
-                    T::auto_edit(item, ui);
-                    if ui.small_button("−").clicked() {
-                        to_remove = Some(idx);
-                    }
-                });
-            }
-            if let Some(idx) = to_remove {
-                value.remove(idx);
+        ui.horizontal(|ui| {
+            ui.label(label);
+            let mut secs = value.as_secs_f64();
+            let response = ui.add(
+                egui::DragValue::new(&mut secs)
+                    .speed(attrs.speed.unwrap_or(0.01))
+                    .range(0.0..=f64::MAX)
+                    .suffix("s"),
+            );
+            if response.changed() {
+                if let Some(step) = attrs.step {
+                    secs = quantize(secs, step);
+                }
+                *value = Duration::from_secs_f64(secs.max(0.0));
             }
-            if ui.small_button("+").clicked() {
-                value.push(T::default());
+
+            if let Some(tooltip) = &attrs.tooltip {
+                response.on_hover_text(tooltip);
             }
         });
-
-        if let Some(tooltip) = &attrs.tooltip {
-            response.header_response.on_hover_text(tooltip);
-        }
     }
 }
 
-impl<T: YoleckAutoEdit> YoleckAutoEdit for [T] {
-    fn auto_edit(value: &mut Self, ui: &mut egui::Ui) {
-        for (idx, item) in value.iter_mut().enumerate() {
-            ui.horizontal(|ui| {
-                ui.label(format!("[{}]", idx));
-                T::auto_edit(item, ui);
-            });
+/// A text box for values that are edited as text and parsed. Text that fails to parse is kept in
+/// the egui memory (with a warning next to it) instead of being written to the value.
+fn parsed_text_field<T: FromStr + ToString>(
+    ui: &mut egui::Ui,
+    value: &mut T,
+    label: &str,
+) -> egui::Response {
+    let text_id = ui.id().with(("yoleck_parsed_text", label));
+    let mut text = ui
+        .data(|data| data.get_temp::<String>(text_id))
+        .unwrap_or_else(|| value.to_string());
+    let response = ui.text_edit_singleline(&mut text);
+    match text.parse() {
+        Ok(parsed) => {
+            if response.changed() {
+                *value = parsed;
+            }
+            ui.data_mut(|data| data.remove::<String>(text_id));
+        }
+        Err(_) => {
+            ui.colored_label(egui::Color32::RED, "⚠")
+                .on_hover_text("Invalid value");
+            ui.data_mut(|data| data.insert_temp(text_id, text));
         }
     }
+    response
 }
 
-#[derive(Clone)]
-struct EntityRefDisplayInfo {
-    pub type_name: String,
-    pub name: String,
-}
-
-impl YoleckAutoEdit for YoleckEntityRef {
+impl YoleckAutoEdit for char {
     fn auto_edit(value: &mut Self, ui: &mut egui::Ui) {
-        ui.horizontal(|ui| {
-            if let Some(uuid) = value.uuid() {
-                ui.label(uuid.to_string());
-                if ui.small_button("✕").clicked() {
-                    value.clear();
-                }
-            } else {
-                ui.label("None");
-            }
-        });
+        parsed_text_field(ui, value, "");
     }
 
     fn auto_edit_field_impl(value: &mut Self, ui: &mut egui::Ui, label: &str, attrs: &FieldAttrs) {
-        // Get entity info map once for both display and drag&drop validation
-        let entity_info_map = ui.ctx().data(|data| {
-            data.get_temp::<HashMap<uuid::Uuid, EntityRefDisplayInfo>>(egui::Id::new(
-                "yoleck_entity_ref_display_info",
-            ))
-        });
+        ui.horizontal(|ui| {
+            ui.label(label);
+            let response = parsed_text_field(ui, value, label);
 
-        let response = ui
-            .horizontal(|ui| {
-                ui.label(label);
+            if let Some(tooltip) = &attrs.tooltip {
+                response.on_hover_text(tooltip);
+            }
+        });
+    }
+}
 
-                let display_text = if let Some(uuid) = value.uuid() {
-                    if let Some(ref info_map) = entity_info_map {
-                        if let Some(info) = info_map.get(&uuid) {
-                            if info.name.is_empty() {
-                                let uuid_str = uuid.to_string();
-                                let uuid_short = &uuid_str[..uuid_str.len().min(8)];
-                                format!("{} ({})", info.type_name, uuid_short)
-                            } else {
-                                format!("{} - {}", info.type_name, info.name)
-                            }
-                        } else {
-                            uuid.to_string()
-                        }
-                    } else {
-                        uuid.to_string()
-                    }
-                } else {
-                    "None".to_string()
-                };
+impl YoleckAutoEdit for uuid::Uuid {
+    fn auto_edit(value: &mut Self, ui: &mut egui::Ui) {
+        Self::auto_edit_field_impl(value, ui, "", &FieldAttrs::default());
+    }
 
-                ui.add(
-                    egui::Button::new(
-                        egui::RichText::new(display_text)
-                            .text_style(ui.style().drag_value_text_style.clone()),
-                    )
-                    .wrap_mode(egui::TextWrapMode::Extend)
-                    .min_size(ui.spacing().interact_size),
-                );
+    fn auto_edit_field_impl(value: &mut Self, ui: &mut egui::Ui, label: &str, attrs: &FieldAttrs) {
+        ui.horizontal(|ui| {
+            ui.label(label);
+            let response = parsed_text_field(ui, value, label);
+            if ui
+                .small_button("🎲")
+                .on_hover_text("Generate a new UUID")
+                .clicked()
+            {
+                *value = uuid::Uuid::new_v4();
+            }
 
-                if value.is_some() && ui.small_button("✕").clicked() {
-                    value.clear();
-                }
+            if let Some(tooltip) = &attrs.tooltip {
+                response.on_hover_text(tooltip);
+            }
+        });
+    }
+}
 
-                if let Some(tooltip) = &attrs.tooltip {
-                    ui.label("ⓘ").on_hover_text(tooltip);
-                }
-            })
-            .response;
+/// Maps and sets, whose keys cannot be edited in place.
+trait KeyedCollection {
+    type Key: YoleckAutoEdit + Default + Clone + PartialEq + Hash;
 
-        // Handle drag & drop
-        if let Some(dropped_uuid) = response.dnd_release_payload::<uuid::Uuid>() {
-            let dropped_uuid = *dropped_uuid;
+    fn rename_key(&mut self, old_key: &Self::Key, new_key: Self::Key);
+    fn remove_key(&mut self, key: &Self::Key);
+    fn insert_default(&mut self);
+}
 
-            let should_accept = if let Some(filter) = &attrs.entity_filter {
-                entity_info_map
-                    .as_ref()
-                    .and_then(|map| map.get(&dropped_uuid))
-                    .is_none_or(|info| &info.type_name == filter)
-            } else {
-                true
-            };
+/// Changes made by [`keyed_entries_ui`], to apply after the UI is done borrowing the values.
+struct KeyedEntriesEdits<K> {
+    renamed: Option<(K, K)>,
+    removed: Option<K>,
+    added: bool,
+}
 
-            if should_accept {
-                value.set(dropped_uuid);
-            }
+impl<K> KeyedEntriesEdits<K> {
+    fn apply(self, collection: &mut impl KeyedCollection<Key = K>) {
+        if let Some((old_key, new_key)) = self.renamed {
+            collection.rename_key(&old_key, new_key);
+        }
+        if let Some(key) = self.removed {
+            collection.remove_key(&key);
+        }
+        if self.added {
+            collection.insert_default();
         }
     }
 }
 
+/// A key that is being edited, before it is applied to the collection.
+#[derive(Clone)]
+struct PendingKey<K> {
+    key: K,
+    /// The widget that had the keyboard focus when the editing started. `None` for keys edited
+    /// with the mouse (e.g. by dragging a number).
+    focus: Option<egui::Id>,
+}
+
+/// The UI for the entries of a map or a set. `keys` are in the iteration order of the collection,
+/// and `value_ui` shows the value of the entry at the given index (for maps).
+fn keyed_entries_ui<K: YoleckAutoEdit + Default + Clone + PartialEq + Hash>(
+    ui: &mut egui::Ui,
+    keys: &[K],
+    mut value_ui: impl FnMut(&mut egui::Ui, usize),
+) -> KeyedEntriesEdits<K> {
+    // Renaming the key moves the entry to another place in the collection, so the new key is kept
+    // here (by the row of the original key) until the editing is done, and only then renamed.
+    let pending_keys_id = ui.id().with("yoleck_pending_keys");
+    let mut pending_keys = ui
+        .data(|data| data.get_temp::<HashMap<egui::Id, PendingKey<K>>>(pending_keys_id))
+        .unwrap_or_default();
+    let mut still_pending_keys = HashMap::new();
+
+    let mut edits = KeyedEntriesEdits {
+        renamed: None,
+        removed: None,
+        added: false,
+    };
+    for (idx, key) in keys.iter().enumerate() {
+        let row_id = ui.id().with(("yoleck_key", egui::epaint::util::hash(key)));
+        ui.push_id(row_id, |ui| {
+            ui.horizontal(|ui| {
+                let pending_key = pending_keys.remove(&row_id);
+                let mut new_key = pending_key
+                    .as_ref()
+                    .map_or_else(|| key.clone(), |pending_key| pending_key.key.clone());
+                K::auto_edit(&mut new_key, ui);
+                if new_key != *key {
+                    // Renaming a key to one that already exists would merge the two entries.
+                    let is_duplicate = keys.contains(&new_key);
+                    if is_duplicate {
+                        ui.colored_label(egui::Color32::RED, "⚠")
+                            .on_hover_text("Duplicate key");
+                    }
+                    let focus = match pending_key {
+                        Some(pending_key) => pending_key.focus,
+                        None => ui.memory(|memory| memory.focused()),
+                    };
+                    let editing_done = ui.input(|input| input.key_pressed(egui::Key::Enter))
+                        || match focus {
+                            Some(focus) => !ui.memory(|memory| memory.has_focus(focus)),
+                            None => !ui.input(|input| input.pointer.any_down()),
+                        };
+                    if !editing_done {
+                        still_pending_keys.insert(
+                            row_id,
+                            PendingKey {
+                                key: new_key,
+                                focus,
+                            },
+                        );
+                    } else if !is_duplicate {
+                        edits.renamed = Some((key.clone(), new_key));
+                    }
+                }
+                value_ui(ui, idx);
+                if ui.small_button("−").clicked() {
+                    edits.removed = Some(key.clone());
+                }
+            });
+        });
+    }
+    if ui
+        .add_enabled(
+            !keys.contains(&K::default()),
+            egui::Button::new("+").small(),
+        )
+        .on_disabled_hover_text("An entry with the default key already exists")
+        .clicked()
+    {
+        edits.added = true;
+    }
+
+    ui.data_mut(|data| {
+        if still_pending_keys.is_empty() {
+            data.remove::<HashMap<egui::Id, PendingKey<K>>>(pending_keys_id);
+        } else {
+            data.insert_temp(pending_keys_id, still_pending_keys);
+        }
+    });
+
+    edits
+}
+
+impl<K, V, S> KeyedCollection for HashMap<K, V, S>
+where
+    K: YoleckAutoEdit + Default + Clone + Eq + Hash,
+    V: Default,
+    S: BuildHasher,
+{
+    type Key = K;
+
+    fn rename_key(&mut self, old_key: &K, new_key: K) {
+        if let Some(value) = self.remove(old_key) {
+            self.insert(new_key, value);
+        }
+    }
+
+    fn remove_key(&mut self, key: &K) {
+        self.remove(key);
+    }
+
+    fn insert_default(&mut self) {
+        self.insert(K::default(), V::default());
+    }
+}
+
+impl<K, V> KeyedCollection for BTreeMap<K, V>
+where
+    K: YoleckAutoEdit + Default + Clone + Ord + Hash,
+    V: Default,
+{
+    type Key = K;
+
+    fn rename_key(&mut self, old_key: &K, new_key: K) {
+        if let Some(value) = self.remove(old_key) {
+            self.insert(new_key, value);
+        }
+    }
+
+    fn remove_key(&mut self, key: &K) {
+        self.remove(key);
+    }
+
+    fn insert_default(&mut self) {
+        self.insert(K::default(), V::default());
+    }
+}
+
+/// Maps, whose values are edited next to their keys.
+trait KeyedMap: KeyedCollection {
+    type Value;
+
+    fn entries_mut(&mut self) -> (Vec<Self::Key>, Vec<&mut Self::Value>);
+}
+
+impl<K, V, S> KeyedMap for HashMap<K, V, S>
+where
+    K: YoleckAutoEdit + Default + Clone + Eq + Hash,
+    V: Default,
+    S: BuildHasher,
+{
+    type Value = V;
+
+    fn entries_mut(&mut self) -> (Vec<K>, Vec<&mut V>) {
+        self.iter_mut()
+            .map(|(key, value)| (key.clone(), value))
+            .unzip()
+    }
+}
+
+impl<K, V> KeyedMap for BTreeMap<K, V>
+where
+    K: YoleckAutoEdit + Default + Clone + Ord + Hash,
+    V: Default,
+{
+    type Value = V;
+
+    fn entries_mut(&mut self) -> (Vec<K>, Vec<&mut V>) {
+        self.iter_mut()
+            .map(|(key, value)| (key.clone(), value))
+            .unzip()
+    }
+}
+
+fn map_ui<M: KeyedMap>(
+    value: &mut M,
+    ui: &mut egui::Ui,
+    mut edit_value: impl FnMut(&mut M::Value, &mut egui::Ui),
+) {
+    let (keys, mut values) = value.entries_mut();
+    let edits = keyed_entries_ui(ui, &keys, |ui, idx| edit_value(values[idx], ui));
+    edits.apply(value);
+}
+
+impl<K, S> KeyedCollection for HashSet<K, S>
+where
+    K: YoleckAutoEdit + Default + Clone + Eq + Hash,
+    S: BuildHasher,
+{
+    type Key = K;
+
+    fn rename_key(&mut self, old_key: &K, new_key: K) {
+        if self.remove(old_key) {
+            self.insert(new_key);
+        }
+    }
+
+    fn remove_key(&mut self, key: &K) {
+        self.remove(key);
+    }
+
+    fn insert_default(&mut self) {
+        self.insert(K::default());
+    }
+}
+
+impl<K> KeyedCollection for BTreeSet<K>
+where
+    K: YoleckAutoEdit + Default + Clone + Ord + Hash,
+{
+    type Key = K;
+
+    fn rename_key(&mut self, old_key: &K, new_key: K) {
+        if self.remove(old_key) {
+            self.insert(new_key);
+        }
+    }
+
+    fn remove_key(&mut self, key: &K) {
+        self.remove(key);
+    }
+
+    fn insert_default(&mut self) {
+        self.insert(K::default());
+    }
+}
+
+/// The label of a map entry in the paths of validation errors - the key itself when it is a
+/// string, a number or a `char`, and otherwise its index in the iteration order of the map.
+fn map_key_label<K: YoleckAutoEdit>(key: &K, index: usize) -> String {
+    if let Some(summary) = K::auto_edit_summary(key) {
+        return summary;
+    }
+    let key: &dyn Any = key;
+    macro_rules! display_types {
+        ($($ty:ty),*) => {
+            $(
+                if let Some(key) = key.downcast_ref::<$ty>() {
+                    return key.to_string();
+                }
+            )*
+        };
+    }
+    display_types!(
+        String,
+        char,
+        bool,
+        i8,
+        i16,
+        i32,
+        i64,
+        i128,
+        isize,
+        u8,
+        u16,
+        u32,
+        u64,
+        u128,
+        usize,
+        uuid::Uuid
+    );
+    index.to_string()
+}
+
+impl<K, V, S> YoleckAutoEdit for HashMap<K, V, S>
+where
+    K: YoleckAutoEdit + Default + Clone + Eq + Hash,
+    V: YoleckAutoEdit + Default,
+    S: BuildHasher + Send + Sync + 'static,
+{
+    fn auto_edit_validate(
+        value: &Self,
+        ctx: &YoleckValidationContext,
+        errors: &mut Vec<YoleckValidationError>,
+    ) {
+        for (index, (key, item)) in value.iter().enumerate() {
+            validate_nested(item, &map_key_label(key, index), ctx, errors);
+        }
+    }
+
+    fn auto_edit_level_paths(value: &mut Self, visit: &mut dyn FnMut(&mut YoleckLevelPath)) {
+        for item in value.values_mut() {
+            V::auto_edit_level_paths(item, visit);
+        }
+    }
+
+    fn auto_edit(value: &mut Self, ui: &mut egui::Ui) {
+        map_ui(value, ui, V::auto_edit);
+    }
+
+    fn auto_edit_field_impl(value: &mut Self, ui: &mut egui::Ui, label: &str, attrs: &FieldAttrs) {
+        collapsing_field(ui, label, attrs, |ui| Self::auto_edit(value, ui));
+    }
+}
+
+impl<K, V> YoleckAutoEdit for BTreeMap<K, V>
+where
+    K: YoleckAutoEdit + Default + Clone + Ord + Hash,
+    V: YoleckAutoEdit + Default,
+{
+    fn auto_edit_validate(
+        value: &Self,
+        ctx: &YoleckValidationContext,
+        errors: &mut Vec<YoleckValidationError>,
+    ) {
+        for (index, (key, item)) in value.iter().enumerate() {
+            validate_nested(item, &map_key_label(key, index), ctx, errors);
+        }
+    }
+
+    fn auto_edit_level_paths(value: &mut Self, visit: &mut dyn FnMut(&mut YoleckLevelPath)) {
+        for item in value.values_mut() {
+            V::auto_edit_level_paths(item, visit);
+        }
+    }
+
+    fn auto_edit(value: &mut Self, ui: &mut egui::Ui) {
+        map_ui(value, ui, V::auto_edit);
+    }
+
+    fn auto_edit_field_impl(value: &mut Self, ui: &mut egui::Ui, label: &str, attrs: &FieldAttrs) {
+        collapsing_field(ui, label, attrs, |ui| Self::auto_edit(value, ui));
+    }
+}
+
+impl<K, S> YoleckAutoEdit for HashSet<K, S>
+where
+    K: YoleckAutoEdit + Default + Clone + Eq + Hash,
+    S: BuildHasher + Send + Sync + 'static,
+{
+    fn auto_edit(value: &mut Self, ui: &mut egui::Ui) {
+        let keys: Vec<K> = value.iter().cloned().collect();
+        let edits = keyed_entries_ui(ui, &keys, |_, _| {});
+        edits.apply(value);
+    }
+
+    fn auto_edit_field_impl(value: &mut Self, ui: &mut egui::Ui, label: &str, attrs: &FieldAttrs) {
+        collapsing_field(ui, label, attrs, |ui| Self::auto_edit(value, ui));
+    }
+}
+
+impl<K> YoleckAutoEdit for BTreeSet<K>
+where
+    K: YoleckAutoEdit + Default + Clone + Ord + Hash,
+{
+    fn auto_edit(value: &mut Self, ui: &mut egui::Ui) {
+        let keys: Vec<K> = value.iter().cloned().collect();
+        let edits = keyed_entries_ui(ui, &keys, |_, _| {});
+        edits.apply(value);
+    }
+
+    fn auto_edit_field_impl(value: &mut Self, ui: &mut egui::Ui, label: &str, attrs: &FieldAttrs) {
+        collapsing_field(ui, label, attrs, |ui| Self::auto_edit(value, ui));
+    }
+}
+
+impl<T: YoleckAutoEdit + Default> YoleckAutoEdit for Option<T> {
+    fn auto_edit_validate(
+        value: &Self,
+        ctx: &YoleckValidationContext,
+        errors: &mut Vec<YoleckValidationError>,
+    ) {
+        if let Some(inner) = value {
+            T::auto_edit_validate(inner, ctx, errors);
+        }
+    }
+
+    fn auto_edit_level_paths(value: &mut Self, visit: &mut dyn FnMut(&mut YoleckLevelPath)) {
+        if let Some(inner) = value {
+            T::auto_edit_level_paths(inner, visit);
+        }
+    }
+
+    fn auto_edit_apply_json(
+        value: &mut Self,
+        json: &serde_json::Value,
+        path: &[String],
+        paths: &YoleckSerializedPaths,
+    ) -> bool {
+        match value {
+            Some(inner) => T::auto_edit_apply_json(inner, json, path, paths),
+            None => false,
+        }
+    }
+
+    fn auto_edit(value: &mut Self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            option_ui(value, ui, T::auto_edit);
+        });
+    }
+
+    fn auto_edit_field_impl(value: &mut Self, ui: &mut egui::Ui, label: &str, attrs: &FieldAttrs) {
+        option_field_ui(value, ui, label, attrs, T::auto_edit);
+    }
+}
+
+/// The checkbox for whether an `Option` has a value, followed by the value.
+fn option_ui<T: Default>(
+    value: &mut Option<T>,
+    ui: &mut egui::Ui,
+    edit_inner: impl FnOnce(&mut T, &mut egui::Ui),
+) {
+    let mut has_value = value.is_some();
+    if ui.checkbox(&mut has_value, "").changed() {
+        if has_value {
+            *value = Some(T::default());
+        } else {
+            *value = None;
+        }
+    }
+    if let Some(inner) = value.as_mut() {
+        edit_inner(inner, ui);
+    }
+}
+
+fn option_field_ui<T: Default>(
+    value: &mut Option<T>,
+    ui: &mut egui::Ui,
+    label: &str,
+    attrs: &FieldAttrs,
+    edit_inner: impl FnOnce(&mut T, &mut egui::Ui),
+) {
+    let response = ui
+        .horizontal(|ui| {
+            ui.label(label);
+            option_ui(value, ui, edit_inner);
+        })
+        .response;
+
+    if let Some(tooltip) = &attrs.tooltip {
+        response.on_hover_text(tooltip);
+    }
+}
+
+/// [`YoleckAutoEdit::auto_edit_apply_json`] for lists. Only changes inside an element can be
+/// applied, since replacing a whole element requires deserializing it.
+fn apply_json_to_element<T: YoleckAutoEdit>(
+    list: YoleckPartAddress,
+    elements: &mut [T],
+    json: &serde_json::Value,
+    path: &[String],
+    paths: &YoleckSerializedPaths,
+) -> bool {
+    let Some(list_path) = paths.path_of(list) else {
+        return false;
+    };
+    let Some([index, rest @ ..]) = path.strip_prefix(list_path) else {
+        return false;
+    };
+    match (index.parse::<usize>().ok(), rest.is_empty()) {
+        (Some(index), false) if index < elements.len() => {
+            T::auto_edit_apply_json(&mut elements[index], json, path, paths)
+        }
+        _ => false,
+    }
+}
+
+/// Dragged by the handles of `Vec` elements for reordering them.
+#[derive(Clone, Copy)]
+struct VecElementDragPayload {
+    list_id: egui::Id,
+    index: usize,
+}
+
+enum VecEdit {
+    Remove(usize),
+    Duplicate(usize),
+    InsertBefore(usize),
+    Move { from: usize, to: usize },
+    Push,
+}
+
+/// The drag handle and the index of a `Vec` element.
+fn vec_element_handle(ui: &mut egui::Ui, list_id: egui::Id, index: usize) {
+    ui.dnd_drag_source(
+        list_id.with(("handle", index)),
+        VecElementDragPayload { list_id, index },
+        |ui| ui.label("☰"),
+    )
+    .response
+    .on_hover_text("Drag to reorder");
+    ui.label(format!("[{}]", index));
+}
+
+impl VecEdit {
+    /// The index an element at `index` moves to, or `None` if the element is removed.
+    fn new_index(&self, index: usize) -> Option<usize> {
+        let inserted_at = |position: usize| if index < position { index } else { index + 1 };
+        match *self {
+            VecEdit::Remove(removed) => match index.cmp(&removed) {
+                std::cmp::Ordering::Less => Some(index),
+                std::cmp::Ordering::Equal => None,
+                std::cmp::Ordering::Greater => Some(index - 1),
+            },
+            VecEdit::Duplicate(duplicated) => Some(inserted_at(duplicated + 1)),
+            VecEdit::InsertBefore(position) => Some(inserted_at(position)),
+            VecEdit::Move { from, to } => Some(if index == from {
+                to
+            } else {
+                let index = if from < index { index - 1 } else { index };
+                if to <= index { index + 1 } else { index }
+            }),
+            VecEdit::Push => Some(index),
+        }
+    }
+}
+
+/// Move the open state of the collapsing headers of the elements of a `Vec` along with the
+/// elements, so that changing the list does not open or close the headers of other elements.
+fn move_vec_collapsing_states(ctx: &egui::Context, list_id: egui::Id, len: usize, edit: &VecEdit) {
+    let opened: Vec<Option<bool>> = (0..len)
+        .map(|index| {
+            let state = egui::collapsing_header::CollapsingState::load(ctx, list_id.with(index))?;
+            state.remove(ctx);
+            Some(state.is_open())
+        })
+        .collect();
+    for (index, is_open) in opened.into_iter().enumerate() {
+        let (Some(is_open), Some(new_index)) = (is_open, edit.new_index(index)) else {
+            continue;
+        };
+        let mut state = egui::collapsing_header::CollapsingState::load_with_default_open(
+            ctx,
+            list_id.with(new_index),
+            false,
+        );
+        state.set_open(is_open);
+        state.store(ctx);
+    }
+}
+
+/// The buttons for moving, duplicating, inserting before and removing a `Vec` element. The
+/// duplicate button is only shown for elements that can be cloned.
+fn vec_element_buttons(
+    ui: &mut egui::Ui,
+    index: usize,
+    len: usize,
+    can_add: bool,
+    can_remove: bool,
+    can_duplicate: bool,
+) -> Option<VecEdit> {
+    let mut edit = None;
+    if ui
+        .add_enabled(0 < index, egui::Button::new("⬆").small())
+        .on_hover_text("Move up")
+        .clicked()
+    {
+        edit = Some(VecEdit::Move {
+            from: index,
+            to: index - 1,
+        });
+    }
+    if ui
+        .add_enabled(index + 1 < len, egui::Button::new("⬇").small())
+        .on_hover_text("Move down")
+        .clicked()
+    {
+        edit = Some(VecEdit::Move {
+            from: index,
+            to: index + 1,
+        });
+    }
+    if can_duplicate
+        && ui
+            .add_enabled(can_add, egui::Button::new("🗐").small())
+            .on_hover_text("Duplicate")
+            .clicked()
+    {
+        edit = Some(VecEdit::Duplicate(index));
+    }
+    if ui
+        .add_enabled(can_add, egui::Button::new("➕").small())
+        .on_hover_text("Insert a new element before this one")
+        .clicked()
+    {
+        edit = Some(VecEdit::InsertBefore(index));
+    }
+    if ui
+        .add_enabled(can_remove, egui::Button::new("−").small())
+        .on_hover_text("Remove")
+        .clicked()
+    {
+        edit = Some(VecEdit::Remove(index));
+    }
+    edit
+}
+
+/// The elements of a `Vec`, with the buttons for changing the list. Elements for which `summary`
+/// returns a description are shown under collapsing headers. Elements can only be duplicated if
+/// `duplicate` is given.
+fn vec_ui<T: Default>(
+    value: &mut Vec<T>,
+    ui: &mut egui::Ui,
+    attrs: &FieldAttrs,
+    summary: impl Fn(&T) -> Option<String>,
+    duplicate: Option<fn(&T) -> T>,
+    mut edit_element: impl FnMut(&mut T, &mut egui::Ui),
+) {
+    let list_id = ui.id().with("yoleck_vec");
+    let len = value.len();
+    let can_add = attrs.max_len.is_none_or(|max_len| len < max_len);
+    let can_remove = attrs.min_len.is_none_or(|min_len| min_len < len);
+
+    let mut edit = None;
+    for (index, item) in value.iter_mut().enumerate() {
+        let row_response = if let Some(summary) = summary(item) {
+            let (_, header, _) = egui::collapsing_header::CollapsingState::load_with_default_open(
+                ui.ctx(),
+                list_id.with(index),
+                false,
+            )
+            .show_header(ui, |ui| {
+                vec_element_handle(ui, list_id, index);
+                ui.label(summary);
+                if let Some(element_edit) =
+                    vec_element_buttons(ui, index, len, can_add, can_remove, duplicate.is_some())
+                {
+                    edit = Some(element_edit);
+                }
+            })
+            .body(|ui| {
+                multi_edit_field_scope(
+                    ui,
+                    &index.to_string(),
+                    YoleckPartAddress::of(&*item),
+                    |ui| {
+                        edit_element(item, ui);
+                    },
+                );
+            });
+            header.response
+        } else {
+            ui.horizontal(|ui| {
+                vec_element_handle(ui, list_id, index);
+                multi_edit_field_scope(
+                    ui,
+                    &index.to_string(),
+                    YoleckPartAddress::of(&*item),
+                    |ui| {
+                        edit_element(item, ui);
+                    },
+                );
+                if let Some(element_edit) =
+                    vec_element_buttons(ui, index, len, can_add, can_remove, duplicate.is_some())
+                {
+                    edit = Some(element_edit);
+                }
+            })
+            .response
+        };
+
+        if let Some(payload) = row_response.dnd_hover_payload::<VecElementDragPayload>()
+            && payload.list_id == list_id
+            && payload.index != index
+        {
+            let y = if payload.index < index {
+                row_response.rect.bottom()
+            } else {
+                row_response.rect.top()
+            };
+            ui.painter().hline(
+                row_response.rect.x_range(),
+                y,
+                ui.visuals().selection.stroke,
+            );
+        }
+        if let Some(payload) = row_response.dnd_release_payload::<VecElementDragPayload>()
+            && payload.list_id == list_id
+            && payload.index != index
+        {
+            edit = Some(VecEdit::Move {
+                from: payload.index,
+                to: index,
+            });
+        }
+    }
+
+    ui.horizontal(|ui| {
+        if ui
+            .add_enabled(can_add, egui::Button::new("+").small())
+            .clicked()
+        {
+            edit = Some(VecEdit::Push);
+        }
+        if let Some(min_len) = attrs.min_len
+            && len < min_len
+        {
+            ui.colored_label(
+                egui::Color32::YELLOW,
+                format!("⚠ Expected at least {min_len} elements"),
+            );
+        }
+        if let Some(max_len) = attrs.max_len
+            && max_len < len
+        {
+            ui.colored_label(
+                egui::Color32::YELLOW,
+                format!("⚠ Expected at most {max_len} elements"),
+            );
+        }
+    });
+
+    let Some(edit) = edit else {
+        return;
+    };
+    move_vec_collapsing_states(ui.ctx(), list_id, len, &edit);
+    match edit {
+        VecEdit::Remove(index) => {
+            value.remove(index);
+        }
+        VecEdit::Duplicate(index) => {
+            if let Some(duplicate) = duplicate {
+                let element = duplicate(&value[index]);
+                value.insert(index + 1, element);
+            }
+        }
+        VecEdit::InsertBefore(index) => {
+            value.insert(index, T::default());
+        }
+        VecEdit::Move { from, to } => {
+            let element = value.remove(from);
+            value.insert(to, element);
+        }
+        VecEdit::Push => {
+            value.push(T::default());
+        }
+    }
+}
+
+impl<T: YoleckAutoEdit + Default> YoleckAutoEdit for Vec<T> {
+    fn auto_edit_validate(
+        value: &Self,
+        ctx: &YoleckValidationContext,
+        errors: &mut Vec<YoleckValidationError>,
+    ) {
+        for (index, item) in value.iter().enumerate() {
+            validate_nested(item, &index.to_string(), ctx, errors);
+        }
+    }
+
+    fn auto_edit_level_paths(value: &mut Self, visit: &mut dyn FnMut(&mut YoleckLevelPath)) {
+        for item in value.iter_mut() {
+            T::auto_edit_level_paths(item, visit);
+        }
+    }
+
+    fn auto_edit_apply_json(
+        value: &mut Self,
+        json: &serde_json::Value,
+        path: &[String],
+        paths: &YoleckSerializedPaths,
+    ) -> bool {
+        apply_json_to_element(YoleckPartAddress::of(&*value), value, json, path, paths)
+    }
+
+    fn auto_edit(value: &mut Self, ui: &mut egui::Ui) {
+        vec_ui(
+            value,
+            ui,
+            &FieldAttrs::default(),
+            T::auto_edit_summary,
+            None,
+            T::auto_edit,
+        );
+    }
+
+    fn auto_edit_field_impl(value: &mut Self, ui: &mut egui::Ui, label: &str, attrs: &FieldAttrs) {
+        vec_field_ui(
+            value,
+            ui,
+            label,
+            attrs,
+            T::auto_edit_summary,
+            None,
+            T::auto_edit,
+        );
+    }
+}
+
+fn vec_field_ui<T: Default>(
+    value: &mut Vec<T>,
+    ui: &mut egui::Ui,
+    label: &str,
+    attrs: &FieldAttrs,
+    summary: impl Fn(&T) -> Option<String>,
+    duplicate: Option<fn(&T) -> T>,
+    edit_element: impl FnMut(&mut T, &mut egui::Ui),
+) {
+    // Long lists start collapsed so they don't push the other fields out of view.
+    let response = egui::CollapsingHeader::new(format!("{label} ({})", value.len()))
+        .id_salt(label)
+        .default_open(value.len() <= 5)
+        .show(ui, |ui| {
+            vec_ui(value, ui, attrs, summary, duplicate, edit_element);
+        });
+
+    if let Some(tooltip) = &attrs.tooltip {
+        response.header_response.on_hover_text(tooltip);
+    }
+}
+
+impl<T: YoleckAutoEdit, const N: usize> YoleckAutoEdit for [T; N] {
+    fn auto_edit_validate(
+        value: &Self,
+        ctx: &YoleckValidationContext,
+        errors: &mut Vec<YoleckValidationError>,
+    ) {
+        for (index, item) in value.iter().enumerate() {
+            validate_nested(item, &index.to_string(), ctx, errors);
+        }
+    }
+
+    fn auto_edit_level_paths(value: &mut Self, visit: &mut dyn FnMut(&mut YoleckLevelPath)) {
+        for item in value.iter_mut() {
+            T::auto_edit_level_paths(item, visit);
+        }
+    }
+
+    fn auto_edit_apply_json(
+        value: &mut Self,
+        json: &serde_json::Value,
+        path: &[String],
+        paths: &YoleckSerializedPaths,
+    ) -> bool {
+        apply_json_to_element(YoleckPartAddress::of(&*value), value, json, path, paths)
+    }
+
+    fn auto_edit(value: &mut Self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            for item in value.iter_mut() {
+                T::auto_edit(item, ui);
+            }
+        });
+    }
+
+    fn auto_edit_field_impl(value: &mut Self, ui: &mut egui::Ui, label: &str, attrs: &FieldAttrs) {
+        if attrs.color_picker && color_picker_field(value, ui, label, attrs) {
+            return;
+        }
+        array_field_ui(value, ui, label, attrs, T::auto_edit);
+    }
+}
+
+fn array_field_ui<T>(
+    value: &mut [T],
+    ui: &mut egui::Ui,
+    label: &str,
+    attrs: &FieldAttrs,
+    mut edit_element: impl FnMut(&mut T, &mut egui::Ui),
+) {
+    let response = ui
+        .horizontal(|ui| {
+            ui.label(label);
+            for item in value.iter_mut() {
+                edit_element(item, ui);
+            }
+        })
+        .response;
+
+    if let Some(tooltip) = &attrs.tooltip {
+        response.on_hover_text(tooltip);
+    }
+}
+
+impl<T: YoleckAutoEdit> YoleckAutoEdit for [T] {
+    fn auto_edit(value: &mut Self, ui: &mut egui::Ui) {
+        for (idx, item) in value.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(format!("[{}]", idx));
+                T::auto_edit(item, ui);
+            });
+        }
+    }
+}
+
+#[derive(Clone)]
+struct EntityRefDisplayInfo {
+    pub type_name: String,
+    pub base_types: Vec<String>,
+    pub markers: Vec<TypeId>,
+    pub name: String,
+}
+
+impl EntityRefDisplayInfo {
+    fn display_text(&self, uuid: uuid::Uuid) -> String {
+        if self.name.is_empty() {
+            let uuid_str = uuid.to_string();
+            let uuid_short = &uuid_str[..uuid_str.len().min(8)];
+            format!("{} ({})", self.type_name, uuid_short)
+        } else {
+            format!("{} - {}", self.type_name, self.name)
+        }
+    }
+
+    fn passes_filter(&self, filter: &EntityRefFilter) -> bool {
+        filter.entity_type.as_ref().is_none_or(|entity_type| {
+            &self.type_name == entity_type || self.base_types.iter().any(|base| base == entity_type)
+        }) && filter
+            .marker
+            .is_none_or(|(marker, _)| self.markers.contains(&marker))
+    }
+}
+
+/// The entities that an entity reference field can point at.
+#[derive(Clone, Default)]
+struct EntityRefFilter {
+    /// From `#[yoleck(entity_ref = "...")]`.
+    entity_type: Option<String>,
+    /// The marker of a [`YoleckEntityRefTo`], with its type name.
+    marker: Option<(TypeId, &'static str)>,
+}
+
+impl EntityRefFilter {
+    #[cfg(feature = "vpeol")]
+    fn allows(&self, registry: &YoleckUuidRegistry, uuid: uuid::Uuid) -> bool {
+        self.entity_type
+            .as_ref()
+            .is_none_or(|entity_type| registry.get_of_type(uuid, entity_type).is_some())
+            && self
+                .marker
+                .is_none_or(|(marker, _)| registry.has_marker(uuid, marker))
+    }
+
+    /// What to ask the user to pick from the viewport.
+    #[cfg(feature = "vpeol")]
+    fn description(&self) -> Option<String> {
+        match (&self.entity_type, self.marker) {
+            (Some(entity_type), _) => Some(entity_type.clone()),
+            (None, Some((_, marker_name))) => Some(ShortName(marker_name).to_string()),
+            (None, None) => None,
+        }
+    }
+}
+
+/// Sent from the entity reference widget to [`auto_edit_system`], which starts the exclusive
+/// system that picks the entity from the viewport.
+#[cfg(feature = "vpeol")]
+#[derive(Clone)]
+struct EntityRefPickRequest {
+    widget_id: egui::Id,
+    filter: EntityRefFilter,
+}
+
+/// Sent from the exclusive system that picks the entity from the viewport back to the entity
+/// reference widget that requested it.
+#[cfg(feature = "vpeol")]
+#[derive(Clone, Copy)]
+struct EntityRefPickResult {
+    widget_id: egui::Id,
+    picked_uuid: uuid::Uuid,
+}
+
+#[cfg(feature = "vpeol")]
+const ENTITY_REF_PICK_RESULT_ID: &str = "yoleck_entity_ref_pick_result";
+
+/// Drop the result of picking an entity reference from the viewport if the widget that requested
+/// it did not take it - e.g. because the selection changed while picking.
+#[cfg(feature = "vpeol")]
+pub(crate) fn discard_entity_ref_pick_result(ctx: &egui::Context) {
+    ctx.data_mut(|data| {
+        data.remove::<EntityRefPickResult>(egui::Id::new(ENTITY_REF_PICK_RESULT_ID));
+    });
+}
+
+impl YoleckAutoEdit for YoleckEntityRef {
+    fn auto_edit(value: &mut Self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            if let Some(uuid) = value.uuid() {
+                ui.label(uuid.to_string());
+                if ui.small_button("✕").clicked() {
+                    value.clear();
+                }
+            } else {
+                ui.label("None");
+            }
+        });
+    }
+
+    fn auto_edit_field_impl(value: &mut Self, ui: &mut egui::Ui, label: &str, attrs: &FieldAttrs) {
+        let filter = EntityRefFilter {
+            entity_type: attrs.entity_filter.clone(),
+            marker: None,
+        };
+        entity_ref_field_ui(value, ui, label, attrs, &filter);
+    }
+}
+
+fn entity_ref_field_ui(
+    value: &mut YoleckEntityRef,
+    ui: &mut egui::Ui,
+    label: &str,
+    attrs: &FieldAttrs,
+    filter: &EntityRefFilter,
+) {
+    // Get entity info map once for display, the dropdown and drag&drop validation
+    let entity_info_map = ui.ctx().data(|data| {
+        data.get_temp::<HashMap<uuid::Uuid, EntityRefDisplayInfo>>(egui::Id::new(
+            "yoleck_entity_ref_display_info",
+        ))
+    });
+
+    // `auto_edit_system` pushes the edited entities to the id, so that picks from the viewport
+    // that were requested for one entity do not end up in another.
+    let widget_id = ui.id().with(("yoleck_entity_ref", label));
+
+    #[cfg(feature = "vpeol")]
+    if let Some(picked_uuid) = ui.ctx().data_mut(|data| {
+        let id = egui::Id::new(ENTITY_REF_PICK_RESULT_ID);
+        let result = data
+            .get_temp::<EntityRefPickResult>(id)
+            .filter(|result| result.widget_id == widget_id)?;
+        data.remove::<EntityRefPickResult>(id);
+        Some(result.picked_uuid)
+    }) {
+        value.set(picked_uuid);
+    }
+
+    let response = ui
+        .horizontal(|ui| {
+            ui.label(label);
+
+            let display_text = if let Some(uuid) = value.uuid() {
+                if let Some(info) = entity_info_map.as_ref().and_then(|map| map.get(&uuid)) {
+                    info.display_text(uuid)
+                } else {
+                    uuid.to_string()
+                }
+            } else {
+                "None".to_string()
+            };
+
+            egui::ComboBox::from_id_salt(widget_id)
+                .selected_text(
+                    egui::RichText::new(display_text)
+                        .text_style(ui.style().drag_value_text_style.clone()),
+                )
+                .wrap_mode(egui::TextWrapMode::Extend)
+                .show_ui(ui, |ui| {
+                    if ui.selectable_label(value.is_none(), "None").clicked() {
+                        value.clear();
+                    }
+                    let mut candidates: Vec<(uuid::Uuid, String)> = entity_info_map
+                        .iter()
+                        .flatten()
+                        .filter(|(_, info)| info.passes_filter(filter))
+                        .map(|(uuid, info)| (*uuid, info.display_text(*uuid)))
+                        .collect();
+                    candidates.sort_by(|a, b| a.1.cmp(&b.1));
+                    for (uuid, text) in candidates {
+                        if ui
+                            .selectable_label(value.uuid() == Some(uuid), text)
+                            .clicked()
+                        {
+                            value.set(uuid);
+                        }
+                    }
+                });
+
+            if value.is_some() && ui.small_button("✕").clicked() {
+                value.clear();
+            }
+
+            #[cfg(feature = "vpeol")]
+            if ui
+                .small_button("🎯")
+                .on_hover_text("Pick from the viewport")
+                .clicked()
+            {
+                ui.ctx().data_mut(|data| {
+                    data.insert_temp(
+                        egui::Id::new("yoleck_entity_ref_pick_request"),
+                        EntityRefPickRequest {
+                            widget_id,
+                            filter: filter.clone(),
+                        },
+                    );
+                });
+            }
+
+            if let Some(tooltip) = &attrs.tooltip {
+                ui.label("ⓘ").on_hover_text(tooltip);
+            }
+        })
+        .response;
+
+    // Handle drag & drop
+    if let Some(dropped_uuid) = response.dnd_release_payload::<uuid::Uuid>() {
+        let dropped_uuid = *dropped_uuid;
+
+        let should_accept = entity_info_map
+            .as_ref()
+            .and_then(|map| map.get(&dropped_uuid))
+            .is_none_or(|info| info.passes_filter(filter));
+
+        if should_accept {
+            value.set(dropped_uuid);
+        }
+    }
+}
+
+impl<T: Component> YoleckAutoEdit for YoleckEntityRefTo<T> {
+    fn auto_edit(value: &mut Self, ui: &mut egui::Ui) {
+        YoleckEntityRef::auto_edit(value, ui);
+    }
+
+    fn auto_edit_field_impl(value: &mut Self, ui: &mut egui::Ui, label: &str, attrs: &FieldAttrs) {
+        let filter = EntityRefFilter {
+            entity_type: None,
+            marker: Some((TypeId::of::<T>(), std::any::type_name::<T>())),
+        };
+        entity_ref_field_ui(value, ui, label, attrs, &filter);
+    }
+}
+
+/// Pick the target of an entity reference field by clicking on an entity in the viewport.
+#[cfg(feature = "vpeol")]
+fn push_entity_ref_pick_exclusive_system(
+    exclusive_queue: &mut YoleckExclusiveSystemsQueue,
+    request: EntityRefPickRequest,
+) {
+    use crate::exclusive_systems::YoleckExclusiveSystemDirective;
+    use crate::vpeol::vpeol_read_click_on_entity;
+    use crate::{yoleck_exclusive_system_cancellable, yoleck_map_entity_to_uuid};
+
+    let EntityRefPickRequest { widget_id, filter } = request;
+    exclusive_queue.push_back(
+        vpeol_read_click_on_entity::<With<YoleckEntityUuid>>
+            .pipe(yoleck_map_entity_to_uuid)
+            .pipe(
+                move |In(picked_uuid): In<Option<uuid::Uuid>>,
+                      mut ui: ResMut<YoleckUi>,
+                      registry: Res<YoleckUuidRegistry>| {
+                    if let Some(description) = filter.description() {
+                        ui.label(format!("Pick a {description}"));
+                    }
+                    let Some(picked_uuid) = picked_uuid else {
+                        return YoleckExclusiveSystemDirective::Listening;
+                    };
+                    if !filter.allows(&registry, picked_uuid) {
+                        return YoleckExclusiveSystemDirective::Listening;
+                    }
+                    ui.ctx().data_mut(|data| {
+                        data.insert_temp(
+                            egui::Id::new(ENTITY_REF_PICK_RESULT_ID),
+                            EntityRefPickResult {
+                                widget_id,
+                                picked_uuid,
+                            },
+                        );
+                    });
+                    YoleckExclusiveSystemDirective::Finished
+                },
+            )
+            .pipe(yoleck_exclusive_system_cancellable),
+    );
+}
+
 use crate::YoleckExtForApp;
 use crate::editing::{YoleckEdit, YoleckUi};
 use crate::specs_registration::YoleckComponent;
 
-use crate::entity_ref::YoleckEntityRefAccessor;
-use bevy::ecs::component::Mutable;
+use crate::entity_ref::YoleckEntityRefAccessor;
+use bevy::ecs::component::Mutable;
+
+use crate::YoleckManaged;
+use crate::entity_uuid::YoleckEntityUuid;
+#[cfg(feature = "vpeol")]
+use crate::exclusive_systems::YoleckExclusiveSystemsQueue;
+
+/// A button that runs an action on a component.
+///
+/// The action is either a `fn(&mut T)`, or a `fn(&mut T, &mut YoleckButtonContext)` for actions
+/// that need the entity or the world:
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_yoleck::prelude::*;
+/// # use bevy_yoleck::auto_edit::YoleckButtonContext;
+/// # use serde::{Deserialize, Serialize};
+/// #[derive(Component, YoleckComponent, YoleckAutoEdit, Serialize, Deserialize, Clone, PartialEq, Default)]
+/// #[yoleck(button = Self::reroll_seed)]
+/// #[yoleck(button(Self::log_name, label = "Log name"))]
+/// struct Terrain {
+///     seed: u64,
+/// }
+///
+/// impl Terrain {
+///     fn reroll_seed(&mut self) {
+///         self.seed = self.seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+///     }
+///
+///     fn log_name(&mut self, ctx: &mut YoleckButtonContext) {
+///         let entity = ctx.entity;
+///         ctx.commands.queue(move |world: &mut World| {
+///             if let Some(name) = world.get::<Name>(entity) {
+///                 info!("{name} has seed {}", world.get::<Terrain>(entity).unwrap().seed);
+///             }
+///         });
+///     }
+/// }
+/// ```
+///
+/// When several entities are edited together, the action runs on each of them. The changes to the
+/// component are saved like any other edit.
+pub struct YoleckButton<T> {
+    pub label: String,
+    pub action: YoleckButtonAction<T>,
+}
+
+impl<T> YoleckButton<T> {
+    pub fn new<Marker>(
+        label: impl Into<String>,
+        action: impl IntoYoleckButtonAction<T, Marker>,
+    ) -> Self {
+        Self {
+            label: label.into(),
+            action: action.into_button_action(),
+        }
+    }
+}
+
+type ButtonActionWithContext<T> = dyn Fn(&mut T, &mut YoleckButtonContext) + Send + Sync;
+
+pub enum YoleckButtonAction<T> {
+    Mutate(Box<dyn Fn(&mut T) + Send + Sync>),
+    WithContext(Box<ButtonActionWithContext<T>>),
+}
+
+/// Access to the entity and to the world for button actions that need them. Use
+/// [`Commands::queue`] to access the [`World`].
+pub struct YoleckButtonContext<'a, 'w, 's> {
+    pub entity: Entity,
+    pub commands: &'a mut Commands<'w, 's>,
+}
+
+/// Functions that can be used as [`YoleckButton`] actions. The `Marker` parameter tells apart the
+/// function signatures.
+pub trait IntoYoleckButtonAction<T, Marker> {
+    fn into_button_action(self) -> YoleckButtonAction<T>;
+}
+
+impl<T, F: Fn(&mut T) + Send + Sync + 'static> IntoYoleckButtonAction<T, fn(&mut T)> for F {
+    fn into_button_action(self) -> YoleckButtonAction<T> {
+        YoleckButtonAction::Mutate(Box::new(self))
+    }
+}
+
+impl<T, F: Fn(&mut T, &mut YoleckButtonContext) + Send + Sync + 'static>
+    IntoYoleckButtonAction<T, fn(&mut T, &mut YoleckButtonContext)> for F
+{
+    fn into_button_action(self) -> YoleckButtonAction<T> {
+        YoleckButtonAction::WithContext(Box::new(self))
+    }
+}
+
+/// A function that edits a field, for `#[yoleck(widget = ...)]` and
+/// [`register_yoleck_widget`](YoleckAutoEditExt::register_yoleck_widget). It gets the same
+/// arguments as [`YoleckAutoEdit::auto_edit_field_impl`].
+pub type YoleckWidgetFn<T> = fn(&mut T, &mut egui::Ui, &str, &FieldAttrs);
+
+pub(crate) type ErasedWidgetFn =
+    Arc<dyn Fn(&mut dyn Any, &mut egui::Ui, &str, &FieldAttrs) + Send + Sync>;
+
+/// Widgets for field types that don't implement [`YoleckAutoEdit`] - usually because both the
+/// type and the trait are defined in other crates.
+///
+/// Only fields marked with `#[yoleck(registered)]` use these widgets - other fields must implement
+/// [`YoleckAutoEdit`]. Fields of types that implement [`YoleckAutoEdit`] always use their
+/// implementation, even when a widget is registered for their type. The widget of a type is also
+/// used for the elements of `Vec`, `Option`, array and map fields of that type.
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_yoleck::prelude::*;
+/// # use bevy_yoleck::auto_edit::FieldAttrs;
+/// # use bevy_yoleck::egui;
+/// # let mut app = App::new();
+/// // Pretend `std::ops::Range` is a type from another crate.
+/// fn range_widget(value: &mut std::ops::Range<f32>, ui: &mut egui::Ui, label: &str, _attrs: &FieldAttrs) {
+///     ui.horizontal(|ui| {
+///         ui.label(label);
+///         ui.add(egui::DragValue::new(&mut value.start));
+///         ui.label("..");
+///         ui.add(egui::DragValue::new(&mut value.end));
+///     });
+/// }
+///
+/// app.register_yoleck_widget(range_widget);
+/// ```
+#[derive(Resource, Default, Clone)]
+pub struct YoleckWidgetRegistry(Arc<HashMap<TypeId, ErasedWidgetFn>>);
+
+impl YoleckWidgetRegistry {
+    pub fn register<T: 'static>(&mut self, widget: YoleckWidgetFn<T>) {
+        Arc::make_mut(&mut self.0).insert(
+            TypeId::of::<T>(),
+            Arc::new(move |value, ui, label, attrs| {
+                if let Some(value) = value.downcast_mut::<T>() {
+                    widget(value, ui, label, attrs);
+                }
+            }),
+        );
+    }
+
+    pub fn contains<T: 'static>(&self) -> bool {
+        self.0.contains_key(&TypeId::of::<T>())
+    }
+}
+
+/// The widget registered for a type in the [`YoleckWidgetRegistry`] that the edit system placed in
+/// the egui context.
+pub(crate) fn registered_widget(ui: &egui::Ui, type_id: TypeId) -> Option<ErasedWidgetFn> {
+    ui.data(|data| {
+        data.get_temp::<YoleckWidgetRegistry>(egui::Id::new("yoleck_widget_registry"))
+            .and_then(|registry| registry.0.get(&type_id).cloned())
+    })
+}
+
+/// Place the resources that widgets need in the egui context.
+pub(crate) fn provide_widget_context(
+    ctx: &egui::Context,
+    assets_directory: Option<&YoleckEditorAssetsDirectoryPath>,
+    widget_registry: Option<&YoleckWidgetRegistry>,
+    editable_levels: Option<&crate::YoleckEditableLevels>,
+) {
+    ctx.data_mut(|data| {
+        if let Some(editable_levels) = editable_levels {
+            data.insert_temp(
+                egui::Id::new("yoleck_editable_levels"),
+                Arc::new(
+                    editable_levels
+                        .names()
+                        .map(str::to_owned)
+                        .collect::<Vec<_>>(),
+                ),
+            );
+        }
+        if let Some(assets_directory) = assets_directory {
+            data.insert_temp(
+                egui::Id::new("yoleck_assets_directory"),
+                Arc::new(assets_directory.0.clone()),
+            );
+        }
+        if let Some(widget_registry) = widget_registry {
+            data.insert_temp(
+                egui::Id::new("yoleck_widget_registry"),
+                widget_registry.clone(),
+            );
+        }
+    });
+}
+
+/// Edit a field with a widget function instead of with [`YoleckAutoEdit`]. Used for
+/// `#[yoleck(widget = ...)]`.
+pub fn edit_with_widget<T: ?Sized>(
+    value: &mut T,
+    ui: &mut egui::Ui,
+    label: &str,
+    attrs: &FieldAttrs,
+    widget: impl FnOnce(&mut T, &mut egui::Ui, &str, &FieldAttrs),
+) {
+    if attrs.readonly {
+        ui.add_enabled_ui(false, |ui| widget(value, ui, label, attrs));
+    } else {
+        widget(value, ui, label, attrs);
+    }
+}
+
+/// Used by `#[derive(YoleckAutoEdit)]` to edit fields with their [`YoleckAutoEdit`]
+/// implementation when they have one, and with the widgets from the [`YoleckWidgetRegistry`]
+/// otherwise. The derive only brings the registry traits into scope for fields marked with
+/// `#[yoleck(registered)]` or `#[yoleck(widget = ...)]`.
+///
+/// `(&&&YoleckFieldEditor::<T>(PhantomData)).edit_field(...)` resolves to
+/// [`YoleckFieldViaAutoEdit`] if `T: YoleckAutoEdit`, to [`YoleckFieldViaElementRegistry`] (which
+/// needs another dereference) if `T` is a collection, and to [`YoleckFieldViaRegistry`] (which
+/// needs a dereference and a copy) otherwise. `Vec`s of elements that implement `Clone` are
+/// matched before all of these (without dereferencing), so that their elements can be duplicated.
+#[doc(hidden)]
+pub struct YoleckFieldEditor<T>(pub std::marker::PhantomData<fn() -> T>);
+
+// Implemented manually because the derives would require `T` to implement these traits too.
+
+impl<T> Clone for YoleckFieldEditor<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for YoleckFieldEditor<T> {}
+
+#[doc(hidden)]
+pub trait YoleckFieldViaAutoEdit<T> {
+    fn edit_field(&self, value: &mut T, ui: &mut egui::Ui, label: &str, attrs: &FieldAttrs);
+    fn validate_field(
+        &self,
+        value: &T,
+        field: &str,
+        ctx: &YoleckValidationContext,
+        errors: &mut Vec<YoleckValidationError>,
+    );
+    fn field_level_paths(&self, value: &mut T, visit: &mut dyn FnMut(&mut YoleckLevelPath));
+    fn apply_field_json(
+        &self,
+        value: &mut T,
+        json: &serde_json::Value,
+        path: &[String],
+        paths: &YoleckSerializedPaths,
+    ) -> bool;
+}
+
+impl<T: YoleckAutoEdit> YoleckFieldViaAutoEdit<T> for &YoleckFieldEditor<T> {
+    fn edit_field(&self, value: &mut T, ui: &mut egui::Ui, label: &str, attrs: &FieldAttrs) {
+        T::auto_edit_with_label_and_attrs(value, ui, label, attrs);
+    }
+
+    fn validate_field(
+        &self,
+        value: &T,
+        field: &str,
+        ctx: &YoleckValidationContext,
+        errors: &mut Vec<YoleckValidationError>,
+    ) {
+        validate_nested(value, field, ctx, errors);
+    }
+
+    fn field_level_paths(&self, value: &mut T, visit: &mut dyn FnMut(&mut YoleckLevelPath)) {
+        T::auto_edit_level_paths(value, visit);
+    }
+
+    fn apply_field_json(
+        &self,
+        value: &mut T,
+        json: &serde_json::Value,
+        path: &[String],
+        paths: &YoleckSerializedPaths,
+    ) -> bool {
+        T::auto_edit_apply_json(value, json, path, paths)
+    }
+}
+
+impl<T: YoleckAutoEdit + Default + Clone> YoleckFieldViaAutoEdit<Vec<T>>
+    for &&YoleckFieldEditor<Vec<T>>
+{
+    fn edit_field(&self, value: &mut Vec<T>, ui: &mut egui::Ui, label: &str, attrs: &FieldAttrs) {
+        edit_with_widget(value, ui, label, attrs, |value, ui, label, attrs| {
+            vec_field_ui(
+                value,
+                ui,
+                label,
+                attrs,
+                T::auto_edit_summary,
+                Some(T::clone),
+                T::auto_edit,
+            );
+        });
+    }
+
+    fn validate_field(
+        &self,
+        value: &Vec<T>,
+        field: &str,
+        ctx: &YoleckValidationContext,
+        errors: &mut Vec<YoleckValidationError>,
+    ) {
+        validate_nested(value, field, ctx, errors);
+    }
+
+    fn field_level_paths(&self, value: &mut Vec<T>, visit: &mut dyn FnMut(&mut YoleckLevelPath)) {
+        Vec::<T>::auto_edit_level_paths(value, visit);
+    }
+
+    fn apply_field_json(
+        &self,
+        value: &mut Vec<T>,
+        json: &serde_json::Value,
+        path: &[String],
+        paths: &YoleckSerializedPaths,
+    ) -> bool {
+        Vec::<T>::auto_edit_apply_json(value, json, path, paths)
+    }
+}
+
+/// The marker shown instead of values whose type has no widget.
+fn missing_widget_marker(ui: &mut egui::Ui, type_name: &str) {
+    ui.colored_label(egui::Color32::YELLOW, "⚠")
+        .on_hover_text(format!("`{type_name}` has no widget"));
+}
+
+/// Shown instead of fields whose type has no widget.
+pub(crate) fn missing_widget_ui(ui: &mut egui::Ui, label: &str, type_name: &str) {
+    ui.horizontal(|ui| {
+        ui.label(label);
+        missing_widget_marker(ui, type_name);
+    });
+}
+
+/// Edit a field with the widget registered for its type. Returns `false` if no widget is
+/// registered for it.
+fn edit_with_registered_widget<T: 'static>(
+    value: &mut T,
+    ui: &mut egui::Ui,
+    label: &str,
+    attrs: &FieldAttrs,
+) -> bool {
+    let Some(widget) = registered_widget(ui, TypeId::of::<T>()) else {
+        return false;
+    };
+    edit_with_widget(value, ui, label, attrs, |value, ui, label, attrs| {
+        widget(value, ui, label, attrs);
+    });
+    true
+}
+
+/// Edit an element of a collection with the widget registered for its type.
+fn edit_element_with_registered_widget<T: 'static>(value: &mut T, ui: &mut egui::Ui) {
+    if let Some(widget) = registered_widget(ui, TypeId::of::<T>()) {
+        widget(value, ui, "", &FieldAttrs::default());
+    } else {
+        missing_widget_marker(ui, std::any::type_name::<T>());
+    }
+}
+
+/// Collections whose elements don't implement [`YoleckAutoEdit`], so they are edited with the
+/// widgets registered for the type of the elements (unless a widget is registered for the type of
+/// the collection itself).
+#[doc(hidden)]
+pub trait YoleckFieldViaElementRegistry<T> {
+    fn edit_field(&self, value: &mut T, ui: &mut egui::Ui, label: &str, attrs: &FieldAttrs);
+
+    fn validate_field(
+        &self,
+        _value: &T,
+        _field: &str,
+        _ctx: &YoleckValidationContext,
+        _errors: &mut Vec<YoleckValidationError>,
+    ) {
+    }
+
+    fn field_level_paths(&self, _value: &mut T, _visit: &mut dyn FnMut(&mut YoleckLevelPath)) {}
+
+    fn apply_field_json(
+        &self,
+        _value: &mut T,
+        _json: &serde_json::Value,
+        _path: &[String],
+        _paths: &YoleckSerializedPaths,
+    ) -> bool {
+        false
+    }
+}
+
+impl<T: Default + Clone + 'static> YoleckFieldViaElementRegistry<Vec<T>>
+    for &&&YoleckFieldEditor<Vec<T>>
+{
+    fn edit_field(&self, value: &mut Vec<T>, ui: &mut egui::Ui, label: &str, attrs: &FieldAttrs) {
+        if !edit_with_registered_widget(value, ui, label, attrs) {
+            vec_field_ui(
+                value,
+                ui,
+                label,
+                attrs,
+                |_| None,
+                Some(T::clone),
+                edit_element_with_registered_widget,
+            );
+        }
+    }
+}
+
+impl<T: Default + 'static> YoleckFieldViaElementRegistry<Vec<T>> for YoleckFieldEditor<Vec<T>> {
+    fn edit_field(&self, value: &mut Vec<T>, ui: &mut egui::Ui, label: &str, attrs: &FieldAttrs) {
+        if !edit_with_registered_widget(value, ui, label, attrs) {
+            vec_field_ui(
+                value,
+                ui,
+                label,
+                attrs,
+                |_| None,
+                None,
+                edit_element_with_registered_widget,
+            );
+        }
+    }
+}
+
+impl<T: Default + 'static> YoleckFieldViaElementRegistry<Option<T>>
+    for YoleckFieldEditor<Option<T>>
+{
+    fn edit_field(
+        &self,
+        value: &mut Option<T>,
+        ui: &mut egui::Ui,
+        label: &str,
+        attrs: &FieldAttrs,
+    ) {
+        if !edit_with_registered_widget(value, ui, label, attrs) {
+            option_field_ui(value, ui, label, attrs, edit_element_with_registered_widget);
+        }
+    }
+}
+
+impl<T: 'static, const N: usize> YoleckFieldViaElementRegistry<[T; N]>
+    for YoleckFieldEditor<[T; N]>
+{
+    fn edit_field(&self, value: &mut [T; N], ui: &mut egui::Ui, label: &str, attrs: &FieldAttrs) {
+        if !edit_with_registered_widget(value, ui, label, attrs) {
+            array_field_ui(value, ui, label, attrs, edit_element_with_registered_widget);
+        }
+    }
+}
+
+impl<K, V, S> YoleckFieldViaElementRegistry<HashMap<K, V, S>>
+    for YoleckFieldEditor<HashMap<K, V, S>>
+where
+    K: YoleckAutoEdit + Default + Clone + Eq + Hash,
+    V: Default + 'static,
+    S: BuildHasher + 'static,
+{
+    fn edit_field(
+        &self,
+        value: &mut HashMap<K, V, S>,
+        ui: &mut egui::Ui,
+        label: &str,
+        attrs: &FieldAttrs,
+    ) {
+        if !edit_with_registered_widget(value, ui, label, attrs) {
+            collapsing_field(ui, label, attrs, |ui| {
+                map_ui(value, ui, edit_element_with_registered_widget);
+            });
+        }
+    }
+}
 
-use crate::YoleckManaged;
-use crate::entity_uuid::YoleckEntityUuid;
+impl<K, V> YoleckFieldViaElementRegistry<BTreeMap<K, V>> for YoleckFieldEditor<BTreeMap<K, V>>
+where
+    K: YoleckAutoEdit + Default + Clone + Ord + Hash,
+    V: Default + 'static,
+{
+    fn edit_field(
+        &self,
+        value: &mut BTreeMap<K, V>,
+        ui: &mut egui::Ui,
+        label: &str,
+        attrs: &FieldAttrs,
+    ) {
+        if !edit_with_registered_widget(value, ui, label, attrs) {
+            collapsing_field(ui, label, attrs, |ui| {
+                map_ui(value, ui, edit_element_with_registered_widget);
+            });
+        }
+    }
+}
+
+#[doc(hidden)]
+pub trait YoleckFieldViaRegistry<T> {
+    fn edit_field(self, value: &mut T, ui: &mut egui::Ui, label: &str, attrs: &FieldAttrs);
+    fn validate_field(
+        self,
+        value: &T,
+        field: &str,
+        ctx: &YoleckValidationContext,
+        errors: &mut Vec<YoleckValidationError>,
+    );
+    fn field_level_paths(self, value: &mut T, visit: &mut dyn FnMut(&mut YoleckLevelPath));
+    fn apply_field_json(
+        self,
+        value: &mut T,
+        json: &serde_json::Value,
+        path: &[String],
+        paths: &YoleckSerializedPaths,
+    ) -> bool;
+}
+
+impl<T: 'static> YoleckFieldViaRegistry<T> for YoleckFieldEditor<T> {
+    fn edit_field(self, value: &mut T, ui: &mut egui::Ui, label: &str, attrs: &FieldAttrs) {
+        if !edit_with_registered_widget(value, ui, label, attrs) {
+            missing_widget_ui(ui, label, std::any::type_name::<T>());
+        }
+    }
+
+    fn validate_field(
+        self,
+        _value: &T,
+        _field: &str,
+        _ctx: &YoleckValidationContext,
+        _errors: &mut Vec<YoleckValidationError>,
+    ) {
+    }
+
+    fn field_level_paths(self, _value: &mut T, _visit: &mut dyn FnMut(&mut YoleckLevelPath)) {}
+
+    fn apply_field_json(
+        self,
+        _value: &mut T,
+        _json: &serde_json::Value,
+        _path: &[String],
+        _paths: &YoleckSerializedPaths,
+    ) -> bool {
+        false
+    }
+}
+
+/// Used by `#[derive(YoleckAutoEdit)]` to replace a field with its value deserialized from JSON,
+/// when the field's type can be deserialized.
+///
+/// `(&&&YoleckFieldEditor::<T>(PhantomData)).deserialize_field(...)` resolves to
+/// [`YoleckFieldViaDeserialize`] if `T: DeserializeOwned`, and to [`YoleckFieldNotDeserializable`]
+/// (which returns `false`) if not.
+#[doc(hidden)]
+pub trait YoleckFieldViaDeserialize<T> {
+    fn deserialize_field(&self, value: &mut T, json: &serde_json::Value) -> bool;
+}
+
+impl<T: serde::de::DeserializeOwned> YoleckFieldViaDeserialize<T> for &YoleckFieldEditor<T> {
+    fn deserialize_field(&self, value: &mut T, json: &serde_json::Value) -> bool {
+        match serde_json::from_value(json.clone()) {
+            Ok(new_value) => {
+                *value = new_value;
+                true
+            }
+            Err(_) => false,
+        }
+    }
+}
+
+#[doc(hidden)]
+pub trait YoleckFieldNotDeserializable<T> {
+    fn deserialize_field(&self, value: &mut T, json: &serde_json::Value) -> bool;
+}
+
+impl<T> YoleckFieldNotDeserializable<T> for YoleckFieldEditor<T> {
+    fn deserialize_field(&self, _value: &mut T, _json: &serde_json::Value) -> bool {
+        false
+    }
+}
+
+/// The paths where the values differ between the entities edited together, the paths of the
+/// parts of the edited value, and the path of the field currently being edited.
+#[derive(Clone)]
+struct MultiEditState {
+    mixed: Arc<Vec<Vec<String>>>,
+    paths: Arc<YoleckSerializedPaths>,
+    path: Vec<String>,
+}
+
+fn multi_edit_state_id() -> egui::Id {
+    egui::Id::new("yoleck_multi_edit")
+}
+
+/// Edit a field of a value that may be edited on behalf of several entities. When the field's
+/// value differs between these entities, it is marked as mixed.
+///
+/// `#[derive(YoleckAutoEdit)]` wraps each field with it. `field` is the name of the field, or its
+/// index for tuple fields and list elements, and `part` is the field itself.
+pub fn multi_edit_field_scope<R>(
+    ui: &mut egui::Ui,
+    field: &str,
+    part: YoleckPartAddress,
+    add_contents: impl FnOnce(&mut egui::Ui) -> R,
+) -> R {
+    // The widgets of the field derive their ids from the field, so that their state does not move
+    // to other fields when fields above them are shown or hidden.
+    ui.push_id(field, |ui| {
+        multi_edit_field_scope_inner(ui, field, part, add_contents)
+    })
+    .inner
+}
+
+fn multi_edit_field_scope_inner<R>(
+    ui: &mut egui::Ui,
+    field: &str,
+    part: YoleckPartAddress,
+    add_contents: impl FnOnce(&mut egui::Ui) -> R,
+) -> R {
+    let Some(mut state) = ui.data(|data| data.get_temp::<MultiEditState>(multi_edit_state_id()))
+    else {
+        return add_contents(ui);
+    };
+    // List elements have no path of their own (see `YoleckSerializedPaths`), so they are found by
+    // their index in the list.
+    let path = match state.paths.path_of(part) {
+        Some(path) => path.to_vec(),
+        None => {
+            let mut path = state.path.clone();
+            path.push(field.to_owned());
+            path
+        }
+    };
+    let is_mixed = state.mixed.iter().any(|mixed| mixed.starts_with(&path));
+    let parent_path = std::mem::replace(&mut state.path, path);
+    ui.data_mut(|data| data.insert_temp(multi_edit_state_id(), state.clone()));
+    let result = if is_mixed {
+        ui.horizontal(|ui| {
+            ui.colored_label(egui::Color32::YELLOW, "≠")
+                .on_hover_text("Differs between the selected entities");
+            ui.vertical(add_contents).inner
+        })
+        .inner
+    } else {
+        add_contents(ui)
+    };
+    state.path = parent_path;
+    ui.data_mut(|data| data.insert_temp(multi_edit_state_id(), state));
+    result
+}
+
+/// Add the paths where the two JSON values differ. Objects with the same keys and arrays with the
+/// same length are compared element by element.
+fn json_diff_paths(
+    a: &serde_json::Value,
+    b: &serde_json::Value,
+    path: &mut Vec<String>,
+    out: &mut Vec<Vec<String>>,
+) {
+    use serde_json::Value;
+    match (a, b) {
+        (Value::Object(a), Value::Object(b))
+            if a.len() == b.len() && a.keys().all(|key| b.contains_key(key)) =>
+        {
+            for (key, a_value) in a {
+                path.push(key.clone());
+                json_diff_paths(a_value, &b[key], path, out);
+                path.pop();
+            }
+        }
+        (Value::Array(a), Value::Array(b)) if a.len() == b.len() => {
+            for (index, (a_value, b_value)) in a.iter().zip(b).enumerate() {
+                path.push(index.to_string());
+                json_diff_paths(a_value, b_value, path, out);
+                path.pop();
+            }
+        }
+        _ => {
+            if a != b {
+                out.push(path.clone());
+            }
+        }
+    }
+}
+
+fn json_at_path_mut<'a>(
+    mut value: &'a mut serde_json::Value,
+    path: &[String],
+) -> Option<&'a mut serde_json::Value> {
+    for key in path {
+        value = match value {
+            serde_json::Value::Object(object) => object.get_mut(key)?,
+            serde_json::Value::Array(array) => array.get_mut(key.parse::<usize>().ok()?)?,
+            _ => return None,
+        };
+    }
+    Some(value)
+}
+
+fn json_at_path<'a>(
+    mut value: &'a serde_json::Value,
+    path: &[String],
+) -> Option<&'a serde_json::Value> {
+    for key in path {
+        value = match value {
+            serde_json::Value::Object(object) => object.get(key)?,
+            serde_json::Value::Array(array) => array.get(key.parse::<usize>().ok()?)?,
+            _ => return None,
+        };
+    }
+    Some(value)
+}
+
+/// Identifies a part of a value (a field, a list element etc.) by its address and its type, for
+/// looking up its path in [`YoleckSerializedPaths`].
+#[doc(hidden)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct YoleckPartAddress {
+    address: usize,
+    type_name: &'static str,
+}
+
+impl YoleckPartAddress {
+    pub fn of<T: ?Sized>(part: &T) -> Self {
+        Self {
+            address: std::ptr::from_ref(part).cast::<()>() as usize,
+            type_name: std::any::type_name::<T>(),
+        }
+    }
+}
+
+/// The paths of the parts of a value in its serialized data - the keys of its fields (after
+/// serde's renaming), the indices of its list elements etc.
+///
+/// The paths are found by serializing the value and matching each serialized part by its address
+/// and type, so they stay valid only as long as the value is not changed. Parts that are not
+/// serialized as themselves - e.g. skipped and flattened fields - have no path. Neither do the
+/// elements of `Vec`s, which serde serializes through references to them, so they should be found
+/// by their index in the list instead.
+#[doc(hidden)]
+#[derive(Default)]
+pub struct YoleckSerializedPaths(HashMap<YoleckPartAddress, Vec<String>>);
+
+impl YoleckSerializedPaths {
+    pub fn of<T: serde::Serialize + ?Sized>(value: &T) -> Self {
+        let mut paths = HashMap::new();
+        // Serializing fails on parts that cannot be serialized to JSON either (e.g. map keys that
+        // are not strings), and the parts after them are left without paths.
+        let _ = value.serialize(SerializedPathsRecorder {
+            path: Vec::new(),
+            paths: &mut paths,
+            next_index: 0,
+            map_key: None,
+        });
+        Self(paths)
+    }
+
+    pub fn path_of(&self, part: YoleckPartAddress) -> Option<&[String]> {
+        self.0.get(&part).map(Vec::as_slice)
+    }
+}
+
+/// Where a path that changed in the serialized data is relative to a field. Used by the
+/// `auto_edit_apply_json` that `#[derive(YoleckAutoEdit)]` generates.
+#[doc(hidden)]
+pub enum YoleckChangedField<'a> {
+    /// The path is of the field itself. Has the new serialized value of the field.
+    Whole(&'a serde_json::Value),
+    /// The path is inside the field. Has the new serialized value of the field.
+    Inside(&'a serde_json::Value),
+    /// The field has no path of its own (e.g. it is flattened), so the path may be inside it.
+    Unknown,
+    Unrelated,
+}
+
+#[doc(hidden)]
+pub fn changed_field<'a, F: ?Sized>(
+    field: &F,
+    json: &'a serde_json::Value,
+    path: &[String],
+    paths: &YoleckSerializedPaths,
+) -> YoleckChangedField<'a> {
+    let Some(field_path) = paths.path_of(YoleckPartAddress::of(field)) else {
+        return YoleckChangedField::Unknown;
+    };
+    if !path.starts_with(field_path) {
+        return YoleckChangedField::Unrelated;
+    }
+    match json_at_path(json, field_path) {
+        Some(field_json) if path.len() == field_path.len() => YoleckChangedField::Whole(field_json),
+        Some(field_json) => YoleckChangedField::Inside(field_json),
+        None => YoleckChangedField::Unrelated,
+    }
+}
+
+/// A serializer that only records the paths of the parts it is given into
+/// [`YoleckSerializedPaths`]. Parts that share both an address and a type (e.g. zero-sized fields)
+/// keep the first path recorded for them.
+struct SerializedPathsRecorder<'a> {
+    path: Vec<String>,
+    paths: &'a mut HashMap<YoleckPartAddress, Vec<String>>,
+    /// The index of the next element of a sequence or a tuple.
+    next_index: usize,
+    map_key: Option<String>,
+}
+
+type SerializedPathsResult = Result<(), serde::de::value::Error>;
+
+impl SerializedPathsRecorder<'_> {
+    fn record<T: serde::Serialize + ?Sized>(
+        &mut self,
+        key: Option<String>,
+        part: &T,
+    ) -> SerializedPathsResult {
+        let mut path = self.path.clone();
+        path.extend(key);
+        self.paths
+            .entry(YoleckPartAddress::of(part))
+            .or_insert_with(|| path.clone());
+        part.serialize(SerializedPathsRecorder {
+            path,
+            paths: self.paths,
+            next_index: 0,
+            map_key: None,
+        })
+    }
+
+    fn record_element<T: serde::Serialize + ?Sized>(&mut self, part: &T) -> SerializedPathsResult {
+        let index = self.next_index;
+        self.next_index += 1;
+        self.record(Some(index.to_string()), part)
+    }
+
+    fn into_variant(mut self, variant: &str) -> Self {
+        self.path.push(variant.to_owned());
+        self
+    }
+}
+
+impl serde::Serializer for SerializedPathsRecorder<'_> {
+    type Ok = ();
+    type Error = serde::de::value::Error;
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    fn serialize_bool(self, _: bool) -> SerializedPathsResult {
+        Ok(())
+    }
+
+    fn serialize_i8(self, _: i8) -> SerializedPathsResult {
+        Ok(())
+    }
+
+    fn serialize_i16(self, _: i16) -> SerializedPathsResult {
+        Ok(())
+    }
+
+    fn serialize_i32(self, _: i32) -> SerializedPathsResult {
+        Ok(())
+    }
+
+    fn serialize_i64(self, _: i64) -> SerializedPathsResult {
+        Ok(())
+    }
+
+    fn serialize_u8(self, _: u8) -> SerializedPathsResult {
+        Ok(())
+    }
+
+    fn serialize_u16(self, _: u16) -> SerializedPathsResult {
+        Ok(())
+    }
+
+    fn serialize_u32(self, _: u32) -> SerializedPathsResult {
+        Ok(())
+    }
+
+    fn serialize_u64(self, _: u64) -> SerializedPathsResult {
+        Ok(())
+    }
+
+    fn serialize_f32(self, _: f32) -> SerializedPathsResult {
+        Ok(())
+    }
+
+    fn serialize_f64(self, _: f64) -> SerializedPathsResult {
+        Ok(())
+    }
+
+    fn serialize_char(self, _: char) -> SerializedPathsResult {
+        Ok(())
+    }
+
+    fn serialize_str(self, _: &str) -> SerializedPathsResult {
+        Ok(())
+    }
+
+    fn serialize_bytes(self, _: &[u8]) -> SerializedPathsResult {
+        Ok(())
+    }
+
+    fn serialize_none(self) -> SerializedPathsResult {
+        Ok(())
+    }
+
+    fn serialize_some<T: serde::Serialize + ?Sized>(mut self, value: &T) -> SerializedPathsResult {
+        self.record(None, value)
+    }
+
+    fn serialize_unit(self) -> SerializedPathsResult {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> SerializedPathsResult {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+    ) -> SerializedPathsResult {
+        Ok(())
+    }
+
+    fn serialize_newtype_struct<T: serde::Serialize + ?Sized>(
+        mut self,
+        _: &'static str,
+        value: &T,
+    ) -> SerializedPathsResult {
+        self.record(None, value)
+    }
+
+    fn serialize_newtype_variant<T: serde::Serialize + ?Sized>(
+        mut self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> SerializedPathsResult {
+        self.record(Some(variant.to_owned()), value)
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self, Self::Error> {
+        Ok(self)
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Self, Self::Error> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_struct(self, _: &'static str, _: usize) -> Result<Self, Self::Error> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        _: usize,
+    ) -> Result<Self, Self::Error> {
+        Ok(self.into_variant(variant))
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Self, Self::Error> {
+        Ok(self)
+    }
+
+    fn serialize_struct(self, _: &'static str, _: usize) -> Result<Self, Self::Error> {
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        _: usize,
+    ) -> Result<Self, Self::Error> {
+        Ok(self.into_variant(variant))
+    }
+}
+
+impl serde::ser::SerializeSeq for SerializedPathsRecorder<'_> {
+    type Ok = ();
+    type Error = serde::de::value::Error;
+
+    fn serialize_element<T: serde::Serialize + ?Sized>(
+        &mut self,
+        value: &T,
+    ) -> SerializedPathsResult {
+        self.record_element(value)
+    }
+
+    fn end(self) -> SerializedPathsResult {
+        Ok(())
+    }
+}
+
+impl serde::ser::SerializeTuple for SerializedPathsRecorder<'_> {
+    type Ok = ();
+    type Error = serde::de::value::Error;
+
+    fn serialize_element<T: serde::Serialize + ?Sized>(
+        &mut self,
+        value: &T,
+    ) -> SerializedPathsResult {
+        self.record_element(value)
+    }
+
+    fn end(self) -> SerializedPathsResult {
+        Ok(())
+    }
+}
+
+impl serde::ser::SerializeTupleStruct for SerializedPathsRecorder<'_> {
+    type Ok = ();
+    type Error = serde::de::value::Error;
+
+    fn serialize_field<T: serde::Serialize + ?Sized>(
+        &mut self,
+        value: &T,
+    ) -> SerializedPathsResult {
+        self.record_element(value)
+    }
+
+    fn end(self) -> SerializedPathsResult {
+        Ok(())
+    }
+}
+
+impl serde::ser::SerializeTupleVariant for SerializedPathsRecorder<'_> {
+    type Ok = ();
+    type Error = serde::de::value::Error;
+
+    fn serialize_field<T: serde::Serialize + ?Sized>(
+        &mut self,
+        value: &T,
+    ) -> SerializedPathsResult {
+        self.record_element(value)
+    }
+
+    fn end(self) -> SerializedPathsResult {
+        Ok(())
+    }
+}
+
+impl serde::ser::SerializeMap for SerializedPathsRecorder<'_> {
+    type Ok = ();
+    type Error = serde::de::value::Error;
+
+    fn serialize_key<T: serde::Serialize + ?Sized>(&mut self, key: &T) -> SerializedPathsResult {
+        // Map keys are written as strings in JSON.
+        self.map_key = match serde_json::to_value(key) {
+            Ok(serde_json::Value::String(key)) => Some(key),
+            Ok(serde_json::Value::Number(key)) => Some(key.to_string()),
+            Ok(serde_json::Value::Bool(key)) => Some(key.to_string()),
+            _ => None,
+        };
+        Ok(())
+    }
+
+    fn serialize_value<T: serde::Serialize + ?Sized>(
+        &mut self,
+        value: &T,
+    ) -> SerializedPathsResult {
+        match self.map_key.take() {
+            Some(key) => self.record(Some(key), value),
+            None => Ok(()),
+        }
+    }
+
+    fn end(self) -> SerializedPathsResult {
+        Ok(())
+    }
+}
+
+impl serde::ser::SerializeStruct for SerializedPathsRecorder<'_> {
+    type Ok = ();
+    type Error = serde::de::value::Error;
+
+    fn serialize_field<T: serde::Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> SerializedPathsResult {
+        self.record(Some(key.to_owned()), value)
+    }
+
+    fn end(self) -> SerializedPathsResult {
+        Ok(())
+    }
+}
+
+impl serde::ser::SerializeStructVariant for SerializedPathsRecorder<'_> {
+    type Ok = ();
+    type Error = serde::de::value::Error;
+
+    fn serialize_field<T: serde::Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> SerializedPathsResult {
+        self.record(Some(key.to_owned()), value)
+    }
+
+    fn end(self) -> SerializedPathsResult {
+        Ok(())
+    }
+}
+
+/// Edit a component of several entities at once. The UI shows the first entity's values, and
+/// the fields that the user changes are written to all the entities (as long as they have these
+/// fields - e.g. entities with a different enum variant don't get the fields of the edited
+/// variant).
+///
+/// The first entity gets the edited component as is. On the other entities, only the changed
+/// fields that actually differ in their data are set, with
+/// [`YoleckAutoEdit::auto_edit_apply_json`], so the fields that are not serialized (e.g.
+/// `#[serde(skip)]`) keep their values. Components that cannot apply a changed field that way are
+/// deserialized whole from their patched data, which resets such fields to their defaults.
+fn multi_auto_edit<T: YoleckComponent + YoleckAutoEdit>(
+    components: &mut [Mut<T>],
+    ui: &mut egui::Ui,
+) {
+    let Ok(originals) = components
+        .iter()
+        .map(|component| serde_json::to_value(&**component))
+        .collect::<Result<Vec<_>, _>>()
+    else {
+        return;
+    };
+    let mut mixed = Vec::new();
+    for other in originals.iter().skip(1) {
+        json_diff_paths(&originals[0], other, &mut Vec::new(), &mut mixed);
+    }
+    mixed.sort();
+    mixed.dedup();
+
+    let mut edited = (*components[0]).clone();
+    let paths = YoleckSerializedPaths::of(&edited);
+    ui.data_mut(|data| {
+        data.insert_temp(
+            multi_edit_state_id(),
+            MultiEditState {
+                mixed: Arc::new(mixed),
+                paths: Arc::new(paths),
+                path: Vec::new(),
+            },
+        );
+    });
+    T::auto_edit(&mut edited, ui);
+    ui.data_mut(|data| data.remove::<MultiEditState>(multi_edit_state_id()));
 
+    if edited == *components[0] {
+        return;
+    }
+    let Ok(edited_json) = serde_json::to_value(&edited) else {
+        return;
+    };
+    let mut changed = Vec::new();
+    json_diff_paths(&originals[0], &edited_json, &mut Vec::new(), &mut changed);
+    let mut edited_json = edited_json;
+    for (component, mut json) in components.iter_mut().zip(originals).skip(1) {
+        let mut differing = Vec::new();
+        for path in changed.iter() {
+            if let Some(new_value) = json_at_path_mut(&mut edited_json, path)
+                && let Some(old_value) = json_at_path_mut(&mut json, path)
+                && old_value != new_value
+            {
+                *old_value = new_value.clone();
+                differing.push(path);
+            }
+        }
+        if differing.is_empty() {
+            continue;
+        }
+        let mut new_component = (**component).clone();
+        // Setting a part may move the parts inside it (e.g. when a list is replaced), so the paths
+        // are found again for each changed part.
+        if differing.iter().all(|path| {
+            let paths = YoleckSerializedPaths::of(&new_component);
+            T::auto_edit_apply_json(&mut new_component, &json, path, &paths)
+        }) {
+            **component = new_component;
+        } else if let Ok(new_component) = serde_json::from_value::<T>(json) {
+            **component = new_component;
+        }
+    }
+    *components[0] = edited;
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn auto_edit_system<T: YoleckComponent + YoleckAutoEdit + YoleckEntityRefAccessor>(
     mut ui: ResMut<YoleckUi>,
-    mut edit: YoleckEdit<&mut T>,
+    mut edit: YoleckEdit<(Entity, &mut T)>,
+    mut commands: Commands,
     entities_query: Query<(&YoleckEntityUuid, &YoleckManaged)>,
     registry: Res<YoleckUuidRegistry>,
+    assets_directory: Option<Res<YoleckEditorAssetsDirectoryPath>>,
+    widget_registry: Option<Res<YoleckWidgetRegistry>>,
+    editable_levels: Option<Res<crate::YoleckEditableLevels>>,
+    #[cfg(feature = "vpeol")] mut exclusive_queue: ResMut<YoleckExclusiveSystemsQueue>,
 ) {
-    let Ok(mut component) = edit.single_mut() else {
+    // Like the vpeol editing systems, only edit the entities together when they all have the
+    // component.
+    if edit.is_empty() || edit.has_nonmatching() {
         return;
-    };
+    }
+    let (entities, mut components): (Vec<Entity>, Vec<Mut<T>>) = edit.iter_matching_mut().unzip();
+
+    provide_widget_context(
+        ui.ctx(),
+        assets_directory.as_deref(),
+        widget_registry.as_deref(),
+        editable_levels.as_deref(),
+    );
 
     // Populate entity display info in egui context only if component has entity ref fields
     if !T::entity_ref_fields().is_empty() {
@@ -645,6 +3525,12 @@ pub fn auto_edit_system<T: YoleckComponent + YoleckAutoEdit + YoleckEntityRefAcc
                 entity_uuid.get(),
                 EntityRefDisplayInfo {
                     type_name: managed.type_name.clone(),
+                    base_types: managed.base_types.clone(),
+                    markers: registry
+                        .markers
+                        .get(&entity_uuid.get())
+                        .cloned()
+                        .unwrap_or_default(),
                     name: managed.name.clone(),
                 },
             );
@@ -658,13 +3544,55 @@ pub fn auto_edit_system<T: YoleckComponent + YoleckAutoEdit + YoleckEntityRefAcc
         });
     }
 
-    ui.group(|ui| {
-        ui.label(egui::RichText::new(T::KEY).strong());
-        ui.separator();
-        T::auto_edit(&mut component, ui);
+    // The ids of the widgets depend on the edited entities, so that state that belongs to a field
+    // of one entity does not leak into the same field of another.
+    component_edit_ui(&mut ui, T::KEY, entities.len(), |ui| {
+        ui.push_id(&entities, |ui| {
+            if let [component] = components.as_mut_slice() {
+                T::auto_edit(component, ui);
+            } else {
+                multi_auto_edit(&mut components, ui);
+            }
+
+            let buttons = T::auto_edit_buttons();
+            if !buttons.is_empty() {
+                ui.separator();
+                ui.horizontal_wrapped(|ui| {
+                    for button in buttons {
+                        if !ui.button(&button.label).clicked() {
+                            continue;
+                        }
+                        for (entity, component) in entities.iter().zip(components.iter_mut()) {
+                            match &button.action {
+                                YoleckButtonAction::Mutate(action) => action(component),
+                                YoleckButtonAction::WithContext(action) => action(
+                                    component,
+                                    &mut YoleckButtonContext {
+                                        entity: *entity,
+                                        commands: &mut commands,
+                                    },
+                                ),
+                            }
+                        }
+                    }
+                });
+            }
+        });
     });
 
-    component.resolve_entity_refs(registry.as_ref());
+    #[cfg(feature = "vpeol")]
+    if let Some(request) = ui.ctx().data_mut(|data| {
+        let id = egui::Id::new("yoleck_entity_ref_pick_request");
+        let request = data.get_temp::<EntityRefPickRequest>(id);
+        data.remove::<EntityRefPickRequest>(id);
+        request
+    }) {
+        push_entity_ref_pick_exclusive_system(&mut exclusive_queue, request);
+    }
+
+    for component in components.iter_mut() {
+        component.resolve_entity_refs(registry.as_ref());
+    }
 }
 
 pub trait YoleckAutoEditExt {
@@ -676,6 +3604,13 @@ pub trait YoleckAutoEditExt {
     >(
         &mut self,
     );
+
+    /// Register a widget for fields of a type that does not implement [`YoleckAutoEdit`]. See
+    /// [`YoleckWidgetRegistry`].
+    ///
+    /// Fields that use these widgets must be marked with `#[yoleck(registered)]`. If no widget is
+    /// registered for their type, they are shown with a warning marker in the editor.
+    fn register_yoleck_widget<T: 'static>(&mut self, widget: YoleckWidgetFn<T>);
 }
 
 impl YoleckAutoEditExt for App {
@@ -687,18 +3622,25 @@ impl YoleckAutoEditExt for App {
     >(
         &mut self,
     ) {
-        self.add_yoleck_edit_system(auto_edit_system::<T>);
+        self.add_yoleck_component_edit_system::<T, _>(auto_edit_system::<T>);
         self.add_systems(
             YoleckInternalSchedule::PostLoadResolutions,
             resolve_entity_refs::<T>,
         );
+        self.world_mut()
+            .get_resource_or_insert_with(YoleckEntityRefHandlers::default)
+            .register::<T>();
+        self.world_mut()
+            .get_resource_or_insert_with(YoleckValidationHandlers::default)
+            .register::<T>();
+        self.world_mut()
+            .get_resource_or_insert_with(YoleckLevelPathHandlers::default)
+            .register::<T>(T::auto_edit_level_paths);
+    }
 
-        let construction_specs = self
-            .world_mut()
-            .get_resource::<crate::YoleckEntityConstructionSpecs>();
-
-        if let Some(specs) = construction_specs {
-            validate_entity_ref_requirements_for::<T>(specs);
-        }
+    fn register_yoleck_widget<T: 'static>(&mut self, widget: YoleckWidgetFn<T>) {
+        self.world_mut()
+            .get_resource_or_insert_with(YoleckWidgetRegistry::default)
+            .register(widget);
     }
 }
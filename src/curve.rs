@@ -0,0 +1,741 @@
+use bevy::color::Mix;
+use bevy::math::curve::{Curve, Interval};
+use bevy::prelude::*;
+use bevy_egui::egui;
+use serde::{Deserialize, Serialize};
+
+use crate::auto_edit::{FieldAttrs, YoleckAutoEdit};
+
+/// How a [`YoleckCurve`] or a [`YoleckGradient`] goes from a key to the next one.
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "bevy_reflect", derive(Reflect))]
+pub enum YoleckInterpolation {
+    /// Keep the value of the key until the next key.
+    Constant,
+    #[default]
+    Linear,
+    /// Ease out of the key and into the next one.
+    Smooth,
+}
+
+impl YoleckInterpolation {
+    const ALL: [Self; 3] = [Self::Constant, Self::Linear, Self::Smooth];
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Constant => "Constant",
+            Self::Linear => "Linear",
+            Self::Smooth => "Smooth",
+        }
+    }
+
+    /// Map the progress between two keys (from 0 to 1) to the weight of the second key.
+    pub fn ease(self, t: f32) -> f32 {
+        match self {
+            Self::Constant => 0.0,
+            Self::Linear => t,
+            Self::Smooth => t * t * (3.0 - 2.0 * t),
+        }
+    }
+}
+
+/// A key of a [`YoleckCurve`].
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "bevy_reflect", derive(Reflect))]
+pub struct YoleckCurveKey {
+    pub time: f32,
+    pub value: f32,
+    /// How to go from this key to the next one.
+    #[serde(default)]
+    pub interpolation: YoleckInterpolation,
+}
+
+/// A keyframed curve of `f32` values, for things like difficulty ramps and particle sizes.
+///
+/// Before the first key and after the last key the curve keeps the value of that key. An empty
+/// curve is always 0.
+///
+/// `YoleckAutoEdit` edits it with a plot where keys can be dragged (double-click to add a key,
+/// right-click to remove one). `#[yoleck(range(...))]` on the field limits the values of the keys.
+/// To use a curve as a component of its own, wrap it in a newtype:
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_yoleck::prelude::*;
+/// # use serde::{Deserialize, Serialize};
+/// #[derive(Component, YoleckComponent, YoleckAutoEdit, Serialize, Deserialize, Clone, PartialEq, Default)]
+/// struct DifficultyRamp(#[yoleck(range(0..=10))] YoleckCurve);
+///
+/// fn enemies_count(ramp: &DifficultyRamp, elapsed_secs: f32) -> usize {
+///     ramp.0.value_at(elapsed_secs).round() as usize
+/// }
+/// ```
+///
+/// It also implements Bevy's [`Curve`] trait.
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "bevy_reflect", derive(Reflect))]
+#[serde(from = "Vec<YoleckCurveKey>", into = "Vec<YoleckCurveKey>")]
+pub struct YoleckCurve {
+    /// Sorted by time.
+    keys: Vec<YoleckCurveKey>,
+}
+
+impl YoleckCurve {
+    pub fn from_keys(keys: impl IntoIterator<Item = YoleckCurveKey>) -> Self {
+        let mut keys: Vec<_> = keys.into_iter().collect();
+        keys.sort_by(|a, b| a.time.total_cmp(&b.time));
+        Self { keys }
+    }
+
+    /// The keys, sorted by time.
+    pub fn keys(&self) -> &[YoleckCurveKey] {
+        &self.keys
+    }
+
+    /// A curve with linear interpolation between `(time, value)` points.
+    pub fn linear(points: impl IntoIterator<Item = (f32, f32)>) -> Self {
+        Self::from_keys(points.into_iter().map(|(time, value)| YoleckCurveKey {
+            time,
+            value,
+            interpolation: YoleckInterpolation::Linear,
+        }))
+    }
+
+    pub fn value_at(&self, time: f32) -> f32 {
+        match find_segment(&self.keys, time) {
+            Some((start, end, weight)) => start.value.lerp(end.value, weight),
+            None => 0.0,
+        }
+    }
+}
+
+impl From<Vec<YoleckCurveKey>> for YoleckCurve {
+    fn from(keys: Vec<YoleckCurveKey>) -> Self {
+        Self::from_keys(keys)
+    }
+}
+
+impl From<YoleckCurve> for Vec<YoleckCurveKey> {
+    fn from(curve: YoleckCurve) -> Self {
+        curve.keys
+    }
+}
+
+impl Curve<f32> for YoleckCurve {
+    fn domain(&self) -> Interval {
+        Interval::EVERYWHERE
+    }
+
+    fn sample_unchecked(&self, t: f32) -> f32 {
+        self.value_at(t)
+    }
+}
+
+/// A key of a [`YoleckGradient`].
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "bevy_reflect", derive(Reflect))]
+pub struct YoleckGradientKey {
+    /// Between 0 and 1.
+    pub position: f32,
+    pub color: Color,
+    /// How to go from this key to the next one.
+    #[serde(default)]
+    pub interpolation: YoleckInterpolation,
+}
+
+/// A color gradient over positions from 0 to 1. Colors are mixed in the Oklab color space.
+///
+/// `YoleckAutoEdit` edits it with a color bar and draggable key markers (double-click to add a
+/// key, right-click to remove one). Like [`YoleckCurve`], it can be used as a field or wrapped in
+/// a newtype component, and it implements Bevy's [`Curve`] trait.
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "bevy_reflect", derive(Reflect))]
+#[serde(from = "Vec<YoleckGradientKey>", into = "Vec<YoleckGradientKey>")]
+pub struct YoleckGradient {
+    /// Sorted by position.
+    keys: Vec<YoleckGradientKey>,
+}
+
+impl YoleckGradient {
+    pub fn from_keys(keys: impl IntoIterator<Item = YoleckGradientKey>) -> Self {
+        let mut keys: Vec<_> = keys.into_iter().collect();
+        keys.sort_by(|a, b| a.position.total_cmp(&b.position));
+        Self { keys }
+    }
+
+    /// The keys, sorted by position.
+    pub fn keys(&self) -> &[YoleckGradientKey] {
+        &self.keys
+    }
+
+    /// A gradient with linear interpolation between `(position, color)` points.
+    pub fn linear(points: impl IntoIterator<Item = (f32, Color)>) -> Self {
+        Self::from_keys(
+            points
+                .into_iter()
+                .map(|(position, color)| YoleckGradientKey {
+                    position,
+                    color,
+                    interpolation: YoleckInterpolation::Linear,
+                }),
+        )
+    }
+
+    /// The color at `position`. An empty gradient is white.
+    pub fn color_at(&self, position: f32) -> Color {
+        match find_segment(&self.keys, position) {
+            Some((start, _, weight)) if weight <= 0.0 => start.color,
+            Some((start, end, weight)) => Oklaba::from(start.color)
+                .mix(&Oklaba::from(end.color), weight)
+                .into(),
+            None => Color::WHITE,
+        }
+    }
+}
+
+impl From<Vec<YoleckGradientKey>> for YoleckGradient {
+    fn from(keys: Vec<YoleckGradientKey>) -> Self {
+        Self::from_keys(keys)
+    }
+}
+
+impl From<YoleckGradient> for Vec<YoleckGradientKey> {
+    fn from(gradient: YoleckGradient) -> Self {
+        gradient.keys
+    }
+}
+
+impl Curve<Color> for YoleckGradient {
+    fn domain(&self) -> Interval {
+        Interval::EVERYWHERE
+    }
+
+    fn sample_unchecked(&self, t: f32) -> Color {
+        self.color_at(t)
+    }
+}
+
+trait Keyframe: Copy {
+    fn position(&self) -> f32;
+    fn set_position(&mut self, position: f32);
+    fn interpolation(&mut self) -> &mut YoleckInterpolation;
+}
+
+impl Keyframe for YoleckCurveKey {
+    fn position(&self) -> f32 {
+        self.time
+    }
+
+    fn set_position(&mut self, position: f32) {
+        self.time = position;
+    }
+
+    fn interpolation(&mut self) -> &mut YoleckInterpolation {
+        &mut self.interpolation
+    }
+}
+
+impl Keyframe for YoleckGradientKey {
+    fn position(&self) -> f32 {
+        self.position
+    }
+
+    fn set_position(&mut self, position: f32) {
+        self.position = position;
+    }
+
+    fn interpolation(&mut self) -> &mut YoleckInterpolation {
+        &mut self.interpolation
+    }
+}
+
+/// The keys around `position`, and the weight of the second one.
+fn find_segment<K: Keyframe>(keys: &[K], position: f32) -> Option<(K, K, f32)> {
+    let index = keys.partition_point(|key| key.position() <= position);
+    if index == 0 || index == keys.len() {
+        let key = if index == 0 {
+            keys.first()
+        } else {
+            keys.last()
+        }?;
+        return Some((*key, *key, 0.0));
+    }
+    let (mut start, end) = (keys[index - 1], keys[index]);
+    let span = end.position() - start.position();
+    let progress = if 0.0 < span {
+        ((position - start.position()) / span).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let weight = start.interpolation().ease(progress);
+    Some((start, end, weight))
+}
+
+/// Move the key at `index` to its place after its position was changed, and return its new index.
+fn reposition_key<K: Keyframe>(keys: &mut [K], mut index: usize) -> usize {
+    while 0 < index && keys[index].position() < keys[index - 1].position() {
+        keys.swap(index, index - 1);
+        index -= 1;
+    }
+    while index + 1 < keys.len() && keys[index + 1].position() < keys[index].position() {
+        keys.swap(index, index + 1);
+        index += 1;
+    }
+    index
+}
+
+const KEY_RADIUS: f32 = 4.0;
+const KEY_HIT_DISTANCE: f32 = 8.0;
+
+/// The state of a curve or gradient editor, kept in egui's memory between frames.
+#[derive(Default, Clone, Copy)]
+struct KeysEditorState {
+    selected: Option<usize>,
+    dragged: Option<usize>,
+    /// The plot area is not changed while dragging a key, so that the key stays under the cursor.
+    frozen_bounds: Option<egui::Rect>,
+}
+
+fn nearest_key(
+    screen_positions: impl Iterator<Item = egui::Pos2>,
+    pointer: egui::Pos2,
+) -> Option<usize> {
+    screen_positions
+        .enumerate()
+        .map(|(index, pos)| (index, pos.distance(pointer)))
+        .filter(|(_, distance)| *distance <= KEY_HIT_DISTANCE)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(index, _)| index)
+}
+
+fn interpolation_combo(ui: &mut egui::Ui, interpolation: &mut YoleckInterpolation) -> bool {
+    let mut changed = false;
+    egui::ComboBox::from_id_salt(ui.id().with("yoleck_interpolation"))
+        .selected_text(interpolation.label())
+        .show_ui(ui, |ui| {
+            for option in YoleckInterpolation::ALL {
+                changed |= ui
+                    .selectable_value(interpolation, option, option.label())
+                    .changed();
+            }
+        });
+    changed
+}
+
+/// Handle the mouse interaction with the keys, which is the same for curves and gradients.
+///
+/// `to_screen` and `from_screen` convert between keys and screen positions. `new_key` creates a
+/// key at a screen position.
+fn interact_with_keys<K: Keyframe>(
+    response: &mut egui::Response,
+    state: &mut KeysEditorState,
+    keys: &mut Vec<K>,
+    to_screen: impl Fn(&K) -> egui::Pos2,
+    from_screen: impl Fn(&mut K, egui::Pos2),
+    new_key: impl Fn(egui::Pos2) -> K,
+) {
+    let Some(pointer) = response.interact_pointer_pos() else {
+        return;
+    };
+    let key_under_pointer = nearest_key(keys.iter().map(&to_screen), pointer);
+
+    if response.drag_started() {
+        state.dragged = key_under_pointer;
+        state.selected = key_under_pointer;
+    }
+    if response.dragged()
+        && let Some(index) = state.dragged
+        && index < keys.len()
+    {
+        from_screen(&mut keys[index], pointer);
+        let index = reposition_key(keys, index);
+        state.dragged = Some(index);
+        state.selected = Some(index);
+        response.mark_changed();
+    }
+    if response.double_clicked() && key_under_pointer.is_none() {
+        keys.push(new_key(pointer));
+        let index = keys.len() - 1;
+        state.selected = Some(reposition_key(keys, index));
+        response.mark_changed();
+    } else if response.secondary_clicked()
+        && let Some(index) = key_under_pointer
+    {
+        keys.remove(index);
+        state.selected = None;
+        response.mark_changed();
+    } else if response.clicked() {
+        state.selected = key_under_pointer;
+    }
+}
+
+/// The controls for the selected key under the plot. Returns whether the key was changed.
+fn selected_key_controls<K: Keyframe>(
+    ui: &mut egui::Ui,
+    state: &mut KeysEditorState,
+    keys: &mut Vec<K>,
+    position_range: Option<(f32, f32)>,
+    add_key_controls: impl FnOnce(&mut egui::Ui, &mut K) -> bool,
+) -> bool {
+    let Some(index) = state.selected.filter(|index| *index < keys.len()) else {
+        state.selected = None;
+        return false;
+    };
+    let mut changed = false;
+    let mut removed = false;
+    ui.horizontal(|ui| {
+        let key = &mut keys[index];
+        let mut position = key.position();
+        let mut drag_value = egui::DragValue::new(&mut position).speed(0.01);
+        if let Some((min, max)) = position_range {
+            drag_value = drag_value.range(min..=max);
+        }
+        if ui.add(drag_value).changed() {
+            key.set_position(position);
+            changed = true;
+        }
+        changed |= add_key_controls(ui, key);
+        changed |= interpolation_combo(ui, key.interpolation());
+        removed = ui.button("🗑").on_hover_text("Remove the key").clicked();
+    });
+    if removed {
+        keys.remove(index);
+        state.selected = None;
+        return true;
+    }
+    state.selected = Some(reposition_key(keys, index));
+    changed
+}
+
+const PLOT_HEIGHT: f32 = 120.0;
+
+/// The part of the curve shown in the plot, in `(time, value)` coordinates.
+fn curve_bounds(curve: &YoleckCurve, attrs: &FieldAttrs) -> egui::Rect {
+    let (mut min_time, mut max_time) = curve
+        .keys
+        .iter()
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), key| {
+            (min.min(key.time), max.max(key.time))
+        });
+    if max_time <= min_time {
+        if min_time.is_finite() {
+            max_time = min_time + 1.0;
+        } else {
+            (min_time, max_time) = (0.0, 1.0);
+        }
+    }
+
+    let (min_value, max_value) = if let Some((min, max)) = attrs.range {
+        (min as f32, max as f32)
+    } else {
+        let (min, max) = curve
+            .keys
+            .iter()
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), key| {
+                (min.min(key.value), max.max(key.value))
+            });
+        if min < max {
+            let padding = (max - min) * 0.1;
+            (min - padding, max + padding)
+        } else if min.is_finite() {
+            (min - 1.0, min + 1.0)
+        } else {
+            (0.0, 1.0)
+        }
+    };
+    egui::Rect::from_min_max(
+        egui::pos2(min_time, min_value),
+        egui::pos2(max_time, max_value),
+    )
+}
+
+fn curve_editor(curve: &mut YoleckCurve, ui: &mut egui::Ui, attrs: &FieldAttrs) -> egui::Response {
+    let size = egui::vec2(ui.available_width().max(120.0), PLOT_HEIGHT);
+    let (mut response, painter) = ui.allocate_painter(size, egui::Sense::click_and_drag());
+    let id = response.id;
+    let mut state: KeysEditorState = ui.data(|data| data.get_temp(id)).unwrap_or_default();
+
+    let bounds = state
+        .frozen_bounds
+        .unwrap_or_else(|| curve_bounds(curve, attrs));
+    let plot_rect = response.rect.shrink(KEY_RADIUS * 2.0);
+    let to_screen = |time: f32, value: f32| {
+        egui::pos2(
+            egui::remap(time, bounds.x_range(), plot_rect.x_range()),
+            egui::remap(
+                value,
+                bounds.y_range(),
+                plot_rect.bottom()..=plot_rect.top(),
+            ),
+        )
+    };
+    let from_screen = |pos: egui::Pos2| {
+        let time = egui::remap(pos.x, plot_rect.x_range(), bounds.x_range());
+        let mut value = egui::remap(
+            pos.y,
+            plot_rect.bottom()..=plot_rect.top(),
+            bounds.y_range(),
+        );
+        if let Some((min, max)) = attrs.range {
+            value = value.clamp(min as f32, max as f32);
+        }
+        (time, value)
+    };
+
+    interact_with_keys(
+        &mut response,
+        &mut state,
+        &mut curve.keys,
+        |key| to_screen(key.time, key.value),
+        |key, pos| (key.time, key.value) = from_screen(pos),
+        |pos| {
+            let (time, value) = from_screen(pos);
+            YoleckCurveKey {
+                time,
+                value,
+                interpolation: Default::default(),
+            }
+        },
+    );
+    if response.drag_started() && state.dragged.is_some() {
+        state.frozen_bounds = Some(bounds);
+    }
+    if response.drag_stopped() {
+        state.dragged = None;
+        state.frozen_bounds = None;
+    }
+
+    let visuals = ui.visuals();
+    painter.rect_filled(response.rect, 2.0, visuals.extreme_bg_color);
+    painter.rect_stroke(
+        response.rect,
+        2.0,
+        visuals.widgets.noninteractive.bg_stroke,
+        egui::StrokeKind::Inside,
+    );
+    if bounds.y_range().contains(0.0) {
+        painter.hline(
+            plot_rect.x_range(),
+            to_screen(0.0, 0.0).y,
+            egui::Stroke::new(1.0, visuals.weak_text_color()),
+        );
+    }
+    let font = egui::FontId::monospace(9.0);
+    for (text, pos, align) in [
+        (bounds.max.y, plot_rect.left_top(), egui::Align2::LEFT_TOP),
+        (
+            bounds.min.y,
+            plot_rect.left_bottom(),
+            egui::Align2::LEFT_BOTTOM,
+        ),
+        (
+            bounds.max.x,
+            plot_rect.right_bottom(),
+            egui::Align2::RIGHT_BOTTOM,
+        ),
+    ] {
+        painter.text(
+            pos,
+            align,
+            format!("{text:.2}"),
+            font.clone(),
+            visuals.weak_text_color(),
+        );
+    }
+    if !curve.keys.is_empty() {
+        let points = (0..=plot_rect.width() as usize / 2)
+            .map(|step| {
+                let x = plot_rect.left() + 2.0 * step as f32;
+                let time = egui::remap(x, plot_rect.x_range(), bounds.x_range());
+                to_screen(time, curve.value_at(time))
+            })
+            .collect();
+        painter.add(egui::Shape::line(
+            points,
+            egui::Stroke::new(1.5, visuals.widgets.active.fg_stroke.color),
+        ));
+    }
+    for (index, key) in curve.keys.iter().enumerate() {
+        let color = if state.selected == Some(index) {
+            visuals.selection.stroke.color
+        } else {
+            visuals.widgets.inactive.fg_stroke.color
+        };
+        painter.circle_filled(to_screen(key.time, key.value), KEY_RADIUS, color);
+    }
+
+    if selected_key_controls(ui, &mut state, &mut curve.keys, None, |ui, key| {
+        let mut drag_value = egui::DragValue::new(&mut key.value)
+            .speed(attrs.speed.unwrap_or(0.01))
+            .prefix("value: ");
+        if let Some((min, max)) = attrs.range {
+            drag_value = drag_value.range(min..=max);
+        }
+        ui.add(drag_value).changed()
+    }) {
+        response.mark_changed();
+    }
+
+    ui.data_mut(|data| data.insert_temp(id, state));
+    response
+}
+
+const GRADIENT_BAR_HEIGHT: f32 = 24.0;
+const GRADIENT_MARKER_HEIGHT: f32 = 10.0;
+
+fn to_egui_color(color: Color) -> egui::Color32 {
+    let [r, g, b, a] = color.to_srgba().to_u8_array();
+    egui::Color32::from_rgba_unmultiplied(r, g, b, a)
+}
+
+fn gradient_editor(gradient: &mut YoleckGradient, ui: &mut egui::Ui) -> egui::Response {
+    let size = egui::vec2(
+        ui.available_width().max(120.0),
+        GRADIENT_BAR_HEIGHT + GRADIENT_MARKER_HEIGHT,
+    );
+    let (mut response, painter) = ui.allocate_painter(size, egui::Sense::click_and_drag());
+    let id = response.id;
+    let mut state: KeysEditorState = ui.data(|data| data.get_temp(id)).unwrap_or_default();
+
+    let bar_rect = egui::Rect::from_min_size(
+        response.rect.min + egui::vec2(GRADIENT_MARKER_HEIGHT / 2.0, 0.0),
+        egui::vec2(
+            response.rect.width() - GRADIENT_MARKER_HEIGHT,
+            GRADIENT_BAR_HEIGHT,
+        ),
+    );
+    let to_screen_x = |position: f32| egui::remap(position, 0.0..=1.0, bar_rect.x_range());
+    let from_screen_x = |x: f32| egui::remap_clamp(x, bar_rect.x_range(), 0.0..=1.0);
+
+    let snapshot = gradient.clone();
+    interact_with_keys(
+        &mut response,
+        &mut state,
+        &mut gradient.keys,
+        |key| {
+            egui::pos2(
+                to_screen_x(key.position),
+                bar_rect.bottom() + GRADIENT_MARKER_HEIGHT / 2.0,
+            )
+        },
+        |key, pos| key.position = from_screen_x(pos.x),
+        |pos| {
+            let position = from_screen_x(pos.x);
+            YoleckGradientKey {
+                position,
+                color: snapshot.color_at(position),
+                interpolation: Default::default(),
+            }
+        },
+    );
+    if response.drag_stopped() {
+        state.dragged = None;
+    }
+
+    let visuals = ui.visuals();
+    painter.rect_filled(bar_rect, 0.0, visuals.extreme_bg_color);
+    const SLICE_WIDTH: f32 = 4.0;
+    let mut x = bar_rect.left();
+    while x < bar_rect.right() {
+        let slice = egui::Rect::from_x_y_ranges(
+            x..=(x + SLICE_WIDTH).min(bar_rect.right()),
+            bar_rect.y_range(),
+        );
+        let color = gradient.color_at(from_screen_x(slice.center().x));
+        painter.rect_filled(slice, 0.0, to_egui_color(color));
+        x += SLICE_WIDTH;
+    }
+    painter.rect_stroke(
+        bar_rect,
+        0.0,
+        visuals.widgets.noninteractive.bg_stroke,
+        egui::StrokeKind::Outside,
+    );
+    for (index, key) in gradient.keys.iter().enumerate() {
+        let x = to_screen_x(key.position);
+        let stroke = if state.selected == Some(index) {
+            egui::Stroke::new(2.0, visuals.selection.stroke.color)
+        } else {
+            egui::Stroke::new(1.0, visuals.widgets.inactive.fg_stroke.color)
+        };
+        painter.add(egui::Shape::convex_polygon(
+            vec![
+                egui::pos2(x, bar_rect.bottom()),
+                egui::pos2(
+                    x + GRADIENT_MARKER_HEIGHT / 2.0,
+                    bar_rect.bottom() + GRADIENT_MARKER_HEIGHT,
+                ),
+                egui::pos2(
+                    x - GRADIENT_MARKER_HEIGHT / 2.0,
+                    bar_rect.bottom() + GRADIENT_MARKER_HEIGHT,
+                ),
+            ],
+            to_egui_color(key.color),
+            stroke,
+        ));
+    }
+
+    if selected_key_controls(
+        ui,
+        &mut state,
+        &mut gradient.keys,
+        Some((0.0, 1.0)),
+        |ui, key| {
+            let before = key.color;
+            Color::auto_edit(&mut key.color, ui);
+            key.color != before
+        },
+    ) {
+        response.mark_changed();
+    }
+
+    ui.data_mut(|data| data.insert_temp(id, state));
+    response
+}
+
+const KEYS_EDITOR_HINT: &str =
+    "Drag the keys to move them, double-click to add a key, and right-click a key to remove it";
+
+impl YoleckAutoEdit for YoleckCurve {
+    fn auto_edit(value: &mut Self, ui: &mut egui::Ui) {
+        ui.vertical(|ui| {
+            curve_editor(value, ui, &FieldAttrs::default()).on_hover_text(KEYS_EDITOR_HINT);
+        });
+    }
+
+    fn auto_edit_summary(value: &Self) -> Option<String> {
+        Some(format!("{} keys", value.keys.len()))
+    }
+
+    fn auto_edit_field_impl(value: &mut Self, ui: &mut egui::Ui, label: &str, attrs: &FieldAttrs) {
+        ui.vertical(|ui| {
+            let response = ui.label(label);
+            if let Some(tooltip) = &attrs.tooltip {
+                response.on_hover_text(tooltip);
+            }
+            curve_editor(value, ui, attrs).on_hover_text(KEYS_EDITOR_HINT);
+        });
+    }
+}
+
+impl YoleckAutoEdit for YoleckGradient {
+    fn auto_edit(value: &mut Self, ui: &mut egui::Ui) {
+        ui.vertical(|ui| {
+            gradient_editor(value, ui).on_hover_text(KEYS_EDITOR_HINT);
+        });
+    }
+
+    fn auto_edit_summary(value: &Self) -> Option<String> {
+        Some(format!("{} keys", value.keys.len()))
+    }
+
+    fn auto_edit_field_impl(value: &mut Self, ui: &mut egui::Ui, label: &str, attrs: &FieldAttrs) {
+        ui.vertical(|ui| {
+            let response = ui.label(label);
+            if let Some(tooltip) = &attrs.tooltip {
+                response.on_hover_text(tooltip);
+            }
+            gradient_editor(value, ui).on_hover_text(KEYS_EDITOR_HINT);
+        });
+    }
+}
@@ -7,10 +7,15 @@ use bevy::platform::collections::{HashMap, HashSet};
 use bevy::prelude::*;
 use bevy::state::state::FreelyMutableState;
 use bevy_egui::egui;
+use uuid::Uuid;
 
 use crate::editor_panels::YoleckPanelUi;
 use crate::entity_management::{YoleckEntryHeader, YoleckRawEntry};
-use crate::entity_uuid::YoleckEntityUuid;
+use crate::entity_ref::{
+    YoleckEntityRefHandlers, YoleckEntityReferrer, clear_entity_references,
+    resolve_entity_references,
+};
+use crate::entity_uuid::{YoleckEntityUuid, YoleckUuidRegistry};
 use crate::exclusive_systems::{
     YoleckActiveExclusiveSystem, YoleckEntityCreationExclusiveSystems,
     YoleckExclusiveSystemDirective, YoleckExclusiveSystemsQueue,
@@ -21,7 +26,8 @@ use crate::prelude::{YoleckComponent, YoleckUi};
 use crate::vpeol;
 use crate::{
     BoxedArc, YoleckBelongsToLevel, YoleckEditMarker, YoleckEditSystems,
-    YoleckEntityConstructionSpecs, YoleckInternalSchedule, YoleckManaged, YoleckState,
+    YoleckEntityConstructionSpecs, YoleckEntityLifecycleStatus, YoleckEntityTypeInfo,
+    YoleckInternalSchedule, YoleckManaged, YoleckState,
 };
 
 /// Whether or not the Yoleck editor is active.
@@ -136,12 +142,17 @@ enum YoleckDirectiveInner {
     SpawnEntity {
         level: Entity,
         type_name: String,
+        uuid: Option<Uuid>,
         data: serde_json::Map<String, serde_json::Value>,
         select_created_entity: bool,
         #[allow(clippy::type_complexity)]
         modify_exclusive_systems:
             Option<Box<dyn Sync + Send + Fn(&mut YoleckExclusiveSystemsQueue)>>,
     },
+    ChangeEntityType {
+        entity: Entity,
+        type_name: String,
+    },
 }
 
 /// Event that can be sent to control Yoleck's editor.
@@ -214,16 +225,33 @@ impl YoleckDirective {
         SpawnEntityBuilder {
             level,
             type_name: type_name.to_string(),
+            uuid: None,
             select_created_entity,
             data: Default::default(),
             modify_exclusive_systems: None,
         }
     }
+
+    /// Respawn an entity as a different entity type.
+    ///
+    /// The new entity keeps the name and UUID of the old one, and the data of the components that
+    /// both entity types have (including the optional components that the old entity has). Data
+    /// of components that the new entity type does not have is dropped. References to the old
+    /// entity are resolved to the new one.
+    ///
+    /// Ignored while an exclusive system is active.
+    pub fn change_entity_type(entity: Entity, type_name: impl ToString) -> Self {
+        Self(YoleckDirectiveInner::ChangeEntityType {
+            entity,
+            type_name: type_name.to_string(),
+        })
+    }
 }
 
 pub struct SpawnEntityBuilder {
     level: Entity,
     type_name: String,
+    uuid: Option<Uuid>,
     select_created_entity: bool,
     data: HashMap<Cow<'static, str>, serde_json::Value>,
     #[allow(clippy::type_complexity)]
@@ -258,6 +286,15 @@ impl SpawnEntityBuilder {
         self
     }
 
+    /// Set the UUID of the spawned entity.
+    ///
+    /// Only meaningful for entity types defined [`with_uuid`](crate::YoleckEntityType::with_uuid).
+    /// Otherwise a new UUID will be generated for the entity (if it needs one).
+    pub fn with_uuid(mut self, uuid: Uuid) -> Self {
+        self.uuid = Some(uuid);
+        self
+    }
+
     /// Change the exclusive systems that will be running the entity is spawned.
     pub fn modify_exclusive_systems(
         mut self,
@@ -273,6 +310,7 @@ impl From<SpawnEntityBuilder> for YoleckDirective {
         YoleckDirective(YoleckDirectiveInner::SpawnEntity {
             level: value.level,
             type_name: value.type_name,
+            uuid: value.uuid,
             data: value
                 .data
                 .into_iter()
@@ -321,6 +359,233 @@ impl YoleckPassedData {
     }
 }
 
+/// Entities the user asked to delete while other entities still refer to them.
+///
+/// Deleting them is deferred until the user confirms it in a dialog.
+#[derive(Default, Resource)]
+pub(crate) struct YoleckPendingDeletion(Option<PendingDeletion>);
+
+pub(crate) struct PendingDeletion {
+    entities: Vec<Entity>,
+    referrers: Vec<(YoleckEntityReferrer, String)>,
+}
+
+impl YoleckPendingDeletion {
+    /// Whether a deletion is waiting for the user to confirm it.
+    pub(crate) fn is_pending(&self) -> bool {
+        self.0.is_some()
+    }
+
+    /// Delete the entities, or - if other entities refer to them - ask the user what to do with
+    /// these references.
+    pub fn request<'a>(
+        &mut self,
+        entities: Vec<Entity>,
+        referrers: impl IntoIterator<Item = (YoleckEntityReferrer, &'a YoleckManaged)>,
+        commands: &mut Commands,
+        writer: &mut MessageWriter<YoleckEditorEvent>,
+        yoleck: &mut YoleckState,
+    ) {
+        let referrers: Vec<(YoleckEntityReferrer, String)> = referrers
+            .into_iter()
+            .map(|(referrer, yoleck_managed)| {
+                let caption = format_caption(referrer.entity, yoleck_managed);
+                (referrer, caption)
+            })
+            .collect();
+        if referrers.is_empty() {
+            delete_entities(&entities, commands, writer, yoleck);
+        } else {
+            self.0 = Some(PendingDeletion {
+                entities,
+                referrers,
+            });
+        }
+    }
+
+    fn show_dialog(
+        &mut self,
+        ctx: &egui::Context,
+        commands: &mut Commands,
+        writer: &mut MessageWriter<YoleckEditorEvent>,
+        yoleck: &mut YoleckState,
+    ) {
+        let Some(pending) = self.0.as_ref() else {
+            return;
+        };
+        let mut confirmed = false;
+        let mut cancelled = false;
+        let modal = egui::Modal::new(egui::Id::new("yoleck_pending_deletion")).show(ctx, |ui| {
+            ui.heading("Delete Referenced Entities?");
+            if pending.entities.len() == 1 {
+                ui.label("The entity being deleted is referenced by:");
+            } else {
+                ui.label("The entities being deleted are referenced by:");
+            }
+            egui::ScrollArea::vertical()
+                .max_height(200.0)
+                .show(ui, |ui| {
+                    for (referrer, caption) in pending.referrers.iter() {
+                        ui.label(format!(
+                            "{caption} - {}.{}",
+                            referrer.component_key, referrer.field
+                        ));
+                    }
+                });
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui.button("Delete and Clear References").clicked() {
+                    confirmed = true;
+                }
+                if ui.button("Cancel").clicked() {
+                    cancelled = true;
+                }
+            });
+        });
+        if modal.should_close() {
+            cancelled = true;
+        }
+
+        if confirmed && let Some(pending) = self.0.take() {
+            let referrers: Vec<YoleckEntityReferrer> = pending
+                .referrers
+                .into_iter()
+                .map(|(referrer, _)| referrer)
+                .collect();
+            commands.queue(move |world: &mut World| {
+                clear_entity_references(world, &referrers);
+            });
+            delete_entities(&pending.entities, commands, writer, yoleck);
+        } else if cancelled {
+            self.0 = None;
+        }
+    }
+}
+
+fn delete_entities(
+    entities: &[Entity],
+    commands: &mut Commands,
+    writer: &mut MessageWriter<YoleckEditorEvent>,
+    yoleck: &mut YoleckState,
+) {
+    for entity in entities {
+        commands.entity(*entity).despawn();
+        writer.write(YoleckEditorEvent::EntityDeselected(*entity));
+    }
+    if !entities.is_empty() {
+        yoleck.level_needs_saving = true;
+    }
+}
+
+/// Whether entities of the type can have the component - either always or as an optional
+/// component.
+fn entity_type_has_component(entity_type_info: &YoleckEntityTypeInfo, component: &TypeId) -> bool {
+    entity_type_info.components.contains(component)
+        || entity_type_info.optional_components.contains(component)
+}
+
+/// The keys of the components whose data would be dropped if the entity was changed to
+/// `type_name`.
+fn components_dropped_by_type_change(
+    yoleck_managed: &YoleckManaged,
+    type_name: &str,
+    construction_specs: &YoleckEntityConstructionSpecs,
+) -> Vec<&'static str> {
+    let new_type_info = construction_specs.get_entity_type_info(type_name);
+    let mut dropped: Vec<&'static str> = yoleck_managed
+        .components_data
+        .keys()
+        .filter(|component| {
+            !new_type_info.is_some_and(|info| entity_type_has_component(info, component))
+        })
+        .filter_map(|component| Some(construction_specs.component_handlers.get(component)?.key()))
+        .collect();
+    dropped.sort();
+    dropped
+}
+
+/// Respawn the entity as `type_name`. Also used for recreating entities whose entity type's
+/// components changed, by "changing" them to their own type.
+pub(crate) fn change_entity_type(world: &mut World, entity: Entity, type_name: String) {
+    let Ok(entity_ref) = world.get_entity(entity) else {
+        return;
+    };
+    let (Some(yoleck_managed), Some(belongs_to_level)) = (
+        entity_ref.get::<YoleckManaged>(),
+        entity_ref.get::<YoleckBelongsToLevel>(),
+    ) else {
+        return;
+    };
+    let construction_specs = world.resource::<YoleckEntityConstructionSpecs>();
+    let Some(new_type_info) = construction_specs.get_entity_type_info(&type_name) else {
+        error!("Entity type {:?} is not registered", type_name);
+        return;
+    };
+    // Optional components are only kept if the entity has them, and the new entity type's
+    // required components that the entity does not have get their defaults when it is spawned.
+    let raw_entry = YoleckRawEntry {
+        data: yoleck_managed
+            .components_data
+            .iter()
+            .filter(|(component, _)| entity_type_has_component(new_type_info, component))
+            .filter_map(|(component, component_data)| {
+                let handler = construction_specs.component_handlers.get(component)?;
+                Some((
+                    handler.key().to_owned(),
+                    handler.serialize(component_data.as_ref()),
+                ))
+            })
+            .collect(),
+        header: YoleckEntryHeader {
+            type_name,
+            name: yoleck_managed.name.clone(),
+            uuid: entity_ref
+                .get::<YoleckEntityUuid>()
+                .map(|entity_uuid| entity_uuid.get()),
+        },
+    };
+    let level = belongs_to_level.level;
+    let was_selected = entity_ref.contains::<YoleckEditMarker>();
+    let uuid = raw_entry.header.uuid;
+    let new_entity_types = new_type_info
+        .has_uuid
+        .then(|| (new_type_info.type_names(), new_type_info.marker_types()));
+
+    // The entities that refer to the old entity need to be resolved to the new one.
+    let referrers = if let Some(uuid) = uuid {
+        let mut query = world.query::<(Entity, &YoleckManaged)>();
+        world
+            .resource::<YoleckEntityRefHandlers>()
+            .find_referrers(query.iter(world), &HashSet::from_iter([uuid]))
+    } else {
+        Vec::new()
+    };
+
+    world.entity_mut(entity).despawn();
+    let mut cmd = world.spawn((raw_entry, YoleckBelongsToLevel { level }));
+    let new_entity = cmd.id();
+    if was_selected {
+        cmd.insert(YoleckEditMarker);
+        world.write_message(YoleckEditorEvent::EntityDeselected(entity));
+        world.write_message(YoleckEditorEvent::EntitySelected(new_entity));
+    }
+    if let Some(uuid) = uuid {
+        // The new entity is only registered when its raw entry is processed, which is too late
+        // for resolving the references to it.
+        let mut registry = world.resource_mut::<YoleckUuidRegistry>();
+        match new_entity_types {
+            Some((entity_types, markers)) => {
+                registry.insert(uuid, new_entity, entity_types, markers)
+            }
+            None => registry.remove(uuid),
+        }
+        resolve_entity_references(world, &referrers);
+    }
+    if let Some(mut yoleck) = world.get_resource_mut::<YoleckState>() {
+        yoleck.level_needs_saving = true;
+    }
+}
+
 fn format_caption(entity: Entity, yoleck_managed: &YoleckManaged) -> String {
     if yoleck_managed.name.is_empty() {
         format!("{} {:?}", yoleck_managed.type_name, entity)
@@ -351,18 +616,47 @@ pub fn new_entity_section(
 
     let button_response = ui.button("Add New Entity");
 
+    let mut entity_type_entry = |ui: &mut egui::Ui, entity_type: &YoleckEntityTypeInfo| {
+        let builder =
+            || YoleckDirective::spawn_entity(yoleck.level_being_edited, &entity_type.name, true);
+        if entity_type.presets.is_empty() {
+            if ui.button(entity_type.caption()).clicked() {
+                writer.write(builder().into());
+            }
+            return;
+        }
+        ui.menu_button(entity_type.caption(), |ui| {
+            if ui.button("Default").clicked() {
+                writer.write(builder().into());
+            }
+            for preset in entity_type.presets.iter() {
+                if ui.button(&preset.name).clicked() {
+                    writer.write((preset.apply)(builder()).into());
+                }
+            }
+        });
+    };
+
     egui::Popup::menu(&button_response).show(|ui| {
+        let mut categories: Vec<&str> = Vec::new();
         for entity_type in construction_specs.entity_types.iter() {
-            if ui.button(&entity_type.name).clicked() {
-                writer.write(YoleckDirective(YoleckDirectiveInner::SpawnEntity {
-                    level: yoleck.level_being_edited,
-                    type_name: entity_type.name.clone(),
-                    data: Default::default(),
-                    select_created_entity: true,
-                    modify_exclusive_systems: None,
-                }));
+            if let Some(category) = entity_type.category.as_deref() {
+                if !categories.contains(&category) {
+                    categories.push(category);
+                }
+            } else {
+                entity_type_entry(ui, entity_type);
             }
         }
+        for category in categories {
+            ui.menu_button(category, |ui| {
+                for entity_type in construction_specs.entity_types.iter() {
+                    if entity_type.category.as_deref() == Some(category) {
+                        entity_type_entry(ui, entity_type);
+                    }
+                }
+            });
+        }
     });
     Ok(())
 }
@@ -400,7 +694,10 @@ pub fn entity_selection_section(
         });
         for entity_type in construction_specs.entity_types.iter() {
             let mut should_show = filter_types.contains(&entity_type.name);
-            if ui.checkbox(&mut should_show, &entity_type.name).changed() {
+            if ui
+                .checkbox(&mut should_show, entity_type.caption())
+                .changed()
+            {
                 if should_show {
                     filter_types.insert(entity_type.name.clone());
                 } else {
@@ -419,7 +716,11 @@ pub fn entity_selection_section(
         .collect();
 
     for (entity, yoleck_managed, edit_marker, entity_uuid) in yoleck_managed_query.iter() {
-        if !filter_types.is_empty() && !filter_types.contains(&yoleck_managed.type_name) {
+        if !filter_types.is_empty()
+            && !filter_types
+                .iter()
+                .any(|type_name| yoleck_managed.is_type(type_name))
+        {
             continue;
         }
         if !yoleck_managed.name.contains(filter_custom_name.as_str()) {
@@ -490,11 +791,16 @@ pub fn entity_editing_section(
     world: &mut World,
     mut previously_edited_entity: Local<Option<Entity>>,
     mut new_entity_created_this_frame: Local<bool>,
+    mut cached_referrers: Local<Option<(Entity, Vec<YoleckEntityReferrer>)>>,
     mut system_state: Local<
         Option<
             SystemState<(
                 ResMut<YoleckState>,
-                Query<(Entity, &mut YoleckManaged), With<YoleckEditMarker>>,
+                Query<
+                    (Entity, &mut YoleckManaged, Option<&YoleckEntityUuid>),
+                    With<YoleckEditMarker>,
+                >,
+                Query<(Entity, &YoleckManaged), Without<YoleckEditMarker>>,
                 Query<Entity, With<YoleckEditMarker>>,
                 MessageReader<YoleckDirective>,
                 Commands,
@@ -504,6 +810,13 @@ pub fn entity_editing_section(
                 Option<Res<YoleckActiveExclusiveSystem>>,
                 ResMut<YoleckExclusiveSystemsQueue>,
                 Res<YoleckEntityCreationExclusiveSystems>,
+                Res<YoleckEntityRefHandlers>,
+                ResMut<YoleckPendingDeletion>,
+                Res<YoleckEntityConstructionSpecs>,
+                (
+                    Query<(), (Changed<YoleckManaged>, Without<YoleckEditMarker>)>,
+                    RemovedComponents<YoleckManaged>,
+                ),
             )>,
         >,
     >,
@@ -517,6 +830,7 @@ pub fn entity_editing_section(
             let (
                 mut yoleck,
                 mut yoleck_managed_query,
+                other_entities_query,
                 yoleck_edited_query,
                 mut directives_reader,
                 mut commands,
@@ -526,12 +840,18 @@ pub fn entity_editing_section(
                 active_exclusive_system,
                 mut exclusive_systems_queue,
                 entity_creation_exclusive_systems,
+                entity_ref_handlers,
+                mut pending_deletion,
+                construction_specs,
+                (changed_entities_query, mut removed_entities),
             ) = system_state.get_mut(world)?;
 
             if !matches!(editor_state.get(), YoleckEditorState::EditorActive) {
                 return Ok(());
             }
 
+            pending_deletion.show_dialog(ui.ctx(), &mut commands, &mut writer, &mut yoleck);
+
             let mut data_passed_to_entities: HashMap<Entity, HashMap<TypeId, BoxedArc>> =
                 Default::default();
             for directive in directives_reader.read() {
@@ -606,6 +926,7 @@ pub fn entity_editing_section(
                     YoleckDirectiveInner::SpawnEntity {
                         level,
                         type_name,
+                        uuid,
                         data,
                         select_created_entity,
                         modify_exclusive_systems: override_exclusive_systems,
@@ -618,7 +939,7 @@ pub fn entity_editing_section(
                                 header: YoleckEntryHeader {
                                     type_name: type_name.clone(),
                                     name: "".to_owned(),
-                                    uuid: None,
+                                    uuid: *uuid,
                                 },
                                 data: data.clone(),
                             },
@@ -643,24 +964,131 @@ pub fn entity_editing_section(
                         }
                         yoleck.level_needs_saving = true;
                     }
+                    YoleckDirectiveInner::ChangeEntityType { entity, type_name } => {
+                        if active_exclusive_system.is_some() {
+                            continue;
+                        }
+                        let (entity, type_name) = (*entity, type_name.clone());
+                        commands.queue(move |world: &mut World| {
+                            change_entity_type(world, entity, type_name);
+                        });
+                    }
                 }
             }
 
             let entity_being_edited;
-            if let Ok((entity, mut yoleck_managed)) = yoleck_managed_query.single_mut() {
+            if let Ok((entity, mut yoleck_managed, entity_uuid)) = yoleck_managed_query.single_mut()
+            {
                 entity_being_edited = Some(entity);
+                // Finding the referrers means going over all the entities, so only do it when the
+                // selection or the entities change.
+                let entities_changed =
+                    !changed_entities_query.is_empty() || removed_entities.read().count() > 0;
+                if entities_changed
+                    || cached_referrers
+                        .as_ref()
+                        .is_none_or(|(cached_entity, _)| *cached_entity != entity)
+                {
+                    let referrers = if let Some(entity_uuid) = entity_uuid {
+                        entity_ref_handlers.find_referrers(
+                            other_entities_query.iter(),
+                            &HashSet::from_iter([entity_uuid.get()]),
+                        )
+                    } else {
+                        Vec::new()
+                    };
+                    *cached_referrers = Some((entity, referrers));
+                }
+                let referrers = cached_referrers
+                    .as_ref()
+                    .map(|(_, referrers)| referrers.as_slice())
+                    .unwrap_or_default();
                 ui.horizontal(|ui| {
                     ui.heading(format_caption(entity, &yoleck_managed));
-                    if ui.button("Delete").clicked() {
-                        commands.entity(entity).despawn();
-                        writer.write(YoleckEditorEvent::EntityDeselected(entity));
-                        yoleck.level_needs_saving = true;
+                    if ui
+                        .add_enabled(!pending_deletion.is_pending(), egui::Button::new("Delete"))
+                        .clicked()
+                    {
+                        pending_deletion.request(
+                            vec![entity],
+                            referrers.iter().filter_map(|referrer| {
+                                let (_, yoleck_managed) =
+                                    other_entities_query.get(referrer.entity).ok()?;
+                                Some((referrer.clone(), yoleck_managed))
+                            }),
+                            &mut commands,
+                            &mut writer,
+                            &mut yoleck,
+                        );
+                    }
+                    // Changing the type respawns the entity, which would pull it from under the
+                    // exclusive system.
+                    if active_exclusive_system.is_some() {
+                        return;
                     }
+                    ui.menu_button("Change Type", |ui| {
+                        for entity_type in construction_specs.entity_types.iter() {
+                            if entity_type.name == yoleck_managed.type_name {
+                                continue;
+                            }
+                            let dropped = components_dropped_by_type_change(
+                                &yoleck_managed,
+                                &entity_type.name,
+                                &construction_specs,
+                            );
+                            let mut hover_text = Vec::new();
+                            if !dropped.is_empty() {
+                                hover_text.push(format!("Drops: {}", dropped.join(", ")));
+                            }
+                            if entity_uuid.is_some() && !entity_type.has_uuid {
+                                hover_text.push("Drops the UUID".to_owned());
+                            }
+                            let mut response = ui.button(entity_type.caption());
+                            if !hover_text.is_empty() {
+                                response = response.on_hover_text(hover_text.join("\n"));
+                            }
+                            if response.clicked() {
+                                let (entity, type_name) = (entity, entity_type.name.clone());
+                                commands.queue(move |world: &mut World| {
+                                    change_entity_type(world, entity, type_name);
+                                });
+                            }
+                        }
+                    });
                 });
                 ui.horizontal(|ui| {
                     ui.label("Custom Name:");
                     ui.text_edit_singleline(&mut yoleck_managed.name);
                 });
+                if !referrers.is_empty() {
+                    let mut select_referrer = None;
+                    egui::CollapsingHeader::new(format!("Referenced by ({})", referrers.len()))
+                        .id_salt("yoleck_referenced_by")
+                        .show(ui, |ui| {
+                            for referrer in referrers.iter() {
+                                let Ok((_, referrer_managed)) =
+                                    other_entities_query.get(referrer.entity)
+                                else {
+                                    continue;
+                                };
+                                let caption = format!(
+                                    "{} - {}.{}",
+                                    format_caption(referrer.entity, referrer_managed),
+                                    referrer.component_key,
+                                    referrer.field,
+                                );
+                                if ui.selectable_label(false, caption).clicked() {
+                                    select_referrer = Some(referrer.entity);
+                                }
+                            }
+                        });
+                    if let Some(referrer_entity) = select_referrer {
+                        commands.entity(entity).remove::<YoleckEditMarker>();
+                        writer.write(YoleckEditorEvent::EntityDeselected(entity));
+                        commands.entity(referrer_entity).insert(YoleckEditMarker);
+                        writer.write(YoleckEditorEvent::EntitySelected(referrer_entity));
+                    }
+                }
             } else {
                 entity_being_edited = None;
             }
@@ -747,8 +1175,11 @@ pub fn entity_editing_section(
 
         if should_run_regular_systems {
             world.resource_scope(|world, mut yoleck_edit_systems: Mut<YoleckEditSystems>| {
-                yoleck_edit_systems.run_systems(world);
+                let components_in_panels = component_panels(world, &yoleck_edit_systems);
+                yoleck_edit_systems.run_systems(world, &components_in_panels);
             });
+            #[cfg(feature = "vpeol")]
+            crate::auto_edit::discard_entity_ref_pick_result(ui.ctx());
         }
         let YoleckUi(content_ui) = world
             .remove_resource()
@@ -762,3 +1193,249 @@ pub fn entity_editing_section(
         Ok(())
     })
 }
+
+enum ComponentPanelAction {
+    Reset,
+    Copy,
+    Paste(serde_json::Value),
+    Add,
+    Remove,
+}
+
+/// The last component values copied from a component panel, as `(key, values)`.
+const COMPONENT_CLIPBOARD_ID: &str = "yoleck_component_clipboard";
+
+/// Set while the edit systems of a component run inside its panel.
+const IN_COMPONENT_PANEL_ID: &str = "yoleck_in_component_panel";
+
+fn component_header(key: &str, entities_count: usize) -> String {
+    if entities_count == 1 {
+        key.to_owned()
+    } else {
+        format!("{key} ({entities_count} entities)")
+    }
+}
+
+/// Show the UI of a component's edit system. Inside the component's panel it is shown as is, and
+/// outside of it (when the entity type does not register the component, so it gets no panel) it is
+/// grouped under a header with the component's key.
+pub(crate) fn component_edit_ui<R>(
+    ui: &mut egui::Ui,
+    key: &str,
+    entities_count: usize,
+    add_contents: impl FnOnce(&mut egui::Ui) -> R,
+) -> R {
+    let in_component_panel = ui.ctx().data(|data| {
+        data.get_temp::<bool>(egui::Id::new(IN_COMPONENT_PANEL_ID))
+            .unwrap_or(false)
+    });
+    if in_component_panel {
+        return add_contents(ui);
+    }
+    ui.group(|ui| {
+        ui.label(egui::RichText::new(component_header(key, entities_count)).strong());
+        ui.separator();
+        add_contents(ui)
+    })
+    .inner
+}
+
+/// Show a collapsible panel for each component that all the edited entities have, and run the
+/// component's edit systems inside it. Returns the components that got panels.
+///
+/// Optional components (see
+/// [`YoleckEntityType::with_optional`](crate::prelude::YoleckEntityType::with_optional)) can be
+/// removed from the panel's context menu, and added with a menu under the panels.
+fn component_panels(world: &mut World, edit_systems: &YoleckEditSystems) -> HashSet<TypeId> {
+    let edited_entities: Vec<(Entity, String)> = world
+        .query_filtered::<(Entity, &YoleckManaged), With<YoleckEditMarker>>()
+        .iter(world)
+        .map(|(entity, yoleck_managed)| (entity, yoleck_managed.type_name.clone()))
+        .collect();
+    let Some((_, first_type_name)) = edited_entities.first() else {
+        return HashSet::new();
+    };
+    let entities: Vec<Entity> = edited_entities.iter().map(|(entity, _)| *entity).collect();
+    // The components of all the edited entities' types, as `(type, key, is_optional)`.
+    let common_components: Vec<(TypeId, &'static str, bool)> = {
+        let construction_specs = world.resource::<YoleckEntityConstructionSpecs>();
+        let type_infos: Vec<_> = edited_entities
+            .iter()
+            .filter_map(|(_, type_name)| construction_specs.get_entity_type_info(type_name))
+            .collect();
+        construction_specs
+            .get_entity_type_info(first_type_name)
+            .map(|info| info.components.as_slice())
+            .unwrap_or_default()
+            .iter()
+            .filter(|component_type| {
+                type_infos
+                    .iter()
+                    .all(|info| info.components.contains(component_type))
+            })
+            .filter_map(|component_type| {
+                let handler = construction_specs.component_handlers.get(component_type)?;
+                let is_optional = type_infos
+                    .iter()
+                    .all(|info| info.optional_components.contains(component_type));
+                Some((*component_type, handler.key(), is_optional))
+            })
+            .collect()
+    };
+    let (components, addable_components): (Vec<_>, Vec<_>) = common_components
+        .into_iter()
+        .partition(|(component_type, _, is_optional)| {
+            !is_optional
+                || entities
+                    .iter()
+                    .all(|entity| world.entity(*entity).contains_type_id(*component_type))
+        });
+
+    let YoleckUi(mut ui) = world
+        .remove_resource()
+        .expect("The YoleckUi resource was put in the world by entity_editing_section");
+    let mut actions = Vec::new();
+    for (component_type, key, is_optional) in components.iter().copied() {
+        let header = component_header(key, entities.len());
+        let systems = edit_systems.component_edit_systems(component_type);
+        let header_response = if systems.is_empty() {
+            ui.add(
+                egui::Label::new(egui::RichText::new(header).strong()).sense(egui::Sense::click()),
+            )
+        } else {
+            egui::CollapsingHeader::new(egui::RichText::new(header).strong())
+                .id_salt(("yoleck_component_panel", key))
+                .default_open(true)
+                .show(&mut ui, |ui| {
+                    let child = ui.new_child(
+                        egui::UiBuilder::new()
+                            .max_rect(ui.available_rect_before_wrap())
+                            .layout(*ui.layout()),
+                    );
+                    let in_component_panel_id = egui::Id::new(IN_COMPONENT_PANEL_ID);
+                    child
+                        .ctx()
+                        .data_mut(|data| data.insert_temp(in_component_panel_id, true));
+                    world.insert_resource(YoleckUi(child));
+                    YoleckEditSystems::run(systems, world);
+                    let YoleckUi(child) = world
+                        .remove_resource()
+                        .expect("The YoleckUi resource was put in the world by this function");
+                    child
+                        .ctx()
+                        .data_mut(|data| data.remove::<bool>(in_component_panel_id));
+                    ui.advance_cursor_after_rect(child.min_rect());
+                })
+                .header_response
+        };
+        header_response.context_menu(|ui| {
+            if ui.button("Reset to default").clicked() {
+                actions.push((component_type, ComponentPanelAction::Reset));
+            }
+            if ui.button("Copy component values").clicked() {
+                actions.push((component_type, ComponentPanelAction::Copy));
+            }
+            let copied = ui.ctx().data(|data| {
+                data.get_temp::<(String, serde_json::Value)>(egui::Id::new(COMPONENT_CLIPBOARD_ID))
+            });
+            let copied = copied.filter(|(copied_key, _)| copied_key == key);
+            if ui
+                .add_enabled(
+                    copied.is_some(),
+                    egui::Button::new("Paste component values"),
+                )
+                .clicked()
+                && let Some((_, values)) = copied
+            {
+                actions.push((component_type, ComponentPanelAction::Paste(values)));
+            }
+            if is_optional && ui.button("Remove component").clicked() {
+                actions.push((component_type, ComponentPanelAction::Remove));
+            }
+        });
+    }
+    if !addable_components.is_empty() {
+        ui.menu_button("Add Component", |ui| {
+            for (component_type, key, _) in addable_components.iter() {
+                if ui.button(*key).clicked() {
+                    actions.push((*component_type, ComponentPanelAction::Add));
+                }
+            }
+        });
+    }
+
+    world.resource_scope(
+        |world, construction_specs: Mut<YoleckEntityConstructionSpecs>| {
+            for (component_type, action) in actions {
+                let Some(handler) = construction_specs.component_handlers.get(&component_type)
+                else {
+                    continue;
+                };
+                let type_default = |type_name: &str| {
+                    construction_specs
+                        .get_entity_type_info(type_name)?
+                        .defaults
+                        .get(handler.key())
+                        .cloned()
+                };
+                match action {
+                    ComponentPanelAction::Reset => {
+                        for (entity, type_name) in edited_entities.iter() {
+                            handler.replace_in_entity(
+                                type_default(type_name),
+                                &mut world.entity_mut(*entity),
+                            );
+                        }
+                    }
+                    ComponentPanelAction::Copy => {
+                        let Some(values) = handler.serialize_from_entity(world.entity(entities[0]))
+                        else {
+                            continue;
+                        };
+                        ui.ctx().copy_text(values.to_string());
+                        ui.ctx().data_mut(|data| {
+                            data.insert_temp(
+                                egui::Id::new(COMPONENT_CLIPBOARD_ID),
+                                (handler.key().to_owned(), values),
+                            )
+                        });
+                    }
+                    ComponentPanelAction::Paste(values) => {
+                        for entity in entities.iter() {
+                            handler.replace_in_entity(
+                                Some(values.clone()),
+                                &mut world.entity_mut(*entity),
+                            );
+                        }
+                    }
+                    ComponentPanelAction::Add => {
+                        for (entity, type_name) in edited_entities.iter() {
+                            let mut entity = world.entity_mut(*entity);
+                            if !entity.contains_type_id(component_type) {
+                                handler.replace_in_entity(type_default(type_name), &mut entity);
+                            }
+                        }
+                    }
+                    ComponentPanelAction::Remove => {
+                        for entity in entities.iter() {
+                            let mut entity = world.entity_mut(*entity);
+                            handler.remove_from_entity(&mut entity);
+                            // Updating the data from the components only handles the components
+                            // that exist.
+                            if let Some(mut yoleck_managed) = entity.get_mut::<YoleckManaged>() {
+                                yoleck_managed.components_data.remove(&component_type);
+                                yoleck_managed.lifecycle_status =
+                                    YoleckEntityLifecycleStatus::JustChanged;
+                            }
+                        }
+                    }
+                }
+            }
+        },
+    );
+    world.insert_resource(YoleckUi(ui));
+    components
+        .into_iter()
+        .map(|(component_type, _, _)| component_type)
+        .collect()
+}
@@ -175,6 +175,7 @@ impl FromWorld for YoleckEditorTopPanelSections {
         Self(vec![
             world.register_system(crate::level_files_manager::level_files_manager_top_section),
             world.register_system(crate::level_files_manager::playtest_buttons_section),
+            world.register_system(crate::validation::validation_problems_top_section),
         ])
     }
 }
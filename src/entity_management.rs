@@ -88,7 +88,12 @@ pub(crate) fn yoleck_process_raw_entries(
             if entity_type_info.has_uuid {
                 let uuid = raw_entry.header.uuid.unwrap_or_else(Uuid::new_v4);
                 cmd.insert(YoleckEntityUuid(uuid));
-                uuid_registry.0.insert(uuid, cmd.id());
+                uuid_registry.insert(
+                    uuid,
+                    cmd.id(),
+                    entity_type_info.type_names(),
+                    entity_type_info.marker_types(),
+                );
             }
             for component_name in entity_type_info.components.iter() {
                 let Some(handler) = construction_specs.component_handlers.get(component_name)
@@ -100,6 +105,15 @@ pub(crate) fn yoleck_process_raw_entries(
                     .data
                     .get_mut(handler.key())
                     .map(|component_data| component_data.take());
+                if raw_component_data.is_none()
+                    && entity_type_info
+                        .optional_components
+                        .contains(component_name)
+                {
+                    continue;
+                }
+                let raw_component_data = raw_component_data
+                    .or_else(|| entity_type_info.defaults.get(handler.key()).cloned());
                 handler.init_in_entity(raw_component_data, &mut cmd, &mut components_data);
             }
             for dlg in entity_type_info.on_init.iter() {
@@ -114,6 +128,10 @@ pub(crate) fn yoleck_process_raw_entries(
             type_name: raw_entry.header.type_name.to_owned(),
             lifecycle_status: YoleckEntityLifecycleStatus::JustCreated,
             components_data,
+            base_types: construction_specs
+                .get_entity_type_info(&raw_entry.header.type_name)
+                .map(|entity_type_info| entity_type_info.base_types.clone())
+                .unwrap_or_default(),
         });
     }
 }
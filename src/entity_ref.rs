@@ -1,6 +1,8 @@
-use std::any::TypeId;
+use std::any::{Any, TypeId};
+use std::marker::PhantomData;
 
 use bevy::ecs::component::Mutable;
+use bevy::platform::collections::{HashMap, HashSet};
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -8,6 +10,7 @@ use uuid::Uuid;
 use crate::YoleckManaged;
 use crate::entity_uuid::YoleckUuidRegistry;
 use crate::errors::YoleckEntityRefCannotBeResolved;
+use crate::specs_registration::YoleckComponent;
 
 /// A reference to another Yoleck entity, stored by UUID for persistence.
 ///
@@ -54,6 +57,9 @@ use crate::errors::YoleckEntityRefCannotBeResolved;
 ///     target: YoleckEntityRef,
 /// }
 /// ```
+///
+/// See also [`YoleckEntityRefTo`] for references whose entity type is specified by a type rather
+/// than a string.
 #[derive(Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Default, Debug)]
 pub struct YoleckEntityRef {
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -131,28 +137,254 @@ impl YoleckEntityRef {
     }
 }
 
+/// A [`YoleckEntityRef`] that can only point at entities that have the marker component `T`.
+///
+/// The marker is a component that Yoleck inserts to the entities of the entity types it can point
+/// at - either a [`YoleckComponent`] registered with
+/// [`with`](crate::prelude::YoleckEntityType::with) (and not as optional), or a component
+/// registered with [`with_marker`](crate::prelude::YoleckEntityType::with_marker). Unlike
+/// `#[yoleck(entity_ref = "...")]`, the editor filters the candidates by the marker, so fields
+/// that refer to it don't have to spell the names of the entity types, and the game can query it
+/// on the referenced entity.
+///
+/// `YoleckEntityRefTo` derefs to [`YoleckEntityRef`], and is saved to the level file the same way
+/// (so changing a field from one to the other does not require upgrading the level files). Its
+/// [`resolve`](Self::resolve) method shadows the one of `YoleckEntityRef`, so
+/// [`entity`](YoleckEntityRef::entity) only returns entities with the marker.
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_yoleck::prelude::*;
+/// # use serde::{Deserialize, Serialize};
+/// #[derive(Component, YoleckComponent, Serialize, Deserialize, Clone, PartialEq, Default)]
+/// struct Planet {
+///     radius: f32,
+/// }
+///
+/// #[derive(Component, YoleckComponent, YoleckAutoEdit, Serialize, Deserialize, Clone, PartialEq, Default)]
+/// struct LaserPointer {
+///     target: YoleckEntityRefTo<Planet>,
+/// }
+///
+/// fn aim_laser_pointers(
+///     laser_pointers_query: Query<&LaserPointer>,
+///     planets_query: Query<(&Planet, &GlobalTransform)>,
+/// ) {
+///     for laser_pointer in laser_pointers_query.iter() {
+///         let Some((planet, transform)) = laser_pointer
+///             .target
+///             .entity()
+///             .and_then(|entity| planets_query.get(entity).ok())
+///         else {
+///             continue;
+///         };
+///         // ...
+///     }
+/// }
+///
+/// # let mut app = App::new();
+/// app.add_yoleck_entity_type({
+///     YoleckEntityType::new("Planet")
+///         .with_uuid()
+///         .with::<Planet>()
+/// });
+/// ```
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct YoleckEntityRefTo<T: Component> {
+    entity_ref: YoleckEntityRef,
+    #[serde(skip)]
+    _phantom_data: PhantomData<fn() -> T>,
+}
+
+impl<T: Component> YoleckEntityRefTo<T> {
+    pub fn new() -> Self {
+        YoleckEntityRef::new().into()
+    }
+
+    pub fn from_uuid(uuid: Uuid) -> Self {
+        YoleckEntityRef::from_uuid(uuid).into()
+    }
+
+    /// The untyped reference.
+    pub fn as_untyped(&self) -> &YoleckEntityRef {
+        &self.entity_ref
+    }
+
+    /// The untyped reference.
+    pub fn as_untyped_mut(&mut self) -> &mut YoleckEntityRef {
+        &mut self.entity_ref
+    }
+
+    /// Like [`YoleckEntityRef::resolve`], but only resolves to entities that have the marker `T`.
+    /// A reference to an entity without it is left unresolved, and returns an error.
+    pub fn resolve(
+        &mut self,
+        registry: &YoleckUuidRegistry,
+    ) -> Result<(), YoleckEntityRefCannotBeResolved> {
+        let entity_ref = &mut self.entity_ref;
+        if let Some(uuid) = entity_ref.uuid {
+            entity_ref.resolved = registry.get_with_marker::<T>(uuid);
+            if entity_ref.resolved.is_none() {
+                return Err(YoleckEntityRefCannotBeResolved { uuid });
+            }
+        } else {
+            entity_ref.resolved = None;
+        }
+        Ok(())
+    }
+}
+
+impl<T: Component> From<YoleckEntityRef> for YoleckEntityRefTo<T> {
+    fn from(entity_ref: YoleckEntityRef) -> Self {
+        Self {
+            entity_ref,
+            _phantom_data: PhantomData,
+        }
+    }
+}
+
+impl<T: Component> std::ops::Deref for YoleckEntityRefTo<T> {
+    type Target = YoleckEntityRef;
+
+    fn deref(&self) -> &Self::Target {
+        &self.entity_ref
+    }
+}
+
+impl<T: Component> std::ops::DerefMut for YoleckEntityRefTo<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.entity_ref
+    }
+}
+
+// Implemented manually because the derives would require `T` to implement these traits too.
+
+impl<T: Component> Default for YoleckEntityRefTo<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Component> Clone for YoleckEntityRefTo<T> {
+    fn clone(&self) -> Self {
+        self.entity_ref.clone().into()
+    }
+}
+
+impl<T: Component> PartialEq for YoleckEntityRefTo<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.entity_ref == other.entity_ref
+    }
+}
+
+impl<T: Component> Eq for YoleckEntityRefTo<T> {}
+
+impl<T: Component> std::hash::Hash for YoleckEntityRefTo<T> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.entity_ref.hash(state);
+    }
+}
+
+impl<T: Component> std::fmt::Debug for YoleckEntityRefTo<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple(&format!(
+            "YoleckEntityRefTo<{}>",
+            std::any::type_name::<T>()
+        ))
+        .field(&self.entity_ref)
+        .finish()
+    }
+}
+
 pub trait YoleckEntityRefAccessor: Sized + Send + Sync + 'static {
     fn entity_ref_fields() -> &'static [(&'static str, Option<&'static str>)];
+    /// Read access to an entity reference field, used for finding the entities that refer to an
+    /// entity. Returns `None` when the component does not have the field at the moment - e.g.
+    /// when it is an enum whose current variant does not have it.
+    fn get_entity_ref(&self, field_name: &str) -> Option<&YoleckEntityRef>;
     fn get_entity_ref_mut(&mut self, field_name: &str) -> &mut YoleckEntityRef;
+    /// Like [`get_entity_ref_mut`](Self::get_entity_ref_mut), but returns `None` when the
+    /// component does not have the field at the moment - e.g. when it is an enum whose current
+    /// variant does not have it.
+    fn try_get_entity_ref_mut(&mut self, field_name: &str) -> Option<&mut YoleckEntityRef> {
+        Some(self.get_entity_ref_mut(field_name))
+    }
+    /// The type ID and the name of the marker of a [`YoleckEntityRefTo`] field, or `None` for
+    /// untyped fields.
+    fn entity_ref_marker(field_name: &str) -> Option<(TypeId, &'static str)> {
+        let _ = field_name;
+        None
+    }
     // TODO: make this more versatile
     fn resolve_entity_refs(&mut self, registry: &YoleckUuidRegistry);
 }
 
-pub(crate) fn validate_entity_ref_requirements_for<T: YoleckEntityRefAccessor>(
-    construction_specs: &crate::YoleckEntityConstructionSpecs,
+/// Check, whenever the entity types change, that the entity types and markers that reference
+/// fields are restricted to (by `#[yoleck(entity_ref = "...")]` or by the marker of a
+/// [`YoleckEntityRefTo`]) exist and can be referenced.
+///
+/// Waits for the entity type definition files to load, since they may define these entity types.
+pub(crate) fn validate_entity_ref_targets(
+    handlers: Res<YoleckEntityRefHandlers>,
+    construction_specs: Res<crate::YoleckEntityConstructionSpecs>,
+    definitions_handles: Res<crate::YoleckEntityTypeDefinitionsHandles>,
+    definitions_assets: Res<Assets<crate::prelude::YoleckEntityTypeDefinitions>>,
 ) {
-    for (field_name, filter) in T::entity_ref_fields() {
-        if let Some(required_entity_type) = filter
-            && let Some(entity_type_info) =
-                construction_specs.get_entity_type_info(required_entity_type)
-            && !entity_type_info.has_uuid
-        {
-            error!(
-                "Component '{}' field '{}' requires entity type '{}' to have UUID.",
-                std::any::type_name::<T>(),
-                field_name,
-                required_entity_type
-            );
+    if !definitions_handles
+        .0
+        .iter()
+        .all(|handle| definitions_assets.contains(handle))
+    {
+        return;
+    }
+    for handler in handlers.0.values() {
+        for (field_name, filter) in handler.entity_ref_fields() {
+            if let Some((marker, marker_name)) = handler.entity_ref_marker(field_name) {
+                let mut marked_entity_types = construction_specs
+                    .entity_types
+                    .iter()
+                    .filter(|entity_type| entity_type.marker_types().contains(&marker))
+                    .peekable();
+                if marked_entity_types.peek().is_none() {
+                    error!(
+                        "Component '{}' field '{}' refers to entities marked with '{}', but no \
+                         entity type has that marker",
+                        handler.component_name(),
+                        field_name,
+                        marker_name
+                    );
+                } else if !marked_entity_types.any(|entity_type| entity_type.has_uuid) {
+                    error!(
+                        "Component '{}' field '{}' refers to entities marked with '{}', but none \
+                         of the entity types with that marker have UUID.",
+                        handler.component_name(),
+                        field_name,
+                        marker_name
+                    );
+                }
+            }
+            let Some(required_entity_type) = filter else {
+                continue;
+            };
+            match construction_specs.get_entity_type_info(required_entity_type) {
+                Some(entity_type_info) => {
+                    if !entity_type_info.has_uuid {
+                        error!(
+                            "Component '{}' field '{}' requires entity type '{}' to have UUID.",
+                            handler.component_name(),
+                            field_name,
+                            required_entity_type
+                        );
+                    }
+                }
+                None => error!(
+                    "Component '{}' field '{}' refers to entity type '{}', which is not registered",
+                    handler.component_name(),
+                    field_name,
+                    required_entity_type
+                ),
+            }
         }
     }
 }
@@ -172,3 +404,216 @@ pub fn resolve_entity_refs<
         }
     }
 }
+
+/// An entity reference field that points at some entity.
+#[derive(Debug, Clone)]
+pub(crate) struct YoleckEntityReferrer {
+    /// The entity that holds the reference.
+    pub entity: Entity,
+    pub component: TypeId,
+    pub component_key: &'static str,
+    pub field: &'static str,
+}
+
+pub(crate) trait YoleckEntityRefHandler: 'static + Sync + Send {
+    fn key(&self) -> &'static str;
+    fn component_name(&self) -> &'static str;
+    fn entity_ref_fields(&self) -> &'static [(&'static str, Option<&'static str>)];
+    fn entity_ref_marker(&self, field_name: &str) -> Option<(TypeId, &'static str)>;
+    fn references(&self, component: &dyn Any) -> Vec<(&'static str, Uuid)>;
+    fn clear_reference(&self, world: &mut World, entity: Entity, field_name: &str);
+    fn resolve_references(&self, world: &mut World, entity: Entity);
+    #[cfg(feature = "vpeol")]
+    fn remap_references(
+        &self,
+        component_data: &mut serde_json::Value,
+        uuid_mapping: &HashMap<Uuid, Uuid>,
+    );
+}
+
+struct YoleckEntityRefHandlerImpl<T: YoleckComponent + YoleckEntityRefAccessor> {
+    _phantom_data: PhantomData<T>,
+}
+
+impl<T: YoleckComponent + YoleckEntityRefAccessor> YoleckEntityRefHandler
+    for YoleckEntityRefHandlerImpl<T>
+{
+    fn key(&self) -> &'static str {
+        T::KEY
+    }
+
+    fn component_name(&self) -> &'static str {
+        std::any::type_name::<T>()
+    }
+
+    fn entity_ref_fields(&self) -> &'static [(&'static str, Option<&'static str>)] {
+        T::entity_ref_fields()
+    }
+
+    fn entity_ref_marker(&self, field_name: &str) -> Option<(TypeId, &'static str)> {
+        T::entity_ref_marker(field_name)
+    }
+
+    fn references(&self, component: &dyn Any) -> Vec<(&'static str, Uuid)> {
+        let Some(component) = component.downcast_ref::<T>() else {
+            return Vec::new();
+        };
+        T::entity_ref_fields()
+            .iter()
+            .filter_map(|(field_name, _)| {
+                let uuid = component.get_entity_ref(field_name)?.uuid()?;
+                Some((*field_name, uuid))
+            })
+            .collect()
+    }
+
+    fn clear_reference(&self, world: &mut World, entity: Entity, field_name: &str) {
+        if let Some(mut component) = world.get_mut::<T>(entity)
+            && let Some(entity_ref) = component.try_get_entity_ref_mut(field_name)
+        {
+            entity_ref.clear();
+        }
+    }
+
+    fn resolve_references(&self, world: &mut World, entity: Entity) {
+        world.resource_scope(|world, registry: Mut<YoleckUuidRegistry>| {
+            if let Some(mut component) = world.get_mut::<T>(entity) {
+                component.resolve_entity_refs(registry.as_ref());
+            }
+            if let Some(mut managed) = world.get_mut::<YoleckManaged>(entity)
+                && let Some(data) = managed.components_data.get_mut(&TypeId::of::<T>())
+                && let Some(data) = data.downcast_mut::<T>()
+            {
+                data.resolve_entity_refs(registry.as_ref());
+            }
+        });
+    }
+
+    #[cfg(feature = "vpeol")]
+    fn remap_references(
+        &self,
+        component_data: &mut serde_json::Value,
+        uuid_mapping: &HashMap<Uuid, Uuid>,
+    ) {
+        let mut component: T = match serde_json::from_value(component_data.clone()) {
+            Ok(component) => component,
+            Err(err) => {
+                error!("Cannot remap references in {}: {err}", T::KEY);
+                return;
+            }
+        };
+        let mut changed = false;
+        for (field_name, _) in T::entity_ref_fields() {
+            let Some(entity_ref) = component.try_get_entity_ref_mut(field_name) else {
+                continue;
+            };
+            if let Some(new_uuid) = entity_ref.uuid().and_then(|uuid| uuid_mapping.get(&uuid)) {
+                entity_ref.set(*new_uuid);
+                changed = true;
+            }
+        }
+        if changed {
+            *component_data = serde_json::to_value(component).expect("should always work");
+        }
+    }
+}
+
+/// The components that have [`YoleckEntityRef`] fields, so that Yoleck can find references to
+/// entities.
+#[derive(Default, Resource)]
+pub(crate) struct YoleckEntityRefHandlers(HashMap<TypeId, Box<dyn YoleckEntityRefHandler>>);
+
+impl YoleckEntityRefHandlers {
+    pub fn register<T: YoleckComponent + YoleckEntityRefAccessor>(&mut self) {
+        if T::entity_ref_fields().is_empty() {
+            return;
+        }
+        self.0.insert(
+            TypeId::of::<T>(),
+            Box::new(YoleckEntityRefHandlerImpl::<T> {
+                _phantom_data: PhantomData,
+            }),
+        );
+    }
+
+    /// All the entity reference fields of an entity that are set, as `(component_type,
+    /// component_key, field, target)`.
+    pub fn references_of(
+        &self,
+        yoleck_managed: &YoleckManaged,
+    ) -> Vec<(TypeId, &'static str, &'static str, Uuid)> {
+        let mut references = Vec::new();
+        for (component_type, component_data) in yoleck_managed.components_data.iter() {
+            let Some(handler) = self.0.get(component_type) else {
+                continue;
+            };
+            for (field, uuid) in handler.references(component_data.as_ref()) {
+                references.push((*component_type, handler.key(), field, uuid));
+            }
+        }
+        references
+    }
+
+    /// Rewrite the entity reference fields in the raw data of an entity, replacing the UUIDs that
+    /// appear as keys in `uuid_mapping` with the matching values. References to other UUIDs are
+    /// left untouched.
+    #[cfg(feature = "vpeol")]
+    pub fn remap_references_in_raw_data(
+        &self,
+        data: &mut serde_json::Map<String, serde_json::Value>,
+        uuid_mapping: &HashMap<Uuid, Uuid>,
+    ) {
+        for handler in self.0.values() {
+            if let Some(component_data) = data.get_mut(handler.key()) {
+                handler.remap_references(component_data, uuid_mapping);
+            }
+        }
+    }
+
+    /// Find all the entity reference fields, among the given entities, that point at any of the
+    /// `targets`.
+    pub fn find_referrers<'a>(
+        &self,
+        entities: impl IntoIterator<Item = (Entity, &'a YoleckManaged)>,
+        targets: &HashSet<Uuid>,
+    ) -> Vec<YoleckEntityReferrer> {
+        let mut referrers = Vec::new();
+        for (entity, yoleck_managed) in entities {
+            for (component, component_key, field, uuid) in self.references_of(yoleck_managed) {
+                if targets.contains(&uuid) {
+                    referrers.push(YoleckEntityReferrer {
+                        entity,
+                        component,
+                        component_key,
+                        field,
+                    });
+                }
+            }
+        }
+        referrers.sort_by_key(|referrer| (referrer.entity, referrer.component_key, referrer.field));
+        referrers
+    }
+}
+
+/// Clear the reference fields. Meant to be used in a command, so that the cleared references will
+/// be picked up by Yoleck and saved to the level.
+pub(crate) fn clear_entity_references(world: &mut World, referrers: &[YoleckEntityReferrer]) {
+    world.resource_scope(|world, handlers: Mut<YoleckEntityRefHandlers>| {
+        for referrer in referrers {
+            if let Some(handler) = handlers.0.get(&referrer.component) {
+                handler.clear_reference(world, referrer.entity, referrer.field);
+            }
+        }
+    });
+}
+
+/// Resolve the reference fields again, after the entity they point at was respawned.
+pub(crate) fn resolve_entity_references(world: &mut World, referrers: &[YoleckEntityReferrer]) {
+    world.resource_scope(|world, handlers: Mut<YoleckEntityRefHandlers>| {
+        for referrer in referrers {
+            if let Some(handler) = handlers.0.get(&referrer.component) {
+                handler.resolve_references(world, referrer.entity);
+            }
+        }
+    });
+}
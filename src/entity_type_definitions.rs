@@ -0,0 +1,312 @@
+use std::any::TypeId;
+use std::ops::Deref;
+
+use bevy::asset::AssetLoader;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use bevy::reflect::TypePath;
+use serde::{Deserialize, Serialize};
+
+use crate::editor::change_entity_type;
+use crate::errors::YoleckAssetLoaderError;
+use crate::prelude::{YoleckEntityUuid, YoleckUuidRegistry};
+use crate::{YoleckEntityConstructionSpecs, YoleckEntityTypeInfo, YoleckManaged};
+
+/// Describes an entity type in a [`YoleckEntityTypeDefinitions`] file.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct YoleckEntityTypeDefinition {
+    /// The `type_name` used to identify the entity type.
+    pub name: String,
+    /// The name of an entity type to [extend](crate::prelude::YoleckEntityType::extends). It must
+    /// be registered in Rust or defined in one of the definition files (in any order).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extends: Option<String>,
+    /// The keys of the entity type's components.
+    ///
+    /// Only components that were already registered in Rust can be used - either with
+    /// [`add_yoleck_component`](crate::YoleckExtForApp::add_yoleck_component) or as part of a
+    /// [`YoleckEntityType`](crate::prelude::YoleckEntityType).
+    pub components: Vec<String>,
+    /// Values to use instead of the `Default` of components, by their keys.
+    #[serde(default)]
+    pub defaults: serde_json::Map<String, serde_json::Value>,
+    /// The submenu of the "Add New Entity" menu to list the entity type under.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    /// Text (usually an emoji) to show before the name of the entity type in the editor.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    /// Whether entities of this type get a UUID. See
+    /// [`YoleckEntityType::with_uuid`](crate::prelude::YoleckEntityType::with_uuid).
+    #[serde(default)]
+    pub uuid: bool,
+}
+
+/// An asset loaded from a `.yolt` file, defining entity types without code changes.
+///
+/// Register the file with
+/// [`add_yoleck_entity_type_definitions`](crate::YoleckExtForApp::add_yoleck_entity_type_definitions).
+/// The entity types are created when the file is loaded, and recreated whenever it changes:
+///
+/// ```json
+/// [
+///     {"format_version": 1},
+///     [
+///         {
+///             "name": "Bush",
+///             "components": ["Vpeol2dPosition", "Decoration"],
+///             "defaults": {"Decoration": {"sprite": "sprites/bush.png"}},
+///             "category": "Decorations",
+///             "icon": "🌳"
+///         }
+///     ]
+/// ]
+/// ```
+///
+/// Levels that use these entity types should only be loaded after the definitions file is
+/// loaded. When the file changes, the existing entities of its entity types are updated - entities
+/// whose entity type's components changed are respawned, and entities whose entity type was
+/// removed are left as they are (with a warning).
+#[derive(Asset, TypePath, Debug, Serialize, Deserialize)]
+pub struct YoleckEntityTypeDefinitions(
+    YoleckEntityTypeDefinitionsHeader,
+    Vec<YoleckEntityTypeDefinition>,
+);
+
+/// Internal Yoleck metadata for the entity type definitions.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct YoleckEntityTypeDefinitionsHeader {
+    format_version: usize,
+}
+
+impl YoleckEntityTypeDefinitions {
+    pub fn new(definitions: impl IntoIterator<Item = YoleckEntityTypeDefinition>) -> Self {
+        Self(
+            YoleckEntityTypeDefinitionsHeader { format_version: 1 },
+            definitions.into_iter().collect(),
+        )
+    }
+}
+
+impl Deref for YoleckEntityTypeDefinitions {
+    type Target = [YoleckEntityTypeDefinition];
+
+    fn deref(&self) -> &Self::Target {
+        &self.1
+    }
+}
+
+#[derive(TypePath)]
+pub(crate) struct YoleckEntityTypeDefinitionsLoader;
+
+impl AssetLoader for YoleckEntityTypeDefinitionsLoader {
+    type Asset = YoleckEntityTypeDefinitions;
+    type Settings = ();
+    type Error = YoleckAssetLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn bevy::asset::io::Reader,
+        _settings: &Self::Settings,
+        _load_context: &mut bevy::asset::LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let json = std::str::from_utf8(&bytes)?;
+        let definitions: YoleckEntityTypeDefinitions = serde_json::from_str(json)?;
+        Ok(definitions)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["yolt"]
+    }
+}
+
+/// The entity type definition files registered with
+/// [`add_yoleck_entity_type_definitions`](crate::YoleckExtForApp::add_yoleck_entity_type_definitions).
+#[derive(Resource, Default)]
+pub struct YoleckEntityTypeDefinitionsHandles(pub Vec<Handle<YoleckEntityTypeDefinitions>>);
+
+/// Recreate the entity types of all the definition files whenever one of them is loaded or
+/// changed, and update the existing entities of these entity types.
+pub(crate) fn apply_entity_type_definitions(
+    mut asset_events: MessageReader<AssetEvent<YoleckEntityTypeDefinitions>>,
+    handles: Res<YoleckEntityTypeDefinitionsHandles>,
+    definitions_assets: Res<Assets<YoleckEntityTypeDefinitions>>,
+    mut construction_specs: ResMut<YoleckEntityConstructionSpecs>,
+    mut entities_query: Query<(Entity, &mut YoleckManaged, Option<&YoleckEntityUuid>)>,
+    mut uuid_registry: ResMut<YoleckUuidRegistry>,
+    mut commands: Commands,
+) {
+    let mut changed = false;
+    for event in asset_events.read() {
+        if let AssetEvent::LoadedWithDependencies { id }
+        | AssetEvent::Modified { id }
+        | AssetEvent::Removed { id } = event
+            && handles.0.iter().any(|handle| handle.id() == *id)
+        {
+            changed = true;
+        }
+    }
+    if !changed {
+        return;
+    }
+
+    // The components of the entity types before they are recreated, for finding the existing
+    // entities that need to be recreated with the new components.
+    let old_entity_types: HashMap<String, (Vec<TypeId>, bool)> = construction_specs
+        .entity_types
+        .iter()
+        .filter(|entity_type| entity_type.from_definitions)
+        .map(|entity_type| {
+            (
+                entity_type.name.clone(),
+                (entity_type.components.clone(), entity_type.has_uuid),
+            )
+        })
+        .collect();
+    construction_specs
+        .entity_types
+        .retain(|entity_type| !entity_type.from_definitions);
+    construction_specs.reindex_entity_types();
+
+    let components_by_key: HashMap<&'static str, _> = construction_specs
+        .component_handlers
+        .iter()
+        .map(|(component_type, handler)| (handler.key(), *component_type))
+        .collect();
+
+    // Definitions can extend entity types that are defined after them - possibly in other files -
+    // so they are added in passes, until none of the remaining definitions can be added.
+    let mut pending: Vec<&YoleckEntityTypeDefinition> = handles
+        .0
+        .iter()
+        .filter_map(|handle| definitions_assets.get(handle))
+        .flat_map(|definitions| definitions.iter())
+        .collect();
+    loop {
+        let pending_before = pending.len();
+        pending.retain(|definition| {
+            if let Some(base) = &definition.extends
+                && !construction_specs.entity_types_index.contains_key(base)
+            {
+                return true;
+            }
+            add_entity_type_definition(&mut construction_specs, &components_by_key, definition);
+            false
+        });
+        if pending.is_empty() || pending.len() == pending_before {
+            break;
+        }
+    }
+    for definition in pending {
+        error!(
+            "Entity type {:?} extends {:?}, which is not registered",
+            definition.name,
+            definition.extends.as_deref().unwrap_or_default()
+        );
+    }
+
+    for (entity, mut yoleck_managed, entity_uuid) in entities_query.iter_mut() {
+        let Some((old_components, old_has_uuid)) = old_entity_types.get(&yoleck_managed.type_name)
+        else {
+            continue;
+        };
+        let Some(entity_type_info) =
+            construction_specs.get_entity_type_info(&yoleck_managed.type_name)
+        else {
+            warn!(
+                "Entity {:?} ({:?}) is of entity type {:?}, which is no longer defined",
+                entity, yoleck_managed.name, yoleck_managed.type_name
+            );
+            continue;
+        };
+        if entity_type_info.components != *old_components
+            || entity_type_info.has_uuid != *old_has_uuid
+        {
+            // Respawning the entity creates it with the new components, and keeps the data of the
+            // components it still has.
+            let type_name = yoleck_managed.type_name.clone();
+            commands.queue(move |world: &mut World| {
+                change_entity_type(world, entity, type_name);
+            });
+        } else if yoleck_managed.base_types != entity_type_info.base_types {
+            yoleck_managed.base_types = entity_type_info.base_types.clone();
+            if let Some(entity_uuid) = entity_uuid {
+                uuid_registry.insert(
+                    entity_uuid.get(),
+                    entity,
+                    entity_type_info.type_names(),
+                    entity_type_info.marker_types(),
+                );
+            }
+        }
+    }
+}
+
+fn add_entity_type_definition(
+    construction_specs: &mut YoleckEntityConstructionSpecs,
+    components_by_key: &HashMap<&'static str, TypeId>,
+    definition: &YoleckEntityTypeDefinition,
+) {
+    if construction_specs
+        .entity_types_index
+        .contains_key(&definition.name)
+    {
+        error!("Entity type {:?} is already registered", definition.name);
+        return;
+    }
+    let mut components = Vec::with_capacity(definition.components.len());
+    for key in definition.components.iter() {
+        let Some(component_type) = components_by_key.get(key.as_str()) else {
+            error!(
+                "Entity type {:?} uses component {:?}, which is not registered",
+                definition.name, key
+            );
+            continue;
+        };
+        components.push(*component_type);
+    }
+    let mut entity_type = YoleckEntityTypeInfo {
+        name: definition.name.clone(),
+        components,
+        optional_components: Vec::new(),
+        markers: Vec::new(),
+        defaults: HashMap::new(),
+        presets: Vec::new(),
+        category: definition.category.clone(),
+        icon: definition.icon.clone(),
+        on_init: Vec::new(),
+        has_uuid: definition.uuid,
+        base_types: Vec::new(),
+        from_definitions: true,
+    };
+    if let Some(base) = &definition.extends
+        && !construction_specs.inherit(&mut entity_type, base)
+    {
+        error!(
+            "Entity type {:?} extends {:?}, which is not registered",
+            definition.name, base
+        );
+        return;
+    }
+    // Set after inheriting, so that they override the defaults of the base type.
+    for (key, value) in definition.defaults.iter() {
+        let Some(key) = entity_type.components.iter().find_map(|component| {
+            let handler = construction_specs.component_handlers.get(component)?;
+            (handler.key() == key).then_some(handler.key())
+        }) else {
+            warn!(
+                "Entity type {:?} has a default for {:?}, which is not one of its components",
+                definition.name, key
+            );
+            continue;
+        };
+        entity_type.defaults.insert(key, value.clone());
+    }
+    let new_index = construction_specs.entity_types.len();
+    construction_specs
+        .entity_types_index
+        .insert(definition.name.clone(), new_index);
+    construction_specs.entity_types.push(entity_type);
+}
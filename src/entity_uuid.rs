@@ -1,3 +1,5 @@
+use std::any::TypeId;
+
 use bevy::prelude::*;
 
 use bevy::platform::collections::HashMap;
@@ -25,11 +27,61 @@ impl YoleckEntityUuid {
 /// by their UUID.
 ///
 /// To find a UUID given the `Entity` - check its [`YoleckEntityUuid`] component.
-#[derive(Resource)]
-pub struct YoleckUuidRegistry(pub(crate) HashMap<Uuid, Entity>);
+#[derive(Resource, Default)]
+pub struct YoleckUuidRegistry {
+    pub(crate) entities: HashMap<Uuid, Entity>,
+    /// The entity type of each entity, followed by the entity types it extends.
+    pub(crate) entity_types: HashMap<Uuid, Vec<String>>,
+    /// The components that mark each entity as a target of
+    /// [`YoleckEntityRefTo`](crate::prelude::YoleckEntityRefTo) fields.
+    pub(crate) markers: HashMap<Uuid, Vec<TypeId>>,
+}
 
 impl YoleckUuidRegistry {
     pub fn get(&self, uuid: Uuid) -> Option<Entity> {
-        self.0.get(&uuid).copied()
+        self.entities.get(&uuid).copied()
+    }
+
+    /// Like [`get`](Self::get), but only finds the entity if it is of the given entity type (or of
+    /// an entity type that [extends](crate::YoleckEntityType::extends) it).
+    pub fn get_of_type(&self, uuid: Uuid, entity_type: &str) -> Option<Entity> {
+        let entity_types = self.entity_types.get(&uuid)?;
+        if !entity_types.iter().any(|name| name == entity_type) {
+            return None;
+        }
+        self.get(uuid)
+    }
+
+    /// Like [`get`](Self::get), but only finds the entity if its entity type has the component
+    /// `T` - either as a [`YoleckComponent`](crate::prelude::YoleckComponent) that all its entities
+    /// have, or as a [marker](crate::prelude::YoleckEntityType::with_marker).
+    pub fn get_with_marker<T: Component>(&self, uuid: Uuid) -> Option<Entity> {
+        self.has_marker(uuid, TypeId::of::<T>())
+            .then(|| self.get(uuid))
+            .flatten()
+    }
+
+    pub(crate) fn has_marker(&self, uuid: Uuid, marker: TypeId) -> bool {
+        self.markers
+            .get(&uuid)
+            .is_some_and(|markers| markers.contains(&marker))
+    }
+
+    pub(crate) fn insert(
+        &mut self,
+        uuid: Uuid,
+        entity: Entity,
+        entity_types: Vec<String>,
+        markers: Vec<TypeId>,
+    ) {
+        self.entities.insert(uuid, entity);
+        self.entity_types.insert(uuid, entity_types);
+        self.markers.insert(uuid, markers);
+    }
+
+    pub(crate) fn remove(&mut self, uuid: Uuid) {
+        self.entities.remove(&uuid);
+        self.entity_types.remove(&uuid);
+        self.markers.remove(&uuid);
     }
 }
@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::{fs, io};
 
 use bevy::platform::collections::HashSet;
@@ -14,7 +14,9 @@ use crate::exclusive_systems::YoleckActiveExclusiveSystem;
 use crate::knobs::YoleckKnobsCache;
 use crate::level_files_upgrading::upgrade_level_file;
 use crate::level_index::YoleckLevelIndexEntry;
+use crate::level_path::YoleckLevelPathHandlers;
 use crate::prelude::{YoleckEditorState, YoleckEntityUuid};
+use crate::validation::{YoleckLevelValidation, YoleckValidationPolicy};
 use crate::{
     YoleckEditableLevels, YoleckEntityConstructionSpecs, YoleckLevelInEditor,
     YoleckLevelInPlaytest, YoleckLevelIndex, YoleckManaged, YoleckPlaytestLevel, YoleckRawLevel,
@@ -52,6 +54,8 @@ pub struct LevelFilesManagerTopSectionLocals {
     loaded_files_index: io::Result<Vec<YoleckLevelIndexEntry>>,
     file_popup_open: bool,
     selected_level_file: SelectedLevelFile,
+    /// The new name of the selected level, while renaming it.
+    rename_to: Option<String>,
 }
 
 impl Default for LevelFilesManagerTopSectionLocals {
@@ -61,10 +65,13 @@ impl Default for LevelFilesManagerTopSectionLocals {
             loaded_files_index: Ok(vec![]),
             file_popup_open: false,
             selected_level_file: SelectedLevelFile::Unsaved(String::new()),
+            rename_to: None,
         }
     }
 }
 
+const SAVE_BLOCKED_TEXT: &str = "Fix the validation problems in the level before saving it";
+
 #[allow(clippy::too_many_arguments)]
 pub fn level_files_manager_top_section(
     mut ui: ResMut<YoleckPanelUi>,
@@ -81,16 +88,23 @@ pub fn level_files_manager_top_section(
     mut level_assets: ResMut<Assets<YoleckRawLevel>>,
     entity_upgrading: Option<Res<YoleckEntityUpgrading>>,
     active_exclusive_system: Option<Res<YoleckActiveExclusiveSystem>>,
+    (validation, validation_policy, level_path_handlers): (
+        Res<YoleckLevelValidation>,
+        Res<YoleckValidationPolicy>,
+        Res<YoleckLevelPathHandlers>,
+    ),
 ) -> Result {
     if active_exclusive_system.is_some() {
         return Ok(());
     }
+    let save_blocked = validation.blocks_saving(*validation_policy);
 
     let LevelFilesManagerTopSectionLocals {
         should_list_files,
         loaded_files_index,
         file_popup_open,
         selected_level_file,
+        rename_to,
     } = &mut *locals;
 
     let gen_raw_level_file = || {
@@ -143,10 +157,17 @@ pub fn level_files_manager_top_section(
             ClearLevel,
             LoadLevel { filename: String },
             SaveExisting { filename: String },
+            RenameLevel { from: String, to: String },
         }
 
         let mut level_management_action = LevelManagementAction::DoNothing;
 
+        // Listed even when the popup is closed, because the level pickers need the levels.
+        if *should_list_files {
+            *should_list_files = false;
+            *loaded_files_index = list_level_files(&levels_directory.0, &mut editable_levels);
+        }
+
         let file_button_response = ui.button("File");
         if file_button_response.clicked() {
             *file_popup_open = !*file_popup_open;
@@ -171,75 +192,6 @@ pub fn level_files_manager_top_section(
                         });
                         levels_directory.0 = path_str.into();
 
-                        let mk_files_index = || levels_directory.0.join("index.yoli");
-
-                        let save_index = |loaded_files_index: &[YoleckLevelIndexEntry]| {
-                            let index_file = mk_files_index();
-                            match fs::File::create(&index_file) {
-                                Ok(fd) => {
-                                    let index =
-                                        YoleckLevelIndex::new(loaded_files_index.iter().cloned());
-                                    serde_json::to_writer(fd, &index).unwrap();
-                                }
-                                Err(err) => {
-                                    warn!("Cannot open {:?} - {}", index_file, err);
-                                }
-                            }
-                        };
-
-                        if *should_list_files {
-                            *should_list_files = false;
-
-                            let editable_levels_update_result = fs::read_dir(&levels_directory.0)
-                                .and_then(|files| {
-                                    editable_levels.levels = files
-                                        .filter_map(|file| {
-                                            let file = match file {
-                                                Ok(file) => file,
-                                                Err(err) => return Some(Err(err)),
-                                            };
-                                            if file.path().extension()
-                                                != Some(std::ffi::OsStr::new(EXTENSION_WITHOUT_DOT))
-                                            {
-                                                return None;
-                                            }
-                                            Some(Ok(file.file_name().to_string_lossy().into()))
-                                        })
-                                        .collect::<Result<_, _>>()?;
-                                    Ok(())
-                                });
-
-                            *loaded_files_index = editable_levels_update_result.and_then(|()| {
-                                let index_file = mk_files_index();
-                                let mut files_index: Vec<YoleckLevelIndexEntry> =
-                                    match fs::File::open(&index_file) {
-                                        Ok(fd) => {
-                                            let index: YoleckLevelIndex =
-                                                serde_json::from_reader(fd)?;
-                                            index.iter().cloned().collect()
-                                        }
-                                        Err(err) => {
-                                            warn!("Cannot open {:?} - {}", index_file, err);
-                                            Vec::new()
-                                        }
-                                    };
-                                let mut existing_files: HashSet<String> = files_index
-                                    .iter()
-                                    .map(|file| file.filename.clone())
-                                    .collect();
-                                for filename in editable_levels.names() {
-                                    if !existing_files.remove(filename) {
-                                        files_index.push(YoleckLevelIndexEntry {
-                                            filename: filename.to_owned(),
-                                        });
-                                    }
-                                }
-                                files_index.retain(|file| !existing_files.contains(&file.filename));
-                                save_index(&files_index);
-                                Ok(files_index)
-                            });
-                        }
-
                         match &mut *loaded_files_index {
                             Ok(files) => {
                                 let mut swap_with_previous = None;
@@ -293,17 +245,19 @@ pub fn level_files_manager_top_section(
                                     });
                                 if let Some(swap_with_previous) = swap_with_previous {
                                     files.swap(swap_with_previous, swap_with_previous - 1);
-                                    save_index(files);
+                                    save_level_index(&levels_directory.0, files);
                                 }
                                 ui.horizontal(|ui| {
                                     #[allow(clippy::collapsible_else_if)]
                                     match &mut *selected_level_file {
                                         SelectedLevelFile::Unsaved(file_name) => {
                                             ui.text_edit_singleline(file_name);
-                                            let button = ui.add_enabled(
-                                                !file_name.is_empty(),
-                                                egui::Button::new("Create"),
-                                            );
+                                            let button = ui
+                                                .add_enabled(
+                                                    !file_name.is_empty() && !save_blocked,
+                                                    egui::Button::new("Create"),
+                                                )
+                                                .on_disabled_hover_text(SAVE_BLOCKED_TEXT);
                                             if button.clicked() {
                                                 if !file_name.ends_with(EXTENSION) {
                                                     file_name.push_str(EXTENSION);
@@ -341,7 +295,40 @@ pub fn level_files_manager_top_section(
                                                 }
                                             }
                                         }
-                                        SelectedLevelFile::Existing(_) => {
+                                        SelectedLevelFile::Existing(filename) => {
+                                            if let Some(new_name) = rename_to {
+                                                ui.text_edit_singleline(new_name);
+                                                if ui
+                                                    .add_enabled(
+                                                        !new_name.is_empty(),
+                                                        egui::Button::new("OK"),
+                                                    )
+                                                    .clicked()
+                                                {
+                                                    level_management_action =
+                                                        LevelManagementAction::RenameLevel {
+                                                            from: filename.clone(),
+                                                            to: new_name.clone(),
+                                                        };
+                                                    *rename_to = None;
+                                                }
+                                                if ui.button("Cancel").clicked() {
+                                                    *rename_to = None;
+                                                }
+                                                return;
+                                            }
+                                            if ui
+                                                .add_enabled(
+                                                    !yoleck.level_needs_saving,
+                                                    egui::Button::new("Rename"),
+                                                )
+                                                .on_disabled_hover_text(
+                                                    "Save the level before renaming it",
+                                                )
+                                                .clicked()
+                                            {
+                                                *rename_to = Some(filename.clone());
+                                            }
                                             let button = ui.add_enabled(
                                                 !yoleck.level_needs_saving,
                                                 egui::Button::new("New Level"),
@@ -390,7 +377,11 @@ pub fn level_files_manager_top_section(
             SelectedLevelFile::Existing(filename) => {
                 ui.label(filename.as_str());
                 ui.add_enabled_ui(yoleck.level_needs_saving, |ui| {
-                    if ui.button("SAVE").clicked() {
+                    if ui
+                        .add_enabled(!save_blocked, egui::Button::new("SAVE"))
+                        .on_disabled_hover_text(SAVE_BLOCKED_TEXT)
+                        .clicked()
+                    {
                         level_management_action = LevelManagementAction::SaveExisting {
                             filename: filename.clone(),
                         }
@@ -404,8 +395,43 @@ pub fn level_files_manager_top_section(
             }
         }
 
+        // The level is reloaded after renaming it, since the references in it to the renamed level
+        // were changed in the file.
+        if let LevelManagementAction::RenameLevel { from, to } = &level_management_action {
+            let mut to = to.clone();
+            if !to.ends_with(EXTENSION) {
+                to.push_str(EXTENSION);
+            }
+            level_management_action = match rename_level_file(
+                &levels_directory.0,
+                from,
+                &to,
+                editable_levels.names(),
+                &level_path_handlers,
+            ) {
+                Ok(()) => {
+                    info!("Renamed level {from:?} to {to:?}");
+                    if let Ok(files) = loaded_files_index {
+                        for file in files.iter_mut() {
+                            if file.filename == *from {
+                                file.filename = to.clone();
+                            }
+                        }
+                        save_level_index(&levels_directory.0, files);
+                    }
+                    *should_list_files = true;
+                    *selected_level_file = SelectedLevelFile::Existing(to.clone());
+                    LevelManagementAction::LoadLevel { filename: to }
+                }
+                Err(err) => {
+                    warn!("Cannot rename {from:?} to {to:?} - {err}");
+                    LevelManagementAction::DoNothing
+                }
+            };
+        }
+
         match level_management_action {
-            LevelManagementAction::DoNothing => {}
+            LevelManagementAction::DoNothing | LevelManagementAction::RenameLevel { .. } => {}
             LevelManagementAction::ClearLevel => {
                 for level_entity in keep_levels_query.iter() {
                     commands.entity(level_entity).despawn();
@@ -459,6 +485,97 @@ pub fn level_files_manager_top_section(
     Ok(())
 }
 
+/// List the level files in the levels directory, and update the index file to match them.
+fn list_level_files(
+    levels_directory: &Path,
+    editable_levels: &mut YoleckEditableLevels,
+) -> io::Result<Vec<YoleckLevelIndexEntry>> {
+    editable_levels.levels = fs::read_dir(levels_directory)?
+        .filter_map(|file| {
+            let file = match file {
+                Ok(file) => file,
+                Err(err) => return Some(Err(err)),
+            };
+            if file.path().extension() != Some(std::ffi::OsStr::new(EXTENSION_WITHOUT_DOT)) {
+                return None;
+            }
+            Some(Ok(file.file_name().to_string_lossy().into()))
+        })
+        .collect::<Result<_, _>>()?;
+
+    let index_file = levels_directory.join("index.yoli");
+    let mut files_index: Vec<YoleckLevelIndexEntry> = match fs::File::open(&index_file) {
+        Ok(fd) => {
+            let index: YoleckLevelIndex = serde_json::from_reader(fd)?;
+            index.iter().cloned().collect()
+        }
+        Err(err) => {
+            warn!("Cannot open {:?} - {}", index_file, err);
+            Vec::new()
+        }
+    };
+    let mut existing_files: HashSet<String> = files_index
+        .iter()
+        .map(|file| file.filename.clone())
+        .collect();
+    for filename in editable_levels.names() {
+        if !existing_files.remove(filename) {
+            files_index.push(YoleckLevelIndexEntry {
+                filename: filename.to_owned(),
+            });
+        }
+    }
+    files_index.retain(|file| !existing_files.contains(&file.filename));
+    save_level_index(levels_directory, &files_index);
+    Ok(files_index)
+}
+
+fn save_level_index(levels_directory: &Path, files_index: &[YoleckLevelIndexEntry]) {
+    let index_file = levels_directory.join("index.yoli");
+    match fs::File::create(&index_file) {
+        Ok(fd) => {
+            let index = YoleckLevelIndex::new(files_index.iter().cloned());
+            serde_json::to_writer(fd, &index).unwrap();
+        }
+        Err(err) => {
+            warn!("Cannot open {:?} - {}", index_file, err);
+        }
+    }
+}
+
+/// Rename a level file, and update the references to it in all the level files.
+fn rename_level_file<'a>(
+    levels_directory: &Path,
+    from: &str,
+    to: &str,
+    levels: impl Iterator<Item = &'a str>,
+    level_path_handlers: &YoleckLevelPathHandlers,
+) -> io::Result<()> {
+    let to_path = levels_directory.join(to);
+    if to_path.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{to:?} already exists"),
+        ));
+    }
+    fs::rename(levels_directory.join(from), &to_path)?;
+    for level in levels {
+        let path = levels_directory.join(if level == from { to } else { level });
+        let result = fs::File::open(&path)
+            .and_then(|fd| Ok(serde_json::from_reader::<_, serde_json::Value>(fd)?))
+            .and_then(|mut content| {
+                if level_path_handlers.rename_level_references(&mut content, from, to) {
+                    serde_json::to_writer(fs::File::create(&path)?, &content)?;
+                }
+                Ok(())
+            });
+        if let Err(err) = result {
+            warn!("Cannot update the references to {from:?} in {path:?} - {err}");
+        }
+    }
+    Ok(())
+}
+
 /// The UI part for Playtest buttons in the top panel.
 #[allow(clippy::too_many_arguments)]
 pub fn playtest_buttons_section(
@@ -0,0 +1,171 @@
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::YoleckRawLevel;
+use crate::prelude::YoleckComponent;
+use crate::validation::{YoleckValidationContext, YoleckValidationError};
+
+/// A reference to another level file, saved in the level file as the file name (relative to the
+/// levels directory).
+///
+/// `YoleckAutoEdit` edits it with a picker of the levels in the levels directory. When the target
+/// level is renamed in the editor, the reference is updated in the components registered with
+/// [`add_yoleck_auto_edit`](crate::auto_edit::YoleckAutoEditExt::add_yoleck_auto_edit) (or with
+/// `add_yoleck_reflect_edit`).
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_yoleck::prelude::*;
+/// # use serde::{Deserialize, Serialize};
+/// #[derive(Component, YoleckComponent, YoleckAutoEdit, Serialize, Deserialize, Clone, PartialEq, Default)]
+/// struct Portal {
+///     target: YoleckLevelPath,
+/// }
+///
+/// fn enter_portal(
+///     portals_query: Query<&Portal>,
+///     asset_server: Res<AssetServer>,
+///     mut commands: Commands,
+/// ) {
+///     for portal in portals_query.iter() {
+///         // The path of the levels directory inside the assets directory.
+///         if let Some(level) = portal.target.load(&asset_server, "levels") {
+///             commands.spawn(YoleckLoadLevel(level));
+///         }
+///     }
+/// }
+/// ```
+#[derive(Default, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct YoleckLevelPath {
+    pub filename: String,
+}
+
+impl YoleckLevelPath {
+    pub fn new(filename: impl Into<String>) -> Self {
+        Self {
+            filename: filename.into(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.filename.is_empty()
+    }
+
+    /// The asset path of the level, given the asset path of the levels directory.
+    pub fn asset_path(&self, levels_directory: &str) -> String {
+        let levels_directory = levels_directory.trim_end_matches('/');
+        if levels_directory.is_empty() {
+            self.filename.clone()
+        } else {
+            format!("{levels_directory}/{}", self.filename)
+        }
+    }
+
+    /// Load the level, or `None` if the path is empty. `levels_directory` is the asset path of the
+    /// levels directory.
+    pub fn load(
+        &self,
+        asset_server: &AssetServer,
+        levels_directory: &str,
+    ) -> Option<Handle<YoleckRawLevel>> {
+        if self.filename.is_empty() {
+            None
+        } else {
+            Some(asset_server.load(self.asset_path(levels_directory)))
+        }
+    }
+}
+
+pub(crate) fn validate_level_path(
+    value: &YoleckLevelPath,
+    ctx: &YoleckValidationContext,
+    errors: &mut Vec<YoleckValidationError>,
+) {
+    if value.is_empty() {
+        return;
+    }
+    let exists = ctx
+        .known_levels
+        .is_none_or(|known_levels| known_levels.contains(&value.filename));
+    if !exists {
+        errors.push(YoleckValidationError {
+            field: String::new(),
+            message: format!("Level file `{}` does not exist", value.filename),
+        });
+    }
+}
+
+/// Calls its callback with every level path in a component.
+type VisitLevelPaths<T> = fn(&mut T, &mut dyn FnMut(&mut YoleckLevelPath));
+
+type RenameLevelPaths = Box<dyn Fn(&mut serde_json::Value, &str, &str) -> bool + Send + Sync>;
+
+/// The components that can have [`YoleckLevelPath`] fields, by their keys in the level files, so
+/// that the references to a renamed level can be updated in the level files.
+#[derive(Default, Resource)]
+pub(crate) struct YoleckLevelPathHandlers(HashMap<&'static str, RenameLevelPaths>);
+
+impl YoleckLevelPathHandlers {
+    pub fn register<T: YoleckComponent>(&mut self, visit: VisitLevelPaths<T>) {
+        self.0.insert(
+            T::KEY,
+            Box::new(move |data, from, to| {
+                let Ok(mut component) = serde_json::from_value::<T>(data.clone()) else {
+                    return false;
+                };
+                let mut replaced = false;
+                visit(&mut component, &mut |level_path| {
+                    if level_path.filename == from {
+                        level_path.filename = to.to_owned();
+                        replaced = true;
+                    }
+                });
+                if !replaced {
+                    return false;
+                }
+                match serde_json::to_value(&component) {
+                    Ok(new_data) => {
+                        *data = new_data;
+                        true
+                    }
+                    Err(_) => false,
+                }
+            }),
+        );
+    }
+
+    /// Replace the references to a renamed level in the data of the entities of a level file.
+    /// Returns whether anything was replaced.
+    ///
+    /// Only the level paths of the registered components are replaced. The data of components
+    /// that do not refer to the renamed level is left as is.
+    pub fn rename_level_references(
+        &self,
+        level: &mut serde_json::Value,
+        from: &str,
+        to: &str,
+    ) -> bool {
+        // The level file is `[header, resources, entries]`, and each entry is `[header,
+        // components]`.
+        let Some(entries) = level.get_mut(2).and_then(|entries| entries.as_array_mut()) else {
+            return false;
+        };
+        let mut replaced = false;
+        for entry in entries.iter_mut() {
+            let Some(components) = entry
+                .get_mut(1)
+                .and_then(|components| components.as_object_mut())
+            else {
+                continue;
+            };
+            for (key, data) in components.iter_mut() {
+                if let Some(rename) = self.0.get(key.as_str()) {
+                    replaced |= rename(data, from, to);
+                }
+            }
+        }
+        replaced
+    }
+}
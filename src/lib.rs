@@ -40,7 +40,9 @@
 //!   [`YoleckEntityType`]. Use [`YoleckEntityType::with`] to register the
 //!   [`YoleckComponent`](crate::specs_registration::YoleckComponent)s for that entity type.
 //! * Register edit systems with
-//!   [`add_yoleck_edit_system`](YoleckExtForApp::add_yoleck_edit_system).
+//!   [`add_yoleck_edit_system`](YoleckExtForApp::add_yoleck_edit_system), or with
+//!   [`add_yoleck_component_edit_system`](YoleckExtForApp::add_yoleck_component_edit_system) for
+//!   systems that edit a single component.
 //! * Register populate systems on [`YoleckSchedule::Populate`]
 //! * If the application starts in editor mode:
 //!   * Add the `EguiPlugin` plugin.
@@ -175,14 +177,17 @@
 //! }
 //! ```
 
+pub mod asset_path;
 pub mod auto_edit;
 mod console;
+pub mod curve;
 mod editing;
 mod editor;
 mod editor_panels;
 mod editor_window;
 mod entity_management;
 pub mod entity_ref;
+mod entity_type_definitions;
 mod entity_upgrading;
 mod entity_uuid;
 mod errors;
@@ -191,10 +196,14 @@ pub mod knobs;
 mod level_files_manager;
 pub mod level_files_upgrading;
 mod level_index;
+pub mod level_path;
 mod picking_helpers;
 mod populating;
+#[cfg(feature = "bevy_reflect")]
+pub mod reflect_edit;
 mod specs_registration;
 mod util;
+pub mod validation;
 #[cfg(feature = "vpeol")]
 pub mod vpeol;
 #[cfg(feature = "vpeol_2d")]
@@ -203,26 +212,36 @@ pub mod vpeol_2d;
 pub mod vpeol_3d;
 
 use std::any::{Any, TypeId};
+use std::borrow::Cow;
 use std::path::Path;
 use std::sync::Arc;
 
+use bevy::asset::AssetPath;
 use bevy::ecs::schedule::ScheduleLabel;
-use bevy::ecs::system::{EntityCommands, SystemId};
-use bevy::platform::collections::HashMap;
+use bevy::ecs::system::SystemId;
+use bevy::platform::collections::{HashMap, HashSet};
 use bevy::prelude::*;
 use bevy_egui::EguiPrimaryContextPass;
 
 pub mod prelude {
+    pub use crate::asset_path::YoleckAssetPath;
     pub use crate::auto_edit::{YoleckAutoEdit, YoleckAutoEditExt};
+    pub use crate::curve::{YoleckCurve, YoleckGradient};
     pub use crate::editing::{YoleckEdit, YoleckUi};
     pub use crate::editor::{YoleckEditorState, YoleckPassedData, YoleckSyncWithEditorState};
     pub use crate::entity_management::{YoleckKeepLevel, YoleckLoadLevel, YoleckRawLevel};
-    pub use crate::entity_ref::{YoleckEntityRef, YoleckEntityRefAccessor};
+    pub use crate::entity_ref::{YoleckEntityRef, YoleckEntityRefAccessor, YoleckEntityRefTo};
+    pub use crate::entity_type_definitions::{
+        YoleckEntityTypeDefinition, YoleckEntityTypeDefinitions,
+    };
     pub use crate::entity_upgrading::YoleckEntityUpgradingPlugin;
     pub use crate::entity_uuid::{YoleckEntityUuid, YoleckUuidRegistry};
     pub use crate::knobs::YoleckKnobs;
     pub use crate::level_index::{YoleckLevelIndex, YoleckLevelIndexEntry};
+    pub use crate::level_path::YoleckLevelPath;
     pub use crate::populating::{YoleckMarking, YoleckPopulate};
+    #[cfg(feature = "bevy_reflect")]
+    pub use crate::reflect_edit::YoleckReflectEditExt;
     pub use crate::specs_registration::{YoleckComponent, YoleckEntityType};
     pub use crate::{
         YoleckBelongsToLevel, YoleckExtForApp, YoleckLevelInEditor, YoleckLevelInPlaytest,
@@ -231,19 +250,22 @@ pub mod prelude {
     pub use bevy_yoleck_macros::{YoleckAutoEdit, YoleckComponent};
 }
 
+pub use self::asset_path::YoleckEditorAssetsDirectoryPath;
 pub use self::console::{YoleckConsoleLogHistory, YoleckConsoleState, console_layer_factory};
 pub use self::editing::YoleckEditMarker;
 pub use self::editor::YoleckDirective;
 pub use self::editor::YoleckEditorEvent;
-use self::editor::YoleckEditorState;
+use self::editor::{YoleckEditorState, YoleckPendingDeletion};
 pub use self::editor_panels::{
     YoleckEditorBottomPanelSections, YoleckEditorBottomPanelTab, YoleckEditorLeftPanelSections,
     YoleckEditorRightPanelSections, YoleckEditorTopPanelSections, YoleckPanelUi,
 };
 pub use self::editor_window::YoleckEditorViewportRect;
+pub use self::entity_type_definitions::YoleckEntityTypeDefinitionsHandles;
 pub use self::picking_helpers::*;
 
 use self::entity_management::{EntitiesToPopulate, YoleckRawLevel};
+use self::entity_ref::YoleckEntityRefHandlers;
 use self::entity_upgrading::YoleckEntityUpgrading;
 use self::exclusive_systems::YoleckExclusiveSystemsPlugin;
 use self::knobs::YoleckKnobsCache;
@@ -251,11 +273,15 @@ pub use self::level_files_manager::YoleckEditorLevelsDirectoryPath;
 pub use self::level_index::YoleckEditableLevels;
 use self::level_index::YoleckLevelIndex;
 pub use self::populating::{YoleckPopulateContext, YoleckSystemMarker};
-use self::prelude::{YoleckKeepLevel, YoleckUuidRegistry};
-use self::specs_registration::{YoleckComponentHandler, YoleckEntityType};
+use self::prelude::{YoleckEntityTypeDefinitions, YoleckKeepLevel, YoleckUuidRegistry};
+use self::specs_registration::{
+    YoleckComponent, YoleckComponentHandler, YoleckComponentHandlerImpl, YoleckEntityPreset,
+    YoleckEntityType, YoleckOnInit,
+};
 use self::util::EditSpecificResources;
 pub use bevy_egui;
 pub use bevy_egui::egui;
+pub use serde_json;
 
 struct YoleckPluginBase;
 pub struct YoleckPluginForGame;
@@ -273,11 +299,25 @@ pub(crate) struct YoleckRunEditSystems;
 impl Plugin for YoleckPluginBase {
     fn build(&self, app: &mut App) {
         app.init_resource::<YoleckEntityConstructionSpecs>();
-        app.insert_resource(YoleckUuidRegistry(Default::default()));
+        app.init_resource::<YoleckEntityRefHandlers>();
+        app.init_resource::<YoleckUuidRegistry>();
         app.register_asset_loader(entity_management::YoleckLevelAssetLoader);
         app.init_asset::<YoleckRawLevel>();
         app.register_asset_loader(level_index::YoleckLevelIndexLoader);
         app.init_asset::<YoleckLevelIndex>();
+        app.init_resource::<YoleckEntityTypeDefinitionsHandles>();
+        app.register_asset_loader(entity_type_definitions::YoleckEntityTypeDefinitionsLoader);
+        app.init_asset::<YoleckEntityTypeDefinitions>();
+        app.add_systems(
+            Update,
+            (
+                entity_type_definitions::apply_entity_type_definitions,
+                entity_ref::validate_entity_ref_targets
+                    .run_if(resource_changed::<YoleckEntityConstructionSpecs>),
+            )
+                .chain()
+                .before(YoleckSystems::ProcessRawEntities),
+        );
 
         app.configure_sets(
             Update,
@@ -377,6 +417,9 @@ impl Plugin for YoleckPluginForEditor {
         app.insert_resource(YoleckEditorLevelsDirectoryPath(
             Path::new(".").join("assets").join("levels"),
         ));
+        app.insert_resource(YoleckEditorAssetsDirectoryPath(
+            Path::new(".").join("assets"),
+        ));
         app.init_resource::<YoleckEditorLeftPanelSections>();
         app.init_resource::<YoleckEditorRightPanelSections>();
         app.init_resource::<YoleckEditorTopPanelSections>();
@@ -385,6 +428,11 @@ impl Plugin for YoleckPluginForEditor {
         app.init_resource::<YoleckConsoleState>();
         app.init_resource::<YoleckConsoleLogHistory>();
         app.init_resource::<YoleckPlaytestLevel>();
+        app.init_resource::<YoleckPendingDeletion>();
+        app.init_resource::<validation::YoleckValidationHandlers>();
+        app.init_resource::<level_path::YoleckLevelPathHandlers>();
+        app.init_resource::<validation::YoleckValidationPolicy>();
+        app.init_resource::<validation::YoleckLevelValidation>();
         app.insert_resource(EditSpecificResources::new().with(YoleckEditableLevels {
             levels: Default::default(),
         }));
@@ -397,6 +445,12 @@ impl Plugin for YoleckPluginForEditor {
             EguiPrimaryContextPass,
             editor_window::yoleck_editor_window.in_set(YoleckRunEditSystems),
         );
+        app.add_systems(
+            Update,
+            validation::update_level_validation
+                .after(YoleckSystems::ProcessRawEntities)
+                .run_if(in_state(YoleckEditorState::EditorActive)),
+        );
 
         app.add_schedule(Schedule::new(
             YoleckInternalSchedule::UpdateManagedDataFromComponents,
@@ -425,6 +479,34 @@ pub trait YoleckExtForApp {
     /// ```
     fn add_yoleck_entity_type(&mut self, entity_type: YoleckEntityType);
 
+    /// Register a [`YoleckComponent`] without adding an entity type that uses it.
+    ///
+    /// This is only needed for components that are only used by entity types from
+    /// [`add_yoleck_entity_type_definitions`](Self::add_yoleck_entity_type_definitions).
+    fn add_yoleck_component<T: YoleckComponent>(&mut self);
+
+    /// Load entity types from a [`YoleckEntityTypeDefinitions`] file.
+    ///
+    /// The entity types are created when the file is loaded, and recreated whenever it changes.
+    /// They can only use components that were registered in Rust - either with
+    /// [`add_yoleck_component`](Self::add_yoleck_component) or by an entity type added with
+    /// [`add_yoleck_entity_type`](Self::add_yoleck_entity_type).
+    ///
+    /// ```no_run
+    /// # use bevy::prelude::*;
+    /// # use bevy_yoleck::prelude::*;
+    /// # use serde::{Deserialize, Serialize};
+    /// #[derive(Default, Clone, PartialEq, Serialize, Deserialize, Component, YoleckComponent)]
+    /// struct Decoration {
+    ///     sprite: String,
+    /// }
+    ///
+    /// # let mut app = App::new();
+    /// app.add_yoleck_component::<Decoration>();
+    /// app.add_yoleck_entity_type_definitions("entity_types.yolt");
+    /// ```
+    fn add_yoleck_entity_type_definitions(&mut self, path: impl Into<AssetPath<'static>>);
+
     /// Add a system for editing Yoleck components in the level editor.
     ///
     /// ```no_run
@@ -446,6 +528,32 @@ pub trait YoleckExtForApp {
     /// See [`YoleckEdit`](crate::editing::YoleckEdit).
     fn add_yoleck_edit_system<P>(&mut self, system: impl 'static + IntoSystem<(), (), P>);
 
+    /// Add a system for editing a specific [`YoleckComponent`] in the level editor.
+    ///
+    /// Unlike [`add_yoleck_edit_system`](Self::add_yoleck_edit_system), the UI of the system is
+    /// placed inside the collapsible panel of the component in the properties area, and the system
+    /// only runs when all the edited entities have that component.
+    ///
+    /// ```no_run
+    /// # use bevy::prelude::*;
+    /// # use bevy_yoleck::prelude::*;
+    /// # use serde::{Deserialize, Serialize};
+    /// # #[derive(Default, Clone, PartialEq, Serialize, Deserialize, Component, YoleckComponent)]
+    /// # struct Component1;
+    /// # let mut app = App::new();
+    ///
+    /// app.add_yoleck_component_edit_system::<Component1, _>(edit_component1);
+    ///
+    /// fn edit_component1(mut ui: ResMut<YoleckUi>, mut edit: YoleckEdit<&mut Component1>) {
+    ///     let Ok(component1) = edit.single_mut() else { return };
+    ///     // Edit `component1` with the `ui`
+    /// }
+    /// ```
+    fn add_yoleck_component_edit_system<T: YoleckComponent, P>(
+        &mut self,
+        system: impl 'static + IntoSystem<(), (), P>,
+    );
+
     /// Register a function that upgrades entities from a previous version of the app format.
     ///
     /// This should only be called _after_ adding
@@ -499,11 +607,19 @@ impl YoleckExtForApp for App {
             handler.build_in_bevy_app(self);
         }
 
-        let new_entry = YoleckEntityTypeInfo {
+        let mut new_entry = YoleckEntityTypeInfo {
             name: entity_type.name.clone(),
             components: component_type_ids,
+            optional_components: entity_type.optional_components,
+            markers: entity_type.markers,
+            defaults: entity_type.defaults,
+            presets: entity_type.presets,
+            category: entity_type.category,
+            icon: entity_type.icon,
             on_init: entity_type.on_init,
             has_uuid: entity_type.has_uuid,
+            base_types: Vec::new(),
+            from_definitions: false,
         };
 
         let mut construction_specs = self
@@ -511,17 +627,65 @@ impl YoleckExtForApp for App {
             .get_resource_mut::<YoleckEntityConstructionSpecs>()
             .expect("YoleckEntityConstructionSpecs was inserted earlier in this function");
 
-        let new_index = construction_specs.entity_types.len();
-        construction_specs
-            .entity_types_index
-            .insert(entity_type.name, new_index);
-        construction_specs.entity_types.push(new_entry);
+        if let Some(base) = entity_type.extends
+            && !construction_specs.inherit(&mut new_entry, &base)
+        {
+            panic!(
+                "Entity type {:?} extends {:?}, which is not registered",
+                entity_type.name, base
+            );
+        }
+
         for handler in component_handlers_to_register {
             // Can handlers can register systems? If so, this needs to be broken into two phases...
             construction_specs
                 .component_handlers
                 .insert(handler.component_type(), handler);
         }
+        for key in new_entry.defaults.keys() {
+            if !new_entry.components.iter().any(|component| {
+                construction_specs
+                    .component_handlers
+                    .get(component)
+                    .is_some_and(|handler| handler.key() == *key)
+            }) {
+                warn!(
+                    "Entity type {:?} has a default for {:?}, which is not one of its components",
+                    entity_type.name, key
+                );
+            }
+        }
+        let new_index = construction_specs.entity_types.len();
+        construction_specs
+            .entity_types_index
+            .insert(entity_type.name, new_index);
+        construction_specs.entity_types.push(new_entry);
+    }
+
+    fn add_yoleck_component<T: YoleckComponent>(&mut self) {
+        let construction_specs = self
+            .world_mut()
+            .get_resource_or_insert_with(YoleckEntityConstructionSpecs::default);
+        if construction_specs
+            .component_handlers
+            .contains_key(&TypeId::of::<T>())
+        {
+            return;
+        }
+        let handler = Box::<YoleckComponentHandlerImpl<T>>::default();
+        handler.build_in_bevy_app(self);
+        self.world_mut()
+            .resource_mut::<YoleckEntityConstructionSpecs>()
+            .component_handlers
+            .insert(TypeId::of::<T>(), handler);
+    }
+
+    fn add_yoleck_entity_type_definitions(&mut self, path: impl Into<AssetPath<'static>>) {
+        let handle = self.world().resource::<AssetServer>().load(path.into());
+        self.world_mut()
+            .get_resource_or_insert_with(YoleckEntityTypeDefinitionsHandles::default)
+            .0
+            .push(handle);
     }
 
     fn add_yoleck_edit_system<P>(&mut self, system: impl 'static + IntoSystem<(), (), P>) {
@@ -532,6 +696,21 @@ impl YoleckExtForApp for App {
         edit_systems.edit_systems.push(system_id);
     }
 
+    fn add_yoleck_component_edit_system<T: YoleckComponent, P>(
+        &mut self,
+        system: impl 'static + IntoSystem<(), (), P>,
+    ) {
+        let system_id = self.world_mut().register_system(system);
+        let mut edit_systems = self
+            .world_mut()
+            .get_resource_or_insert_with(YoleckEditSystems::default);
+        edit_systems
+            .component_edit_systems
+            .entry(TypeId::of::<T>())
+            .or_default()
+            .push(system_id);
+    }
+
     fn add_yoleck_entity_upgrade(
         &mut self,
         to_version: usize,
@@ -576,6 +755,18 @@ pub struct YoleckManaged {
     lifecycle_status: YoleckEntityLifecycleStatus,
 
     pub(crate) components_data: HashMap<TypeId, BoxedAny>,
+
+    /// The entity types that the entity's type [extends](YoleckEntityType::extends). Updated when
+    /// the entity type is recreated from a changed [`YoleckEntityTypeDefinitions`] file.
+    pub(crate) base_types: Vec<String>,
+}
+
+impl YoleckManaged {
+    /// Check if the entity is of the entity type `type_name`, or of a type that
+    /// [extends](YoleckEntityType::extends) it.
+    pub fn is_type(&self, type_name: &str) -> bool {
+        self.type_name == type_name || self.base_types.iter().any(|base| base == type_name)
+    }
 }
 
 /// A marker for entities that belongs to the Yoleck level and should be despawned with it.
@@ -605,11 +796,35 @@ pub enum YoleckEntityLifecycleStatus {
 #[derive(Default, Resource)]
 struct YoleckEditSystems {
     edit_systems: Vec<SystemId>,
+    /// Systems that edit a specific component, and run inside that component's panel.
+    component_edit_systems: HashMap<TypeId, Vec<SystemId>>,
 }
 
 impl YoleckEditSystems {
-    pub(crate) fn run_systems(&mut self, world: &mut World) {
-        for system_id in self.edit_systems.iter() {
+    /// Run the systems that are not placed in component panels. This includes the systems of
+    /// components that did not get a panel, because an edited entity's type does not register them.
+    pub(crate) fn run_systems(
+        &mut self,
+        world: &mut World,
+        components_in_panels: &HashSet<TypeId>,
+    ) {
+        Self::run(&self.edit_systems, world);
+        for (component_type, systems) in self.component_edit_systems.iter() {
+            if !components_in_panels.contains(component_type) {
+                Self::run(systems, world);
+            }
+        }
+    }
+
+    pub(crate) fn component_edit_systems(&self, component_type: TypeId) -> &[SystemId] {
+        self.component_edit_systems
+            .get(&component_type)
+            .map(|systems| systems.as_slice())
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn run(systems: &[SystemId], world: &mut World) {
+        for system_id in systems.iter() {
             world
                 .run_system(*system_id)
                 .expect("edit systems handled by Yoleck - system should been properly handled");
@@ -620,10 +835,21 @@ impl YoleckEditSystems {
 pub(crate) struct YoleckEntityTypeInfo {
     pub name: String,
     pub components: Vec<TypeId>,
-    #[allow(clippy::type_complexity)]
-    pub(crate) on_init:
-        Vec<Box<dyn 'static + Sync + Send + Fn(YoleckEditorState, &mut EntityCommands)>>,
+    /// Components (also listed in `components`) that entities can be created without.
+    pub optional_components: Vec<TypeId>,
+    /// Components registered with [`YoleckEntityType::with_marker`].
+    pub markers: Vec<TypeId>,
+    /// Values used instead of the `Default` of components, by their keys.
+    pub defaults: HashMap<&'static str, serde_json::Value>,
+    pub(crate) presets: Vec<YoleckEntityPreset>,
+    pub category: Option<String>,
+    pub icon: Option<String>,
+    pub(crate) on_init: Vec<YoleckOnInit>,
     pub has_uuid: bool,
+    /// The entity types this type extends, starting from its direct base.
+    pub base_types: Vec<String>,
+    /// Whether the entity type was created from a [`YoleckEntityTypeDefinitions`] file.
+    pub from_definitions: bool,
 }
 
 #[derive(Default, Resource)]
@@ -633,10 +859,98 @@ pub(crate) struct YoleckEntityConstructionSpecs {
     pub component_handlers: HashMap<TypeId, Box<dyn YoleckComponentHandler>>,
 }
 
+impl YoleckEntityTypeInfo {
+    /// The name of the entity type, with its icon if it has one.
+    pub fn caption(&self) -> Cow<'_, str> {
+        if let Some(icon) = &self.icon {
+            format!("{icon} {}", self.name).into()
+        } else {
+            self.name.as_str().into()
+        }
+    }
+
+    /// The name of the entity type, followed by the entity types it extends - as they are kept in
+    /// the [`YoleckUuidRegistry`].
+    pub fn type_names(&self) -> Vec<String> {
+        std::iter::once(&self.name)
+            .chain(self.base_types.iter())
+            .cloned()
+            .collect()
+    }
+
+    /// The components that all the entities of this type have, which can be used as the markers of
+    /// [`YoleckEntityRefTo`](crate::prelude::YoleckEntityRefTo) fields - as they are kept in the
+    /// [`YoleckUuidRegistry`].
+    pub fn marker_types(&self) -> Vec<TypeId> {
+        self.components
+            .iter()
+            .filter(|component| !self.optional_components.contains(component))
+            .chain(self.markers.iter())
+            .copied()
+            .collect()
+    }
+}
+
 impl YoleckEntityConstructionSpecs {
     pub fn get_entity_type_info(&self, entity_type: &str) -> Option<&YoleckEntityTypeInfo> {
         Some(&self.entity_types[*self.entity_types_index.get(entity_type)?])
     }
+
+    /// Make `entity_type` extend the registered entity type `base`. Returns `false` if `base` is not
+    /// registered.
+    pub(crate) fn inherit(&self, entity_type: &mut YoleckEntityTypeInfo, base: &str) -> bool {
+        let Some(base) = self.get_entity_type_info(base) else {
+            return false;
+        };
+        let own_components =
+            std::mem::replace(&mut entity_type.components, base.components.clone());
+        for component in own_components.iter() {
+            if !entity_type.components.contains(component) {
+                entity_type.components.push(*component);
+            }
+        }
+        for component in base.optional_components.iter() {
+            // Components that the entity type itself registers as required stay required.
+            if own_components.contains(component)
+                || entity_type.optional_components.contains(component)
+            {
+                continue;
+            }
+            entity_type.optional_components.push(*component);
+        }
+        for marker in base.markers.iter() {
+            if !entity_type.markers.contains(marker) {
+                entity_type.markers.push(*marker);
+            }
+        }
+        for (key, value) in base.defaults.iter() {
+            entity_type
+                .defaults
+                .entry(*key)
+                .or_insert_with(|| value.clone());
+        }
+        entity_type.on_init = base
+            .on_init
+            .iter()
+            .cloned()
+            .chain(entity_type.on_init.drain(..))
+            .collect();
+        entity_type.has_uuid |= base.has_uuid;
+        entity_type.base_types = std::iter::once(base.name.clone())
+            .chain(base.base_types.iter().cloned())
+            .collect();
+        true
+    }
+
+    /// Rebuild `entity_types_index` after entity types were removed.
+    pub fn reindex_entity_types(&mut self) {
+        self.entity_types_index = self
+            .entity_types
+            .iter()
+            .enumerate()
+            .map(|(index, entity_type)| (entity_type.name.clone(), index))
+            .collect();
+    }
 }
 
 /// Fields of the Yoleck editor.
@@ -0,0 +1,528 @@
+//! Editing components with Bevy's reflection instead of with [`YoleckAutoEdit`].
+//!
+//! Components that implement [`Reflect`] can be edited without deriving [`YoleckAutoEdit`] -
+//! which is useful for components from other crates:
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_yoleck::prelude::*;
+//! # use bevy_yoleck::auto_edit::FieldAttrs;
+//! # use serde::{Deserialize, Serialize};
+//! #[derive(Component, YoleckComponent, Reflect, Serialize, Deserialize, Clone, PartialEq, Default)]
+//! #[reflect(Default)]
+//! struct Light {
+//!     #[reflect(@FieldAttrs { range: Some((0.0, 10.0)), ..Default::default() })]
+//!     intensity: f32,
+//!     color: Color,
+//!     #[reflect(ignore)]
+//!     flicker_phase: f32,
+//! }
+//!
+//! # let mut app = App::new();
+//! app.add_yoleck_reflect_edit::<Light>();
+//! ```
+//!
+//! Structs, tuples, enums, lists, arrays, maps, sets and options are edited through their
+//! reflection. Common types (numbers, strings, vectors, colors...) use their [`YoleckAutoEdit`]
+//! implementation, and types registered with
+//! [`register_yoleck_widget`](crate::auto_edit::YoleckAutoEditExt::register_yoleck_widget) use
+//! their widget.
+//!
+//! Fields are customized by using [`FieldAttrs`] as a custom reflect attribute
+//! (`#[reflect(@FieldAttrs { ... })]`), and fields with `#[reflect(ignore)]` are not shown.
+//! Switching enum variants and adding list elements need the default values of the new fields, so
+//! their types must be registered with `#[reflect(Default)]`.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use bevy::ecs::component::Mutable;
+use bevy::prelude::*;
+use bevy::reflect::attributes::CustomAttributes;
+use bevy::reflect::enums::{DynamicEnum, DynamicVariant, VariantInfo};
+use bevy::reflect::std_traits::ReflectDefault;
+use bevy::reflect::structs::DynamicStruct;
+use bevy::reflect::tuple::DynamicTuple;
+use bevy::reflect::{GetTypeRegistration, PartialReflect, ReflectMut, TypeRegistry};
+use bevy_egui::egui;
+
+use crate::YoleckEditableLevels;
+use crate::asset_path::YoleckEditorAssetsDirectoryPath;
+use crate::auto_edit::{
+    FieldAttrs, YoleckAutoEdit, YoleckWidgetRegistry, collapsing_field, missing_widget_ui,
+    provide_widget_context, registered_widget,
+};
+use crate::curve::{YoleckCurve, YoleckGradient};
+use crate::editor::component_edit_ui;
+use crate::level_path::YoleckLevelPathHandlers;
+use crate::prelude::{YoleckComponent, YoleckEdit, YoleckExtForApp, YoleckLevelPath, YoleckUi};
+
+/// Edit a reflected value. `label` may be empty for top level values.
+pub fn reflect_edit(
+    value: &mut dyn PartialReflect,
+    ui: &mut egui::Ui,
+    label: &str,
+    attrs: &FieldAttrs,
+    type_registry: &TypeRegistry,
+) {
+    if attrs.readonly {
+        ui.add_enabled_ui(false, |ui| {
+            reflect_edit_impl(value, ui, label, attrs, type_registry);
+        });
+    } else {
+        reflect_edit_impl(value, ui, label, attrs, type_registry);
+    }
+}
+
+/// Edit the value with the [`YoleckAutoEdit`] implementation of the first listed type that
+/// matches it, and return from the calling function.
+macro_rules! edit_as_auto_edit_types {
+    ($value:ident, $ui:ident, $label:ident, $attrs:ident; $($ty:ty),* $(,)?) => {
+        $(
+            if let Some(value) = $value.try_downcast_mut::<$ty>() {
+                <$ty as YoleckAutoEdit>::auto_edit_field_impl(value, $ui, $label, $attrs);
+                return;
+            }
+        )*
+    };
+}
+
+fn reflect_edit_impl(
+    value: &mut dyn PartialReflect,
+    ui: &mut egui::Ui,
+    label: &str,
+    attrs: &FieldAttrs,
+    type_registry: &TypeRegistry,
+) {
+    if let Some(value) = value.try_as_reflect_mut()
+        && let Some(widget) = registered_widget(ui, value.as_any().type_id())
+    {
+        widget(value.as_any_mut(), ui, label, attrs);
+        return;
+    }
+
+    edit_as_auto_edit_types!(
+        value, ui, label, attrs;
+        f32, f64, u8, i8, u16, i16, u32, i32, u64, i64, usize, isize, bool, char, String,
+        PathBuf, Duration, Vec2, Vec3, Vec4, IVec2, IVec3, IVec4, UVec2, UVec3, UVec4, Quat,
+        Color, Rect, Transform, YoleckLevelPath, YoleckCurve, YoleckGradient,
+    );
+
+    let type_path = value.reflect_type_path().to_owned();
+    match value.reflect_mut() {
+        ReflectMut::Struct(value) => {
+            let info = value.get_represented_struct_info();
+            compound_ui(ui, label, attrs, |ui| {
+                for index in 0..value.field_len() {
+                    let name = value.name_at(index).unwrap_or_default().to_owned();
+                    let field_attrs = custom_field_attrs(
+                        info.and_then(|info| info.field_at(index))
+                            .map(|field| field.custom_attributes()),
+                    );
+                    let label = field_attrs
+                        .label
+                        .clone()
+                        .unwrap_or_else(|| name.replace('_', " "));
+                    if let Some(field) = value.field_at_mut(index) {
+                        ui.push_id(index, |ui| {
+                            reflect_edit(field, ui, &label, &field_attrs, type_registry);
+                        });
+                    }
+                }
+            });
+        }
+        ReflectMut::TupleStruct(value) => {
+            let info = value.get_represented_tuple_struct_info();
+            let field_attrs = |index: usize| {
+                custom_field_attrs(
+                    info.and_then(|info| info.field_at(index))
+                        .map(|field| field.custom_attributes()),
+                )
+            };
+            // Like with the derive, newtypes are edited as their inner field.
+            if value.field_len() == 1 {
+                let field_attrs = field_attrs(0);
+                if let Some(field) = value.field_mut(0) {
+                    reflect_edit(
+                        field,
+                        ui,
+                        label,
+                        &attrs.merged_with(&field_attrs),
+                        type_registry,
+                    );
+                }
+                return;
+            }
+            compound_ui(ui, label, attrs, |ui| {
+                for index in 0..value.field_len() {
+                    let field_attrs = field_attrs(index);
+                    let label = field_attrs
+                        .label
+                        .clone()
+                        .unwrap_or_else(|| index.to_string());
+                    if let Some(field) = value.field_mut(index) {
+                        ui.push_id(index, |ui| {
+                            reflect_edit(field, ui, &label, &field_attrs, type_registry);
+                        });
+                    }
+                }
+            });
+        }
+        ReflectMut::Tuple(value) => {
+            compound_ui(ui, label, attrs, |ui| {
+                for index in 0..value.field_len() {
+                    if let Some(field) = value.field_mut(index) {
+                        ui.push_id(index, |ui| {
+                            let label = index.to_string();
+                            reflect_edit(field, ui, &label, &FieldAttrs::default(), type_registry);
+                        });
+                    }
+                }
+            });
+        }
+        ReflectMut::List(value) => {
+            let item_type_id = value
+                .get_represented_list_info()
+                .map(|info| info.item_ty().id());
+            let len = value.len();
+            compound_ui(ui, &format!("{label} ({len})"), attrs, |ui| {
+                let can_remove = attrs.min_len.is_none_or(|min_len| min_len < len);
+                let mut to_remove = None;
+                for index in 0..len {
+                    let Some(item) = value.get_mut(index) else {
+                        continue;
+                    };
+                    ui.push_id(index, |ui| {
+                        ui.horizontal(|ui| {
+                            if ui.add_enabled(can_remove, egui::Button::new("−")).clicked() {
+                                to_remove = Some(index);
+                            }
+                            ui.vertical(|ui| {
+                                let label = format!("[{index}]");
+                                reflect_edit(
+                                    item,
+                                    ui,
+                                    &label,
+                                    &FieldAttrs::default(),
+                                    type_registry,
+                                );
+                            });
+                        });
+                    });
+                }
+                if let Some(index) = to_remove {
+                    value.remove(index);
+                }
+                let new_item =
+                    item_type_id.and_then(|type_id| default_value(type_id, type_registry));
+                let can_add =
+                    new_item.is_some() && attrs.max_len.is_none_or(|max_len| len < max_len);
+                if ui.add_enabled(can_add, egui::Button::new("+")).clicked()
+                    && let Some(new_item) = new_item
+                {
+                    value.push(new_item);
+                }
+            });
+        }
+        ReflectMut::Array(value) => {
+            compound_ui(ui, label, attrs, |ui| {
+                for index in 0..value.len() {
+                    if let Some(item) = value.get_mut(index) {
+                        ui.push_id(index, |ui| {
+                            let label = format!("[{index}]");
+                            reflect_edit(item, ui, &label, &FieldAttrs::default(), type_registry);
+                        });
+                    }
+                }
+            });
+        }
+        // Keys and set elements are only shown - they are hashed, so they cannot be edited in
+        // place.
+        ReflectMut::Map(value) => {
+            compound_ui(ui, &format!("{label} ({})", value.len()), attrs, |ui| {
+                let keys: Vec<Box<dyn PartialReflect>> =
+                    value.iter().map(|(key, _)| key.to_dynamic()).collect();
+                let mut to_remove = None;
+                for (index, key) in keys.iter().enumerate() {
+                    let Some(entry) = value.get_mut(key.as_ref()) else {
+                        continue;
+                    };
+                    ui.push_id(index, |ui| {
+                        ui.horizontal(|ui| {
+                            if ui.button("−").clicked() {
+                                to_remove = Some(index);
+                            }
+                            ui.vertical(|ui| {
+                                let label = format!("{key:?}");
+                                reflect_edit(
+                                    entry,
+                                    ui,
+                                    &label,
+                                    &FieldAttrs::default(),
+                                    type_registry,
+                                );
+                            });
+                        });
+                    });
+                }
+                if let Some(index) = to_remove {
+                    value.remove(keys[index].as_ref());
+                }
+            });
+        }
+        ReflectMut::Set(value) => {
+            compound_ui(ui, &format!("{label} ({})", value.len()), attrs, |ui| {
+                let elements: Vec<Box<dyn PartialReflect>> =
+                    value.iter().map(|element| element.to_dynamic()).collect();
+                for element in elements {
+                    ui.horizontal(|ui| {
+                        if ui.button("−").clicked() {
+                            value.remove(element.as_ref());
+                        }
+                        ui.label(format!("{element:?}"));
+                    });
+                }
+            });
+        }
+        ReflectMut::Enum(value) => {
+            let Some(info) = value.get_represented_enum_info() else {
+                missing_widget_ui(ui, label, &type_path);
+                return;
+            };
+            let current = value.variant_index();
+            let mut switch_to = None;
+            ui.horizontal(|ui| {
+                if !label.is_empty() {
+                    ui.label(label);
+                }
+                egui::ComboBox::from_id_salt(ui.id().with("yoleck_reflect_variant"))
+                    .selected_text(value.variant_name())
+                    .show_ui(ui, |ui| {
+                        for (index, variant) in info.iter().enumerate() {
+                            let new_value = if index == current {
+                                None
+                            } else {
+                                default_variant(variant, type_registry)
+                            };
+                            let response = ui.add_enabled(
+                                index == current || new_value.is_some(),
+                                egui::Button::selectable(index == current, variant.name()),
+                            );
+                            if response.clicked() {
+                                switch_to = new_value;
+                            }
+                        }
+                    });
+            });
+            if let Some(new_value) = switch_to {
+                // The new value is made from defaults of the variant's own fields, so it always
+                // fits.
+                let _ = value.try_apply(&new_value);
+                return;
+            }
+            let variant = info.variant_at(current);
+            ui.indent("yoleck_reflect_variant_fields", |ui| {
+                for index in 0..value.field_len() {
+                    let (name, custom_attributes) = match variant {
+                        Some(VariantInfo::Struct(variant)) => match variant.field_at(index) {
+                            Some(field) => (
+                                field.name().replace('_', " "),
+                                Some(field.custom_attributes()),
+                            ),
+                            None => (index.to_string(), None),
+                        },
+                        Some(VariantInfo::Tuple(variant)) => (
+                            index.to_string(),
+                            variant
+                                .field_at(index)
+                                .map(|field| field.custom_attributes()),
+                        ),
+                        _ => (index.to_string(), None),
+                    };
+                    let field_attrs = custom_field_attrs(custom_attributes);
+                    let label = field_attrs.label.clone().unwrap_or(name);
+                    if let Some(field) = value.field_at_mut(index) {
+                        ui.push_id(index, |ui| {
+                            reflect_edit(field, ui, &label, &field_attrs, type_registry);
+                        });
+                    }
+                }
+            });
+        }
+        _ => {
+            missing_widget_ui(ui, label, &type_path);
+        }
+    }
+}
+
+/// Show the fields of a compound value, under a collapsing header unless it is the top level
+/// value.
+fn compound_ui(
+    ui: &mut egui::Ui,
+    label: &str,
+    attrs: &FieldAttrs,
+    add_contents: impl FnOnce(&mut egui::Ui),
+) {
+    if label.is_empty() {
+        ui.vertical(add_contents);
+    } else {
+        collapsing_field(ui, label, attrs, add_contents);
+    }
+}
+
+fn custom_field_attrs(custom_attributes: Option<&CustomAttributes>) -> FieldAttrs {
+    custom_attributes
+        .and_then(|custom_attributes| custom_attributes.get::<FieldAttrs>())
+        .cloned()
+        .unwrap_or_default()
+}
+
+fn default_value(
+    type_id: std::any::TypeId,
+    type_registry: &TypeRegistry,
+) -> Option<Box<dyn PartialReflect>> {
+    let reflect_default = type_registry.get_type_data::<ReflectDefault>(type_id)?;
+    Some(reflect_default.default().into_partial_reflect())
+}
+
+fn default_variant(variant: &VariantInfo, type_registry: &TypeRegistry) -> Option<DynamicEnum> {
+    let dynamic_variant = match variant {
+        VariantInfo::Unit(_) => DynamicVariant::Unit,
+        VariantInfo::Tuple(variant) => {
+            let mut fields = DynamicTuple::default();
+            for field in variant.iter() {
+                fields.insert_boxed(default_value(field.type_id(), type_registry)?);
+            }
+            DynamicVariant::Tuple(fields)
+        }
+        VariantInfo::Struct(variant) => {
+            let mut fields = DynamicStruct::default();
+            for field in variant.iter() {
+                fields.insert_boxed(field.name(), default_value(field.type_id(), type_registry)?);
+            }
+            DynamicVariant::Struct(fields)
+        }
+    };
+    Some(DynamicEnum::new(variant.name(), dynamic_variant))
+}
+
+pub fn reflect_edit_system<T: YoleckComponent + Reflect>(
+    mut ui: ResMut<YoleckUi>,
+    mut edit: YoleckEdit<&mut T>,
+    type_registry: Res<AppTypeRegistry>,
+    assets_directory: Option<Res<YoleckEditorAssetsDirectoryPath>>,
+    widget_registry: Option<Res<YoleckWidgetRegistry>>,
+    editable_levels: Option<Res<YoleckEditableLevels>>,
+) {
+    let Ok(mut component) = edit.single_mut() else {
+        return;
+    };
+
+    provide_widget_context(
+        ui.ctx(),
+        assets_directory.as_deref(),
+        widget_registry.as_deref(),
+        editable_levels.as_deref(),
+    );
+
+    let type_registry = type_registry.read();
+    component_edit_ui(&mut ui, T::KEY, 1, |ui| {
+        reflect_edit(
+            component.as_partial_reflect_mut(),
+            ui,
+            "",
+            &FieldAttrs::default(),
+            &type_registry,
+        );
+    });
+}
+
+/// Call `visit` with every [`YoleckLevelPath`] in a reflected value.
+fn reflect_level_paths(
+    value: &mut dyn PartialReflect,
+    visit: &mut dyn FnMut(&mut YoleckLevelPath),
+) {
+    if let Some(level_path) = value.try_downcast_mut::<YoleckLevelPath>() {
+        visit(level_path);
+        return;
+    }
+    match value.reflect_mut() {
+        ReflectMut::Struct(value) => {
+            for index in 0..value.field_len() {
+                if let Some(field) = value.field_at_mut(index) {
+                    reflect_level_paths(field, visit);
+                }
+            }
+        }
+        ReflectMut::TupleStruct(value) => {
+            for index in 0..value.field_len() {
+                if let Some(field) = value.field_mut(index) {
+                    reflect_level_paths(field, visit);
+                }
+            }
+        }
+        ReflectMut::Tuple(value) => {
+            for index in 0..value.field_len() {
+                if let Some(field) = value.field_mut(index) {
+                    reflect_level_paths(field, visit);
+                }
+            }
+        }
+        ReflectMut::List(value) => {
+            for index in 0..value.len() {
+                if let Some(item) = value.get_mut(index) {
+                    reflect_level_paths(item, visit);
+                }
+            }
+        }
+        ReflectMut::Array(value) => {
+            for index in 0..value.len() {
+                if let Some(item) = value.get_mut(index) {
+                    reflect_level_paths(item, visit);
+                }
+            }
+        }
+        ReflectMut::Map(value) => {
+            let keys: Vec<_> = value.iter().map(|(key, _)| key.to_dynamic()).collect();
+            for key in keys {
+                if let Some(item) = value.get_mut(key.as_ref()) {
+                    reflect_level_paths(item, visit);
+                }
+            }
+        }
+        ReflectMut::Enum(value) => {
+            for index in 0..value.field_len() {
+                if let Some(field) = value.field_at_mut(index) {
+                    reflect_level_paths(field, visit);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+pub trait YoleckReflectEditExt {
+    /// Edit a component with [`reflect_edit`], without implementing [`YoleckAutoEdit`] for it.
+    /// This also registers the component's type (and the types of its fields) for reflection.
+    fn add_yoleck_reflect_edit<
+        T: Component<Mutability = Mutable> + YoleckComponent + Reflect + GetTypeRegistration,
+    >(
+        &mut self,
+    );
+}
+
+impl YoleckReflectEditExt for App {
+    fn add_yoleck_reflect_edit<
+        T: Component<Mutability = Mutable> + YoleckComponent + Reflect + GetTypeRegistration,
+    >(
+        &mut self,
+    ) {
+        self.register_type::<T>();
+        self.add_yoleck_component_edit_system::<T, _>(reflect_edit_system::<T>);
+        self.world_mut()
+            .get_resource_or_insert_with(YoleckLevelPathHandlers::default)
+            .register::<T>(|component, visit| {
+                reflect_level_paths(component.as_partial_reflect_mut(), visit);
+            });
+    }
+}
@@ -1,5 +1,6 @@
 use std::any::{Any, TypeId};
 use std::marker::PhantomData;
+use std::sync::Arc;
 
 use bevy::ecs::component::Mutable;
 use bevy::ecs::system::EntityCommands;
@@ -7,6 +8,7 @@ use bevy::platform::collections::HashMap;
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
 
+use crate::editor::SpawnEntityBuilder;
 use crate::prelude::YoleckEditorState;
 use crate::{BoxedAny, YoleckEntityLifecycleStatus, YoleckInternalSchedule, YoleckManaged};
 
@@ -31,17 +33,33 @@ pub struct YoleckEntityType {
     /// The `type_name` used to identify the entity type.
     pub name: String,
     pub(crate) components: Vec<Box<dyn YoleckComponentHandler>>,
-    #[allow(clippy::type_complexity)]
-    pub(crate) on_init:
-        Vec<Box<dyn 'static + Sync + Send + Fn(YoleckEditorState, &mut EntityCommands)>>,
+    pub(crate) optional_components: Vec<TypeId>,
+    pub(crate) markers: Vec<TypeId>,
+    pub(crate) defaults: HashMap<&'static str, serde_json::Value>,
+    pub(crate) presets: Vec<YoleckEntityPreset>,
+    pub(crate) category: Option<String>,
+    pub(crate) icon: Option<String>,
+    pub(crate) extends: Option<String>,
+    pub(crate) on_init: Vec<YoleckOnInit>,
     pub has_uuid: bool,
 }
 
+/// A function that runs when an entity is created, before it is populated.
+pub(crate) type YoleckOnInit =
+    Arc<dyn 'static + Sync + Send + Fn(YoleckEditorState, &mut EntityCommands)>;
+
 impl YoleckEntityType {
     pub fn new(name: impl ToString) -> Self {
         Self {
             name: name.to_string(),
             components: Default::default(),
+            optional_components: Default::default(),
+            markers: Default::default(),
+            defaults: Default::default(),
+            presets: Default::default(),
+            category: None,
+            icon: None,
+            extends: None,
             on_init: Default::default(),
             has_uuid: false,
         }
@@ -54,6 +72,101 @@ impl YoleckEntityType {
         self
     }
 
+    /// Register a [`YoleckComponent`] that entities of this type may or may not have.
+    ///
+    /// Entities are created without optional components, and designers can add and remove them
+    /// per entity from the properties panel of the editor. Only the entities that have the
+    /// component store it in the level file.
+    ///
+    /// Since the component can be removed from an entity after it was populated, populate systems
+    /// that insert things because of it should use `Option<&T>` and remove them when it is
+    /// missing:
+    ///
+    /// ```no_run
+    /// # use bevy::prelude::*;
+    /// # use bevy_yoleck::prelude::*;
+    /// # use serde::{Deserialize, Serialize};
+    /// # #[derive(Default, Clone, PartialEq, Serialize, Deserialize, Component, YoleckComponent)]
+    /// # struct Crate;
+    /// #[derive(Default, Clone, PartialEq, Serialize, Deserialize, Component, YoleckComponent)]
+    /// struct Breakable {
+    ///     hits: u32,
+    /// }
+    ///
+    /// #[derive(Component)]
+    /// struct Health(u32);
+    ///
+    /// # let mut app = App::new();
+    /// app.add_yoleck_entity_type({
+    ///     YoleckEntityType::new("Crate")
+    ///         .with::<Crate>()
+    ///         .with_optional::<Breakable>()
+    /// });
+    ///
+    /// fn populate_breakable(mut populate: YoleckPopulate<Option<&Breakable>, With<Crate>>) {
+    ///     populate.populate(|_ctx, mut cmd, breakable| {
+    ///         if let Some(breakable) = breakable {
+    ///             cmd.insert(Health(breakable.hits));
+    ///         } else {
+    ///             cmd.remove::<Health>();
+    ///         }
+    ///     });
+    /// }
+    /// ```
+    pub fn with_optional<T: YoleckComponent>(mut self) -> Self {
+        self.optional_components.push(TypeId::of::<T>());
+        self.with::<T>()
+    }
+
+    /// Create entities of this type with `component` instead of the component's `Default`.
+    ///
+    /// The component still needs to be registered with [`with`](Self::with) (or
+    /// [`with_optional`](Self::with_optional)). The value is also used when loading entities whose
+    /// data does not have the component, and when resetting the component in the editor.
+    pub fn with_default<T: YoleckComponent>(mut self, component: T) -> Self {
+        self.defaults.insert(
+            T::KEY,
+            serde_json::to_value(component).expect("Component must always be serializable"),
+        );
+        self
+    }
+
+    /// Add a named preset for creating entities of this type.
+    ///
+    /// Presets are listed under the entity type in the "Add New Entity" menu of the editor.
+    /// Choosing one spawns the entity with the [`SpawnEntityBuilder`] that `preset` returns:
+    ///
+    /// ```no_run
+    /// # use bevy::prelude::*;
+    /// # use bevy_yoleck::prelude::*;
+    /// # use serde::{Deserialize, Serialize};
+    /// #[derive(Default, Clone, PartialEq, Serialize, Deserialize, Component, YoleckComponent)]
+    /// enum KeyColor {
+    ///     #[default]
+    ///     Red,
+    ///     Blue,
+    /// }
+    ///
+    /// # let mut app = App::new();
+    /// app.add_yoleck_entity_type({
+    ///     YoleckEntityType::new("Key")
+    ///         .with::<KeyColor>()
+    ///         .with_preset("Red Key", |builder| builder.with(KeyColor::Red))
+    ///         .with_preset("Blue Key", |builder| builder.with(KeyColor::Blue))
+    /// });
+    /// ```
+    pub fn with_preset(
+        mut self,
+        name: impl ToString,
+        preset: impl 'static + Sync + Send + Fn(SpawnEntityBuilder) -> SpawnEntityBuilder,
+    ) -> Self {
+        self.presets.push(YoleckEntityPreset {
+            name: name.to_string(),
+            apply: Box::new(preset),
+        });
+        self
+    }
+
     /// Automatically spawn regular Bevy components when creating entities of this type.
     ///
     /// This is useful for marker components that don't carry data that needs to be saved to files.
@@ -61,19 +174,50 @@ impl YoleckEntityType {
         mut self,
         bundle_maker: impl 'static + Sync + Send + Fn() -> T,
     ) -> Self {
-        self.on_init.push(Box::new(move |_, cmd| {
+        self.on_init.push(Arc::new(move |_, cmd| {
             cmd.insert(bundle_maker());
         }));
         self
     }
 
+    /// Insert the marker component `T` to entities of this type when creating them, so that
+    /// [`YoleckEntityRefTo<T>`](crate::prelude::YoleckEntityRefTo) fields can refer to them.
+    ///
+    /// A [`YoleckComponent`] registered with [`with`](Self::with) can also be used as the marker
+    /// of a `YoleckEntityRefTo`, so this is only needed for components that are not saved to the
+    /// level file:
+    ///
+    /// ```no_run
+    /// # use bevy::prelude::*;
+    /// # use bevy_yoleck::prelude::*;
+    /// # use serde::{Deserialize, Serialize};
+    /// #[derive(Component, Default)]
+    /// struct IsPlanet;
+    ///
+    /// #[derive(Component, YoleckComponent, YoleckAutoEdit, Serialize, Deserialize, Clone, PartialEq, Default)]
+    /// struct LaserPointer {
+    ///     target: YoleckEntityRefTo<IsPlanet>,
+    /// }
+    ///
+    /// # let mut app = App::new();
+    /// app.add_yoleck_entity_type({
+    ///     YoleckEntityType::new("Planet")
+    ///         .with_uuid()
+    ///         .with_marker::<IsPlanet>()
+    /// });
+    /// ```
+    pub fn with_marker<T: Component + Default>(mut self) -> Self {
+        self.markers.push(TypeId::of::<T>());
+        self.insert_on_init(T::default)
+    }
+
     /// Similar to [`insert_on_init`](Self::insert_on_init), but only applies for entities in the
     /// editor. Will not be added during playtests or actual game.
     pub fn insert_on_init_during_editor<T: Bundle>(
         mut self,
         bundle_maker: impl 'static + Sync + Send + Fn() -> T,
     ) -> Self {
-        self.on_init.push(Box::new(move |editor_state, cmd| {
+        self.on_init.push(Arc::new(move |editor_state, cmd| {
             if matches!(editor_state, YoleckEditorState::EditorActive) {
                 cmd.insert(bundle_maker());
             }
@@ -87,7 +231,7 @@ impl YoleckEntityType {
         mut self,
         bundle_maker: impl 'static + Sync + Send + Fn() -> T,
     ) -> Self {
-        self.on_init.push(Box::new(move |editor_state, cmd| {
+        self.on_init.push(Arc::new(move |editor_state, cmd| {
             if matches!(editor_state, YoleckEditorState::GameActive) {
                 cmd.insert(bundle_maker());
             }
@@ -127,6 +271,64 @@ impl YoleckEntityType {
         self.has_uuid = true;
         self
     }
+
+    /// Make this entity type extend another, already registered, entity type.
+    ///
+    /// The entity type inherits the components, optional components and defaults of the base
+    /// type, its [`insert_on_init`](Self::insert_on_init) hooks (which run before the ones of
+    /// this type) and its [`with_uuid`](Self::with_uuid) setting. An optional component of the
+    /// base type that this type registers with [`with`](Self::with) is required in this type. Use
+    /// [`YoleckManaged::is_type`](crate::YoleckManaged::is_type) to match entities of the base
+    /// type and all its descendants:
+    ///
+    /// ```no_run
+    /// # use bevy::prelude::*;
+    /// # use bevy_yoleck::prelude::*;
+    /// # use bevy_yoleck::YoleckManaged;
+    /// # use serde::{Deserialize, Serialize};
+    /// # #[derive(Default, Clone, PartialEq, Serialize, Deserialize, Component, YoleckComponent)]
+    /// # struct Health(f32);
+    /// # #[derive(Default, Clone, PartialEq, Serialize, Deserialize, Component, YoleckComponent)]
+    /// # struct Wings;
+    /// # let mut app = App::new();
+    /// app.add_yoleck_entity_type({
+    ///     YoleckEntityType::new("Enemy")
+    ///         .with::<Health>()
+    ///         .with_default(Health(10.0))
+    /// });
+    /// app.add_yoleck_entity_type({
+    ///     YoleckEntityType::new("FlyingEnemy")
+    ///         .extends("Enemy")
+    ///         .with::<Wings>()
+    /// });
+    ///
+    /// fn count_enemies(query: Query<&YoleckManaged>) {
+    ///     let enemies = query.iter().filter(|managed| managed.is_type("Enemy")).count();
+    ///     info!("There are {enemies} enemies");
+    /// }
+    /// ```
+    pub fn extends(mut self, base: impl ToString) -> Self {
+        self.extends = Some(base.to_string());
+        self
+    }
+
+    /// List this entity type under a submenu of the "Add New Entity" menu.
+    pub fn with_category(mut self, category: impl ToString) -> Self {
+        self.category = Some(category.to_string());
+        self
+    }
+
+    /// Show text (usually an emoji) before the name of this entity type in the editor.
+    pub fn with_icon(mut self, icon: impl ToString) -> Self {
+        self.icon = Some(icon.to_string());
+        self
+    }
+}
+
+/// A named way to create an entity. See [`YoleckEntityType::with_preset`].
+pub(crate) struct YoleckEntityPreset {
+    pub name: String,
+    pub apply: Box<dyn 'static + Sync + Send + Fn(SpawnEntityBuilder) -> SpawnEntityBuilder>,
 }
 
 pub(crate) trait YoleckComponentHandler: 'static + Sync + Send {
@@ -140,10 +342,15 @@ pub(crate) trait YoleckComponentHandler: 'static + Sync + Send {
     );
     fn build_in_bevy_app(&self, app: &mut App);
     fn serialize(&self, component: &dyn Any) -> serde_json::Value;
+    fn serialize_from_entity(&self, entity: EntityRef) -> Option<serde_json::Value>;
+    /// Replace the component in the entity with one deserialized from `data`, or with the default
+    /// if `data` is `None`.
+    fn replace_in_entity(&self, data: Option<serde_json::Value>, entity: &mut EntityWorldMut);
+    fn remove_from_entity(&self, entity: &mut EntityWorldMut);
 }
 
 #[derive(Default)]
-struct YoleckComponentHandlerImpl<T: YoleckComponent> {
+pub(crate) struct YoleckComponentHandlerImpl<T: YoleckComponent> {
     _phantom_data: PhantomData<T>,
 }
 
@@ -191,27 +398,66 @@ impl<T: YoleckComponent> YoleckComponentHandler for YoleckComponentHandlerImpl<T
             .expect("Serialize must be called with the correct type");
         serde_json::to_value(concrete).expect("Component must always be serializable")
     }
+
+    fn serialize_from_entity(&self, entity: EntityRef) -> Option<serde_json::Value> {
+        Some(
+            serde_json::to_value(entity.get::<T>()?)
+                .expect("Component must always be serializable"),
+        )
+    }
+
+    fn replace_in_entity(&self, data: Option<serde_json::Value>, entity: &mut EntityWorldMut) {
+        let component: T = if let Some(data) = data {
+            match serde_json::from_value(data) {
+                Ok(component) => component,
+                Err(err) => {
+                    error!("Cannot load {:?}: {:?}", T::KEY, err);
+                    return;
+                }
+            }
+        } else {
+            Default::default()
+        };
+        entity.insert(component);
+    }
+
+    fn remove_from_entity(&self, entity: &mut EntityWorldMut) {
+        entity.remove::<T>();
+    }
 }
 
 impl<T: YoleckComponent> YoleckComponentHandlerImpl<T> {
     fn update_data_from_components(mut query: Query<(&mut YoleckManaged, &mut T)>) {
         for (mut yoleck_managed, component) in query.iter_mut() {
-            let yoleck_managed = yoleck_managed.as_mut();
-            match yoleck_managed.components_data.entry(TypeId::of::<T>()) {
-                bevy::platform::collections::hash_map::Entry::Vacant(entry) => {
-                    yoleck_managed.lifecycle_status = YoleckEntityLifecycleStatus::JustChanged;
-                    entry.insert(Box::<T>::new(component.clone()));
-                }
-                bevy::platform::collections::hash_map::Entry::Occupied(mut entry) => {
-                    let existing: &mut T = entry
-                        .get_mut()
-                        .downcast_mut()
-                        .expect("Component data is of wrong type");
-                    if existing != component.as_ref() {
-                        yoleck_managed.lifecycle_status = YoleckEntityLifecycleStatus::JustChanged;
-                        *existing = component.clone();
+            // This runs for all the entities every frame, so only the entities whose data actually
+            // changed are marked as changed.
+            let changed = {
+                let yoleck_managed = yoleck_managed.bypass_change_detection();
+                let changed = match yoleck_managed.components_data.entry(TypeId::of::<T>()) {
+                    bevy::platform::collections::hash_map::Entry::Vacant(entry) => {
+                        entry.insert(Box::<T>::new(component.clone()));
+                        true
                     }
+                    bevy::platform::collections::hash_map::Entry::Occupied(mut entry) => {
+                        let existing: &mut T = entry
+                            .get_mut()
+                            .downcast_mut()
+                            .expect("Component data is of wrong type");
+                        if existing != component.as_ref() {
+                            *existing = component.clone();
+                            true
+                        } else {
+                            false
+                        }
+                    }
+                };
+                if changed {
+                    yoleck_managed.lifecycle_status = YoleckEntityLifecycleStatus::JustChanged;
                 }
+                changed
+            };
+            if changed {
+                yoleck_managed.set_changed();
             }
         }
     }
@@ -0,0 +1,407 @@
+//! Validation of the fields of auto-edited components.
+//!
+//! The `YoleckAutoEdit` derive generates the validation from these field attributes:
+//!
+//! * `#[yoleck(min = ...)]` and `#[yoleck(max = ...)]` - bounds for numeric fields.
+//! * `#[yoleck(non_empty)]` - for strings, collections, options, asset paths and entity references.
+//! * `#[yoleck(regex = "...")]` - for strings. Requires the `regex` feature. Like `Regex::is_match`
+//!   of the [`regex`](https://docs.rs/regex) crate, the pattern can match any part of the string
+//!   unless it is anchored with `^` and `$`.
+//! * `#[yoleck(validate = function)]` - a function that takes a reference to the field and returns
+//!   a `Result<(), String>`.
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_yoleck::prelude::*;
+//! # use serde::{Deserialize, Serialize};
+//! #[derive(Component, YoleckComponent, YoleckAutoEdit, Serialize, Deserialize, Clone, PartialEq, Default)]
+//! struct Spawner {
+//!     #[yoleck(non_empty)]
+//!     enemy: String,
+//!     #[yoleck(min = 1, max = 100)]
+//!     count: u32,
+//!     #[yoleck(validate = validate_interval)]
+//!     interval: f32,
+//! }
+//!
+//! fn validate_interval(interval: &f32) -> Result<(), String> {
+//!     if interval.is_finite() && 0.0 < *interval {
+//!         Ok(())
+//!     } else {
+//!         Err("Must be positive".to_owned())
+//!     }
+//! }
+//! ```
+//!
+//! Errors are shown in red under the fields. The editor also checks all the entities in the level,
+//! lists the problems in the top panel, and - depending on [`YoleckValidationPolicy`] - prevents
+//! saving the level while there are problems.
+
+use std::any::{Any, TypeId};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::PathBuf;
+#[cfg(feature = "regex")]
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_egui::egui;
+#[cfg(feature = "regex")]
+pub use regex::Regex;
+
+use crate::asset_path::YoleckAssetPath;
+use crate::auto_edit::YoleckAutoEdit;
+use crate::entity_ref::{YoleckEntityRef, YoleckEntityRefTo};
+use crate::level_path::YoleckLevelPath;
+use crate::prelude::YoleckComponent;
+use crate::{YoleckDirective, YoleckEditableLevels, YoleckManaged};
+
+/// A field that failed its validation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct YoleckValidationError {
+    /// The path of the field, with the fields of nested values and the indices of list elements
+    /// separated by dots (e.g. `waves.2.count`)
+    pub field: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for YoleckValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.field.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}: {}", self.field, self.message)
+        }
+    }
+}
+
+/// What the values are validated against, besides themselves.
+#[derive(Default, Debug, Clone, Copy)]
+pub struct YoleckValidationContext<'a> {
+    /// The level files in the levels directory. [`YoleckLevelPath`]s are only checked when it is
+    /// known.
+    pub known_levels: Option<&'a BTreeSet<String>>,
+}
+
+/// Run the validation of a field's value, adding the field to the paths of the errors it finds.
+pub fn validate_nested<T: YoleckAutoEdit + ?Sized>(
+    value: &T,
+    field: &str,
+    ctx: &YoleckValidationContext,
+    errors: &mut Vec<YoleckValidationError>,
+) {
+    let mut nested_errors = Vec::new();
+    T::auto_edit_validate(value, ctx, &mut nested_errors);
+    errors.extend(
+        nested_errors
+            .into_iter()
+            .map(|error| YoleckValidationError {
+                field: if error.field.is_empty() {
+                    field.to_owned()
+                } else if field.is_empty() {
+                    error.field
+                } else {
+                    format!("{field}.{}", error.field)
+                },
+                message: error.message,
+            }),
+    );
+}
+
+/// Types that can be validated with `#[yoleck(min = ...)]` and `#[yoleck(max = ...)]`.
+#[diagnostic::on_unimplemented(
+    message = "`#[yoleck(min = ...)]` and `#[yoleck(max = ...)]` are not supported for `{Self}`"
+)]
+pub trait YoleckValidateBounds {
+    fn validation_value(&self) -> f64;
+}
+
+macro_rules! impl_validate_bounds_for_number {
+    ($($ty:ty),*) => {
+        $(
+            impl YoleckValidateBounds for $ty {
+                fn validation_value(&self) -> f64 {
+                    *self as f64
+                }
+            }
+        )*
+    };
+}
+
+impl_validate_bounds_for_number!(f32, f64, u8, i8, u16, i16, u32, i32, u64, i64, usize, isize);
+
+impl YoleckValidateBounds for Duration {
+    fn validation_value(&self) -> f64 {
+        self.as_secs_f64()
+    }
+}
+
+/// Types that can be validated with `#[yoleck(non_empty)]`.
+#[diagnostic::on_unimplemented(message = "`#[yoleck(non_empty)]` is not supported for `{Self}`")]
+pub trait YoleckValidateNonEmpty {
+    fn is_empty_for_validation(&self) -> bool;
+}
+
+impl YoleckValidateNonEmpty for String {
+    fn is_empty_for_validation(&self) -> bool {
+        self.is_empty()
+    }
+}
+
+impl YoleckValidateNonEmpty for PathBuf {
+    fn is_empty_for_validation(&self) -> bool {
+        self.as_os_str().is_empty()
+    }
+}
+
+impl<T> YoleckValidateNonEmpty for Vec<T> {
+    fn is_empty_for_validation(&self) -> bool {
+        self.is_empty()
+    }
+}
+
+impl<T> YoleckValidateNonEmpty for Option<T> {
+    fn is_empty_for_validation(&self) -> bool {
+        self.is_none()
+    }
+}
+
+impl<K, V, S> YoleckValidateNonEmpty for HashMap<K, V, S> {
+    fn is_empty_for_validation(&self) -> bool {
+        self.is_empty()
+    }
+}
+
+impl<K, V> YoleckValidateNonEmpty for BTreeMap<K, V> {
+    fn is_empty_for_validation(&self) -> bool {
+        self.is_empty()
+    }
+}
+
+impl<K, S> YoleckValidateNonEmpty for HashSet<K, S> {
+    fn is_empty_for_validation(&self) -> bool {
+        self.is_empty()
+    }
+}
+
+impl<K> YoleckValidateNonEmpty for BTreeSet<K> {
+    fn is_empty_for_validation(&self) -> bool {
+        self.is_empty()
+    }
+}
+
+impl<T: Asset> YoleckValidateNonEmpty for YoleckAssetPath<T> {
+    fn is_empty_for_validation(&self) -> bool {
+        self.is_empty()
+    }
+}
+
+impl YoleckValidateNonEmpty for YoleckLevelPath {
+    fn is_empty_for_validation(&self) -> bool {
+        self.is_empty()
+    }
+}
+
+impl YoleckValidateNonEmpty for YoleckEntityRef {
+    fn is_empty_for_validation(&self) -> bool {
+        self.is_none()
+    }
+}
+
+impl<T: Component> YoleckValidateNonEmpty for YoleckEntityRefTo<T> {
+    fn is_empty_for_validation(&self) -> bool {
+        self.is_none()
+    }
+}
+
+/// Used by the code generated for `#[yoleck(regex = "...")]`, which has a static one for each
+/// field so that the regex is only compiled once - when the field is first validated.
+///
+/// Without the `regex` feature, creating it fails the compilation.
+#[doc(hidden)]
+pub struct YoleckLazyRegex {
+    #[cfg(feature = "regex")]
+    pattern: &'static str,
+    #[cfg(feature = "regex")]
+    regex: OnceLock<Regex>,
+}
+
+impl YoleckLazyRegex {
+    pub const fn new(pattern: &'static str) -> Self {
+        #[cfg(feature = "regex")]
+        return Self {
+            pattern,
+            regex: OnceLock::new(),
+        };
+        #[cfg(not(feature = "regex"))]
+        {
+            let _ = pattern;
+            panic!("`#[yoleck(regex = \"...\")]` requires the `regex` feature of bevy-yoleck");
+        }
+    }
+
+    pub fn is_match(&self, text: &str) -> bool {
+        #[cfg(feature = "regex")]
+        return self
+            .regex
+            .get_or_init(|| Regex::new(self.pattern).expect("regex is checked by the derive macro"))
+            .is_match(text);
+        #[cfg(not(feature = "regex"))]
+        {
+            let _ = text;
+            unreachable!("cannot be created without the `regex` feature");
+        }
+    }
+}
+
+/// What the editor does when entities in the level fail their validation.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum YoleckValidationPolicy {
+    /// List the problems in the top panel, but allow saving the level.
+    #[default]
+    Warn,
+    /// List the problems in the top panel, and don't allow saving the level until they are fixed.
+    BlockSave,
+}
+
+/// A field of an entity in the level that failed its validation.
+#[derive(Debug, Clone)]
+pub struct YoleckValidationProblem {
+    pub entity: Entity,
+    /// The name of the entity, or its type if it does not have a name.
+    pub entity_name: String,
+    pub component: &'static str,
+    pub error: YoleckValidationError,
+}
+
+/// The validation problems of all the entities in the level being edited.
+#[derive(Resource, Default, Debug)]
+pub struct YoleckLevelValidation {
+    pub problems: Vec<YoleckValidationProblem>,
+}
+
+impl YoleckLevelValidation {
+    /// Whether the editor should refuse to save the level.
+    pub fn blocks_saving(&self, policy: YoleckValidationPolicy) -> bool {
+        policy == YoleckValidationPolicy::BlockSave && !self.problems.is_empty()
+    }
+}
+
+struct ValidationHandler {
+    key: &'static str,
+    validate:
+        fn(&(dyn Any + Send + Sync), &YoleckValidationContext, &mut Vec<YoleckValidationError>),
+}
+
+/// The auto-edited components, so that the editor can validate them without knowing their types.
+#[derive(Default, Resource)]
+pub(crate) struct YoleckValidationHandlers(HashMap<TypeId, ValidationHandler>);
+
+impl YoleckValidationHandlers {
+    pub fn register<T: YoleckComponent + YoleckAutoEdit>(&mut self) {
+        self.0.insert(
+            TypeId::of::<T>(),
+            ValidationHandler {
+                key: T::KEY,
+                validate: |data, ctx, errors| {
+                    if let Some(component) = data.downcast_ref::<T>() {
+                        T::auto_edit_validate(component, ctx, errors);
+                    }
+                },
+            },
+        );
+    }
+}
+
+/// Validate the level again when entities are added, changed or removed (loading another level
+/// does all of these), or when the list of levels (which level paths are validated against)
+/// changes.
+pub(crate) fn update_level_validation(
+    query: Query<(Entity, &YoleckManaged)>,
+    changed_query: Query<(), Changed<YoleckManaged>>,
+    mut removed_entities: RemovedComponents<YoleckManaged>,
+    handlers: Res<YoleckValidationHandlers>,
+    mut validation: ResMut<YoleckLevelValidation>,
+    editable_levels: Option<Res<YoleckEditableLevels>>,
+) {
+    let any_removed = removed_entities.read().count() > 0;
+    let levels_changed = editable_levels
+        .as_ref()
+        .is_some_and(|editable_levels| editable_levels.is_changed());
+    if changed_query.is_empty() && !any_removed && !levels_changed {
+        return;
+    }
+    let ctx = YoleckValidationContext {
+        known_levels: editable_levels
+            .as_ref()
+            .map(|editable_levels| &editable_levels.levels),
+    };
+
+    validation.problems.clear();
+    for (entity, yoleck_managed) in query.iter() {
+        for (component_type, component_data) in yoleck_managed.components_data.iter() {
+            let Some(handler) = handlers.0.get(component_type) else {
+                continue;
+            };
+            let mut errors = Vec::new();
+            (handler.validate)(component_data.as_ref(), &ctx, &mut errors);
+            let entity_name = if yoleck_managed.name.is_empty() {
+                yoleck_managed.type_name.clone()
+            } else {
+                yoleck_managed.name.clone()
+            };
+            validation
+                .problems
+                .extend(errors.into_iter().map(|error| YoleckValidationProblem {
+                    entity,
+                    entity_name: entity_name.clone(),
+                    component: handler.key,
+                    error,
+                }));
+        }
+    }
+    validation
+        .problems
+        .sort_by(|a, b| (&a.entity_name, a.entity).cmp(&(&b.entity_name, b.entity)));
+}
+
+/// The UI part for listing the validation problems in the top panel. Clicking a problem selects
+/// its entity.
+pub fn validation_problems_top_section(
+    mut ui: ResMut<crate::YoleckPanelUi>,
+    validation: Res<YoleckLevelValidation>,
+    policy: Res<YoleckValidationPolicy>,
+    mut writer: MessageWriter<YoleckDirective>,
+) -> Result {
+    if validation.problems.is_empty() {
+        return Ok(());
+    }
+    let color = match *policy {
+        YoleckValidationPolicy::Warn => egui::Color32::YELLOW,
+        YoleckValidationPolicy::BlockSave => egui::Color32::RED,
+    };
+    let button = egui::Button::new(
+        egui::RichText::new(format!("⚠ {} problems", validation.problems.len())).color(color),
+    );
+    egui::containers::menu::MenuButton::from_button(button).ui(&mut ui, |ui| {
+        if validation.blocks_saving(*policy) {
+            ui.label("The level cannot be saved until these are fixed");
+            ui.separator();
+        }
+        egui::ScrollArea::vertical()
+            .max_height(300.0)
+            .show(ui, |ui| {
+                for problem in validation.problems.iter() {
+                    let text = format!(
+                        "{} / {} - {}",
+                        problem.entity_name, problem.component, problem.error
+                    );
+                    if ui.button(text).clicked() {
+                        writer.write(YoleckDirective::set_selected(Some(problem.entity)));
+                        ui.close();
+                    }
+                }
+            });
+    });
+    Ok(())
+}
@@ -10,24 +10,29 @@ use bevy::camera::primitives::{Aabb, MeshAabb};
 use bevy::ecs::query::QueryFilter;
 use bevy::ecs::system::SystemParam;
 use bevy::mesh::VertexAttributeValues;
-use bevy::platform::collections::HashMap;
+use bevy::platform::collections::{HashMap, HashSet};
 use bevy::prelude::*;
 use bevy::render::render_resource::PrimitiveTopology;
 use bevy::window::{PrimaryWindow, WindowRef};
 use bevy_egui::{EguiContexts, egui};
 
+use crate::editor::YoleckPendingDeletion;
 use crate::entity_management::YoleckRawEntry;
+use crate::entity_ref::YoleckEntityRefHandlers;
+use crate::entity_uuid::{YoleckEntityUuid, YoleckUuidRegistry};
 use crate::knobs::YoleckKnobMarker;
 use crate::prelude::{YoleckEditorState, YoleckUi};
 use crate::{
-    YoleckDirective, YoleckEditMarker, YoleckEditorEvent, YoleckEditorViewportRect,
-    YoleckEntityConstructionSpecs, YoleckManaged, YoleckRunEditSystems, YoleckState,
+    YoleckBelongsToLevel, YoleckDirective, YoleckEditMarker, YoleckEditorEvent,
+    YoleckEditorViewportRect, YoleckEntityConstructionSpecs, YoleckManaged, YoleckRunEditSystems,
+    YoleckState,
 };
 
 pub mod prelude {
     pub use crate::vpeol::{
-        VpeolCameraState, VpeolDragPlane, VpeolOverrideDragPlane, VpeolRepositionLevel,
-        VpeolSelectionCuePlugin, VpeolWillContainClickableChildren, YoleckKnobClick,
+        VpeolCameraState, VpeolDragPlane, VpeolEntityRefLines, VpeolEntityRefLinesPlugin,
+        VpeolOverrideDragPlane, VpeolRepositionLevel, VpeolSelectionCuePlugin,
+        VpeolWillContainClickableChildren, YoleckKnobClick,
     };
     #[cfg(feature = "vpeol_2d")]
     pub use crate::vpeol_2d::{
@@ -89,6 +94,7 @@ impl Plugin for VpeolBasePlugin {
                 handle_delete_entity_key,
                 handle_copy_entity_key,
                 handle_paste_entity_key,
+                handle_duplicate_entity_key,
             )
                 .run_if(in_state(YoleckEditorState::EditorActive)),
         );
@@ -611,6 +617,164 @@ fn restore_transform_from_cache_after_transform_propagate(
     }
 }
 
+/// Draw arrows from entities to the targets of their [`YoleckEntityRef`](crate::YoleckEntityRef)
+/// fields, so that the connections between entities can be seen in the viewport.
+///
+/// The arrows are labelled with the name of the field, and colored by the component and field
+/// they come from. Which entities get their arrows drawn can be configured (also at runtime) with
+/// the [`VpeolEntityRefLines`] resource.
+#[derive(Default)]
+pub struct VpeolEntityRefLinesPlugin {
+    /// The initial value of the [`VpeolEntityRefLines`] resource.
+    pub mode: VpeolEntityRefLines,
+}
+
+impl Plugin for VpeolEntityRefLinesPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.mode);
+        app.add_systems(
+            Update,
+            draw_entity_ref_lines.run_if(in_state(YoleckEditorState::EditorActive)),
+        );
+    }
+}
+
+/// Which entities [`VpeolEntityRefLinesPlugin`] draws reference arrows from.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VpeolEntityRefLines {
+    /// Don't draw any arrows.
+    Hidden,
+    /// Only draw the arrows of the selected entities.
+    #[default]
+    Selected,
+    /// Draw the arrows of all the entities.
+    All,
+}
+
+#[derive(SystemParam)]
+struct VpeolEntityPositions<'w, 's> {
+    global_transform_query: Query<'w, 's, &'static GlobalTransform>,
+    #[cfg(feature = "vpeol_2d")]
+    vpeol_2d_query: Query<'w, 's, &'static crate::vpeol_2d::Vpeol2dPosition>,
+    #[cfg(feature = "vpeol_3d")]
+    vpeol_3d_query: Query<'w, 's, &'static crate::vpeol_3d::Vpeol3dPosition>,
+}
+
+impl VpeolEntityPositions<'_, '_> {
+    fn get(&self, entity: Entity) -> Option<Vec3> {
+        let global_transform = self.global_transform_query.get(entity).ok();
+        #[cfg(feature = "vpeol_3d")]
+        if let Ok(position) = self.vpeol_3d_query.get(entity) {
+            return Some(position.0);
+        }
+        #[cfg(feature = "vpeol_2d")]
+        if let Ok(position) = self.vpeol_2d_query.get(entity) {
+            let z = global_transform.map_or(0.0, |transform| transform.translation().z);
+            return Some(position.0.extend(z));
+        }
+        Some(global_transform?.translation())
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn draw_entity_ref_lines(
+    mut egui_context: EguiContexts,
+    mode: Res<VpeolEntityRefLines>,
+    cameras_query: Query<(&Camera, &GlobalTransform), With<VpeolCameraState>>,
+    editor_viewport: Res<YoleckEditorViewportRect>,
+    handlers: Res<YoleckEntityRefHandlers>,
+    uuid_registry: Res<YoleckUuidRegistry>,
+    referrers_query: Query<(Entity, &YoleckManaged, Has<YoleckEditMarker>)>,
+    positions: VpeolEntityPositions,
+) -> Result {
+    if *mode == VpeolEntityRefLines::Hidden {
+        return Ok(());
+    }
+    let Ok((camera, camera_transform)) = cameras_query.single() else {
+        return Ok(());
+    };
+    let ctx = egui_context.ctx_mut()?;
+    let viewport_rect = editor_viewport
+        .rect
+        .unwrap_or_else(|| ctx.input(|i| i.viewport_rect()));
+    // The camera's viewport is already placed at the editor viewport, so the "viewport"
+    // coordinates are in logical window pixels - which are also egui's points.
+    let to_screen = |position: Vec3| -> Option<egui::Pos2> {
+        let position = camera.world_to_viewport(camera_transform, position).ok()?;
+        Some(egui::pos2(position.x, position.y))
+    };
+
+    let painter = ctx
+        .layer_painter(egui::LayerId::new(
+            egui::Order::Background,
+            egui::Id::new("vpeol_entity_ref_lines"),
+        ))
+        .with_clip_rect(viewport_rect);
+    let font_id = egui::FontId::proportional(12.0);
+    let arrow_length = 10.0;
+    let arrow_width = 5.0;
+
+    for (entity, yoleck_managed, is_selected) in referrers_query.iter() {
+        if *mode == VpeolEntityRefLines::Selected && !is_selected {
+            continue;
+        }
+        let references = handlers.references_of(yoleck_managed);
+        if references.is_empty() {
+            continue;
+        }
+        let Some(source) = positions.get(entity).and_then(to_screen) else {
+            continue;
+        };
+        for (_, component_key, field, uuid) in references {
+            let Some(target) = uuid_registry
+                .get(uuid)
+                .and_then(|target| positions.get(target))
+                .and_then(to_screen)
+            else {
+                continue;
+            };
+            if source.distance(target) < arrow_length {
+                continue;
+            }
+            let direction = (target - source).normalized();
+
+            let color = entity_ref_line_color(component_key, field);
+            let arrow_base = target - direction * arrow_length;
+            painter.line_segment([source, arrow_base], egui::Stroke::new(2.0, color));
+            let perp = egui::Vec2::new(-direction.y, direction.x);
+            painter.add(egui::Shape::convex_polygon(
+                vec![
+                    target,
+                    arrow_base + perp * arrow_width,
+                    arrow_base - perp * arrow_width,
+                ],
+                color,
+                egui::Stroke::NONE,
+            ));
+            painter.text(
+                source + (target - source) * 0.5,
+                egui::Align2::CENTER_BOTTOM,
+                field,
+                font_id.clone(),
+                color,
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// A color that is stable for each reference field, so that references of the same kind can be
+/// recognized at a glance.
+fn entity_ref_line_color(component_key: &str, field: &str) -> egui::Color32 {
+    use std::hash::{DefaultHasher, Hash, Hasher};
+
+    let mut hasher = DefaultHasher::new();
+    (component_key, field).hash(&mut hasher);
+    let hue = (hasher.finish() % 360) as f32 / 360.0;
+    egui::ecolor::Hsva::new(hue, 0.8, 1.0, 1.0).into()
+}
+
 pub(crate) fn ray_intersection_with_mesh(ray: Ray3d, mesh: &Mesh) -> Option<f32> {
     let aabb = mesh.compute_aabb()?;
     let distance_to_aabb = ray_intersection_with_aabb(ray, aabb)?;
@@ -773,26 +937,56 @@ pub fn vpeol_read_click_on_entity<Filter: QueryFilter>(
 #[derive(Component)]
 pub struct VpeolRepositionLevel(pub Transform);
 
+#[allow(clippy::too_many_arguments)]
 fn handle_delete_entity_key(
     mut egui_context: EguiContexts,
     keyboard_input: Res<ButtonInput<KeyCode>>,
     mut yoleck_state: ResMut<YoleckState>,
-    query: Query<Entity, With<YoleckEditMarker>>,
+    query: Query<(
+        Entity,
+        &YoleckManaged,
+        Option<&YoleckEntityUuid>,
+        Has<YoleckEditMarker>,
+    )>,
+    entity_ref_handlers: Res<YoleckEntityRefHandlers>,
+    mut pending_deletion: ResMut<YoleckPendingDeletion>,
     mut commands: Commands,
     mut writer: MessageWriter<YoleckEditorEvent>,
 ) -> Result {
-    if egui_context.ctx_mut()?.egui_wants_keyboard_input() {
+    if egui_context.ctx_mut()?.egui_wants_keyboard_input() || pending_deletion.is_pending() {
         return Ok(());
     }
 
     if keyboard_input.just_pressed(KeyCode::Delete) {
-        for entity in query.iter() {
-            commands.entity(entity).despawn();
-            writer.write(YoleckEditorEvent::EntityDeselected(entity));
-        }
-        if !query.is_empty() {
-            yoleck_state.level_needs_saving = true;
+        let mut entities_to_delete = Vec::new();
+        let mut uuids_to_delete = HashSet::new();
+        for (entity, _, entity_uuid, is_selected) in query.iter() {
+            if is_selected {
+                entities_to_delete.push(entity);
+                uuids_to_delete.extend(entity_uuid.map(|entity_uuid| entity_uuid.get()));
+            }
         }
+        let referrers = if uuids_to_delete.is_empty() {
+            Vec::new()
+        } else {
+            entity_ref_handlers.find_referrers(
+                query
+                    .iter()
+                    .filter(|(_, _, _, is_selected)| !is_selected)
+                    .map(|(entity, yoleck_managed, _, _)| (entity, yoleck_managed)),
+                &uuids_to_delete,
+            )
+        };
+        pending_deletion.request(
+            entities_to_delete,
+            referrers.into_iter().filter_map(|referrer| {
+                let (_, yoleck_managed, _, _) = query.get(referrer.entity).ok()?;
+                Some((referrer, yoleck_managed))
+            }),
+            &mut commands,
+            &mut writer,
+            &mut yoleck_state,
+        );
     }
 
     Ok(())
@@ -821,10 +1015,70 @@ impl FromWorld for VpeolClipboard {
     }
 }
 
+fn entity_as_raw_entry(
+    yoleck_managed: &YoleckManaged,
+    entity_uuid: Option<&YoleckEntityUuid>,
+    construction_specs: &YoleckEntityConstructionSpecs,
+) -> Option<YoleckRawEntry> {
+    let entity_type = construction_specs.get_entity_type_info(&yoleck_managed.type_name)?;
+
+    let data: serde_json::Map<String, serde_json::Value> = entity_type
+        .components
+        .iter()
+        .filter_map(|component| {
+            let component_data = yoleck_managed.components_data.get(component)?;
+            let handler = &construction_specs.component_handlers[component];
+            Some((
+                handler.key().to_string(),
+                handler.serialize(component_data.as_ref()),
+            ))
+        })
+        .collect();
+
+    Some(YoleckRawEntry {
+        header: crate::entity_management::YoleckEntryHeader {
+            type_name: yoleck_managed.type_name.clone(),
+            name: yoleck_managed.name.clone(),
+            // Kept so that references between the copied entities can be remapped when they are
+            // pasted. The pasted entities will get new UUIDs.
+            uuid: entity_uuid.map(|entity_uuid| entity_uuid.get()),
+        },
+        data,
+    })
+}
+
+/// Spawn copies of the entries. Entries with UUIDs get new UUIDs, and references between the
+/// entries are changed to point at the new UUIDs.
+fn spawn_entries_as_copies(
+    entries: impl IntoIterator<Item = (Entity, YoleckRawEntry)>,
+    entity_ref_handlers: &YoleckEntityRefHandlers,
+    directives_writer: &mut MessageWriter<YoleckDirective>,
+) {
+    let entries = entries.into_iter().collect::<Vec<_>>();
+    let uuid_mapping: HashMap<uuid::Uuid, uuid::Uuid> = entries
+        .iter()
+        .filter_map(|(_, entry)| Some((entry.header.uuid?, uuid::Uuid::new_v4())))
+        .collect();
+
+    for (level, mut entry) in entries {
+        entity_ref_handlers.remap_references_in_raw_data(&mut entry.data, &uuid_mapping);
+        let mut builder = YoleckDirective::spawn_entity(level, entry.header.type_name, true)
+            .extend(entry.data.into_iter());
+        if let Some(new_uuid) = entry
+            .header
+            .uuid
+            .and_then(|old_uuid| uuid_mapping.get(&old_uuid))
+        {
+            builder = builder.with_uuid(*new_uuid);
+        }
+        directives_writer.write(builder.into());
+    }
+}
+
 fn handle_copy_entity_key(
     mut egui_context: EguiContexts,
     keyboard_input: Res<ButtonInput<KeyCode>>,
-    query: Query<&YoleckManaged, With<YoleckEditMarker>>,
+    query: Query<(&YoleckManaged, Option<&YoleckEntityUuid>), With<YoleckEditMarker>>,
     construction_specs: Res<YoleckEntityConstructionSpecs>,
     mut clipboard: ResMut<VpeolClipboard>,
 ) -> Result {
@@ -837,31 +1091,8 @@ fn handle_copy_entity_key(
     if ctrl_pressed && keyboard_input.just_pressed(KeyCode::KeyC) {
         let entities: Vec<YoleckRawEntry> = query
             .iter()
-            .filter_map(|yoleck_managed| {
-                let entity_type =
-                    construction_specs.get_entity_type_info(&yoleck_managed.type_name)?;
-
-                let data: serde_json::Map<String, serde_json::Value> = entity_type
-                    .components
-                    .iter()
-                    .filter_map(|component| {
-                        let component_data = yoleck_managed.components_data.get(component)?;
-                        let handler = &construction_specs.component_handlers[component];
-                        Some((
-                            handler.key().to_string(),
-                            handler.serialize(component_data.as_ref()),
-                        ))
-                    })
-                    .collect();
-
-                Some(YoleckRawEntry {
-                    header: crate::entity_management::YoleckEntryHeader {
-                        type_name: yoleck_managed.type_name.clone(),
-                        name: yoleck_managed.name.clone(),
-                        uuid: None,
-                    },
-                    data,
-                })
+            .filter_map(|(yoleck_managed, entity_uuid)| {
+                entity_as_raw_entry(yoleck_managed, entity_uuid, &construction_specs)
             })
             .collect();
 
@@ -887,6 +1118,7 @@ fn handle_paste_entity_key(
     mut egui_context: EguiContexts,
     keyboard_input: Res<ButtonInput<KeyCode>>,
     yoleck_state: Res<YoleckState>,
+    entity_ref_handlers: Res<YoleckEntityRefHandlers>,
     mut directives_writer: MessageWriter<YoleckDirective>,
     mut clipboard: ResMut<VpeolClipboard>,
 ) -> Result {
@@ -925,16 +1157,54 @@ fn handle_paste_entity_key(
             && !entities.is_empty()
         {
             let level_being_edited = yoleck_state.level_being_edited;
-
-            for entry in entities {
-                directives_writer.write(
-                    YoleckDirective::spawn_entity(level_being_edited, entry.header.type_name, true)
-                        .extend(entry.data.into_iter())
-                        .into(),
-                );
-            }
+            spawn_entries_as_copies(
+                entities
+                    .into_iter()
+                    .map(|entry| (level_being_edited, entry)),
+                &entity_ref_handlers,
+                &mut directives_writer,
+            );
         }
     }
 
     Ok(())
 }
+
+fn handle_duplicate_entity_key(
+    mut egui_context: EguiContexts,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    query: Query<
+        (
+            &YoleckManaged,
+            Option<&YoleckEntityUuid>,
+            &YoleckBelongsToLevel,
+        ),
+        With<YoleckEditMarker>,
+    >,
+    construction_specs: Res<YoleckEntityConstructionSpecs>,
+    entity_ref_handlers: Res<YoleckEntityRefHandlers>,
+    mut directives_writer: MessageWriter<YoleckDirective>,
+) -> Result {
+    if egui_context.ctx_mut()?.egui_wants_keyboard_input() {
+        return Ok(());
+    }
+
+    let ctrl_pressed = keyboard_input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+
+    if ctrl_pressed && keyboard_input.just_pressed(KeyCode::KeyD) {
+        spawn_entries_as_copies(
+            query
+                .iter()
+                .filter_map(|(yoleck_managed, entity_uuid, belongs_to_level)| {
+                    Some((
+                        belongs_to_level.level,
+                        entity_as_raw_entry(yoleck_managed, entity_uuid, &construction_specs)?,
+                    ))
+                }),
+            &entity_ref_handlers,
+            &mut directives_writer,
+        );
+    }
+
+    Ok(())
+}
@@ -167,7 +167,9 @@ impl Plugin for Vpeol2dPluginForEditor {
                 .chain()
                 .run_if(in_state(YoleckEditorState::EditorActive)),
         );
-        app.add_yoleck_edit_system(vpeol_2d_edit_transform_group);
+        app.add_yoleck_component_edit_system::<Vpeol2dPosition, _>(vpeol_2d_edit_position);
+        app.add_yoleck_component_edit_system::<Vpeol2dRotatation, _>(vpeol_2d_edit_rotation);
+        app.add_yoleck_component_edit_system::<Vpeol2dScale, _>(vpeol_2d_edit_scale);
         app.world_mut()
             .resource_mut::<YoleckEntityCreationExclusiveSystems>()
             .on_entity_creation(|queue| queue.push_back(vpeol_2d_init_position));
@@ -483,32 +485,10 @@ impl Default for Vpeol2dScale {
     }
 }
 
-fn vpeol_2d_edit_transform_group(
+fn vpeol_2d_edit_position(
     mut ui: ResMut<YoleckUi>,
-    position_edit: YoleckEdit<(Entity, &mut Vpeol2dPosition)>,
-    rotation_edit: YoleckEdit<&mut Vpeol2dRotatation>,
-    scale_edit: YoleckEdit<&mut Vpeol2dScale>,
-    passed_data: Res<YoleckPassedData>,
-) {
-    let has_any = !position_edit.is_empty() || !rotation_edit.is_empty() || !scale_edit.is_empty();
-    if !has_any {
-        return;
-    }
-
-    ui.group(|ui| {
-        ui.label(egui::RichText::new("Transform").strong());
-        ui.separator();
-
-        vpeol_2d_edit_position_impl(ui, position_edit, &passed_data);
-        vpeol_2d_edit_rotation_impl(ui, rotation_edit);
-        vpeol_2d_edit_scale_impl(ui, scale_edit);
-    });
-}
-
-fn vpeol_2d_edit_position_impl(
-    ui: &mut egui::Ui,
     mut edit: YoleckEdit<(Entity, &mut Vpeol2dPosition)>,
-    passed_data: &YoleckPassedData,
+    passed_data: Res<YoleckPassedData>,
 ) {
     if edit.is_empty() || edit.has_nonmatching() {
         return;
@@ -542,7 +522,7 @@ fn vpeol_2d_edit_position_impl(
     }
 }
 
-fn vpeol_2d_edit_rotation_impl(ui: &mut egui::Ui, mut edit: YoleckEdit<&mut Vpeol2dRotatation>) {
+fn vpeol_2d_edit_rotation(mut ui: ResMut<YoleckUi>, mut edit: YoleckEdit<&mut Vpeol2dRotatation>) {
     if edit.is_empty() || edit.has_nonmatching() {
         return;
     }
@@ -577,7 +557,7 @@ fn vpeol_2d_edit_rotation_impl(ui: &mut egui::Ui, mut edit: YoleckEdit<&mut Vpeo
     });
 }
 
-fn vpeol_2d_edit_scale_impl(ui: &mut egui::Ui, mut edit: YoleckEdit<&mut Vpeol2dScale>) {
+fn vpeol_2d_edit_scale(mut ui: ResMut<YoleckUi>, mut edit: YoleckEdit<&mut Vpeol2dScale>) {
     if edit.is_empty() || edit.has_nonmatching() {
         return;
     }
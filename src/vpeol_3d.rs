@@ -256,7 +256,9 @@ impl Plugin for Vpeol3dPluginForEditor {
                 .chain()
                 .run_if(in_state(YoleckEditorState::EditorActive)),
         );
-        app.add_yoleck_edit_system(vpeol_3d_edit_transform_group);
+        app.add_yoleck_component_edit_system::<Vpeol3dPosition, _>(vpeol_3d_edit_position);
+        app.add_yoleck_component_edit_system::<Vpeol3dRotation, _>(vpeol_3d_edit_rotation);
+        app.add_yoleck_component_edit_system::<Vpeol3dScale, _>(vpeol_3d_edit_scale);
         app.world_mut()
             .resource_mut::<YoleckEntityCreationExclusiveSystems>()
             .on_entity_creation(|queue| queue.push_back(vpeol_3d_init_position));
@@ -1128,44 +1130,16 @@ impl CommonDragPlane {
     }
 }
 
-fn vpeol_3d_edit_transform_group(
+fn vpeol_3d_edit_position(
     mut ui: ResMut<YoleckUi>,
-    position_edit: YoleckEdit<(
+    mut edit: YoleckEdit<(
         Entity,
         &mut Vpeol3dPosition,
         Option<&VpeolDragPlane>,
         Option<&Vpeol3dSnapToPlane>,
     )>,
-    rotation_edit: YoleckEdit<&mut Vpeol3dRotation>,
-    scale_edit: YoleckEdit<&mut Vpeol3dScale>,
     global_drag_plane: Res<VpeolDragPlane>,
     passed_data: Res<YoleckPassedData>,
-) {
-    let has_any = !position_edit.is_empty() || !rotation_edit.is_empty() || !scale_edit.is_empty();
-    if !has_any {
-        return;
-    }
-
-    ui.group(|ui| {
-        ui.label(egui::RichText::new("Transform").strong());
-        ui.separator();
-
-        vpeol_3d_edit_position_impl(ui, position_edit, &global_drag_plane, &passed_data);
-        vpeol_3d_edit_rotation_impl(ui, rotation_edit);
-        vpeol_3d_edit_scale_impl(ui, scale_edit);
-    });
-}
-
-fn vpeol_3d_edit_position_impl(
-    ui: &mut egui::Ui,
-    mut edit: YoleckEdit<(
-        Entity,
-        &mut Vpeol3dPosition,
-        Option<&VpeolDragPlane>,
-        Option<&Vpeol3dSnapToPlane>,
-    )>,
-    global_drag_plane: &VpeolDragPlane,
-    passed_data: &YoleckPassedData,
 ) {
     if edit.is_empty() || edit.has_nonmatching() {
         return;
@@ -1177,7 +1151,7 @@ fn vpeol_3d_edit_position_impl(
     let mut common_drag_plane = CommonDragPlane::NotDecidedYet;
 
     for (entity, position, drag_plane, _) in edit.iter_matching() {
-        let VpeolDragPlane(drag_plane) = drag_plane.unwrap_or(global_drag_plane);
+        let VpeolDragPlane(drag_plane) = drag_plane.unwrap_or(&global_drag_plane);
         common_drag_plane.consider(*drag_plane.normal);
 
         if let Some(pos) = passed_data.get::<Vec3>(entity) {
@@ -1217,7 +1191,7 @@ fn vpeol_3d_edit_position_impl(
     }
 }
 
-fn vpeol_3d_edit_rotation_impl(ui: &mut egui::Ui, mut edit: YoleckEdit<&mut Vpeol3dRotation>) {
+fn vpeol_3d_edit_rotation(mut ui: ResMut<YoleckUi>, mut edit: YoleckEdit<&mut Vpeol3dRotation>) {
     if edit.is_empty() || edit.has_nonmatching() {
         return;
     }
@@ -1278,7 +1252,7 @@ fn vpeol_3d_edit_rotation_impl(ui: &mut egui::Ui, mut edit: YoleckEdit<&mut Vpeo
     });
 }
 
-fn vpeol_3d_edit_scale_impl(ui: &mut egui::Ui, mut edit: YoleckEdit<&mut Vpeol3dScale>) {
+fn vpeol_3d_edit_scale(mut ui: ResMut<YoleckUi>, mut edit: YoleckEdit<&mut Vpeol3dScale>) {
     if edit.is_empty() || edit.has_nonmatching() {
         return;
     }
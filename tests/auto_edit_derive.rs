@@ -0,0 +1,776 @@
+use bevy::prelude::*;
+use bevy_yoleck::auto_edit::YoleckAutoEdit;
+use bevy_yoleck::egui;
+use bevy_yoleck::prelude::*;
+use serde::{Deserialize, Serialize};
+
+fn run_auto_edit<T: YoleckAutoEdit>(value: &mut T) {
+    let ctx = egui::Context::default();
+    let mut output = ctx.run_ui(Default::default(), |ui| {
+        T::auto_edit(value, ui);
+    });
+    output.textures_delta.clear();
+}
+
+#[derive(
+    Default, Clone, PartialEq, Serialize, Deserialize, Component, YoleckComponent, YoleckAutoEdit,
+)]
+enum EnemyBehavior {
+    #[default]
+    Idle,
+    #[yoleck(label = "Patrol Between")]
+    Patrol {
+        #[yoleck(range(0.0..=10.0), visible_if = "!waypoints.is_empty()")]
+        speed: f32,
+        #[yoleck(hidden)]
+        progress: f32,
+        waypoints: Vec<Vec2>,
+    },
+    Chase(f32, #[yoleck(label = "give up after")] u32),
+}
+
+#[test]
+fn derive_auto_edit_for_enum() {
+    assert!(EnemyBehavior::entity_ref_fields().is_empty());
+    for mut behavior in [
+        EnemyBehavior::Idle,
+        EnemyBehavior::Patrol {
+            speed: 1.0,
+            progress: 0.5,
+            waypoints: vec![Vec2::ZERO, Vec2::X],
+        },
+        EnemyBehavior::Chase(2.0, 3),
+    ] {
+        let original = behavior.clone();
+        run_auto_edit(&mut behavior);
+        assert!(behavior == original);
+    }
+}
+
+#[derive(Component)]
+struct Gate;
+
+#[derive(
+    Default, Clone, PartialEq, Serialize, Deserialize, Component, YoleckComponent, YoleckAutoEdit,
+)]
+enum TriggerTarget {
+    #[default]
+    Nothing,
+    Open {
+        door: YoleckEntityRefTo<Gate>,
+    },
+    Close {
+        door: YoleckEntityRefTo<Gate>,
+        delay: f32,
+    },
+    Spawn(#[yoleck(entity_ref = "Spawner")] YoleckEntityRef),
+}
+
+#[test]
+fn derive_auto_edit_for_enum_with_entity_refs() {
+    assert_eq!(
+        TriggerTarget::entity_ref_fields(),
+        &[("door", None), ("0", Some("Spawner"))],
+    );
+    assert_eq!(
+        TriggerTarget::entity_ref_marker("door").map(|(marker, _)| marker),
+        Some(std::any::TypeId::of::<Gate>()),
+    );
+    assert!(TriggerTarget::entity_ref_marker("0").is_none());
+
+    let door_uuid = uuid::Uuid::new_v4();
+    let mut target = TriggerTarget::Close {
+        door: YoleckEntityRefTo::from_uuid(door_uuid),
+        delay: 1.0,
+    };
+    assert_eq!(
+        target
+            .get_entity_ref("door")
+            .and_then(|entity_ref| entity_ref.uuid()),
+        Some(door_uuid),
+    );
+    assert!(target.get_entity_ref("0").is_none());
+    assert!(target.try_get_entity_ref_mut("0").is_none());
+    target.get_entity_ref_mut("door").clear();
+    assert_eq!(target.get_entity_ref("door").unwrap().uuid(), None);
+
+    let spawner_uuid = uuid::Uuid::new_v4();
+    let mut target = TriggerTarget::Spawn(YoleckEntityRef::from_uuid(spawner_uuid));
+    assert!(target.try_get_entity_ref_mut("door").is_none());
+    assert_eq!(
+        target
+            .try_get_entity_ref_mut("0")
+            .and_then(|entity_ref| entity_ref.uuid()),
+        Some(spawner_uuid),
+    );
+    run_auto_edit(&mut target);
+}
+
+#[derive(
+    Default, Clone, PartialEq, Serialize, Deserialize, Component, YoleckComponent, YoleckAutoEdit,
+)]
+struct Health(#[yoleck(range(0..=100))] u32);
+
+#[derive(
+    Default, Clone, PartialEq, Serialize, Deserialize, Component, YoleckComponent, YoleckAutoEdit,
+)]
+struct Span(f32, #[yoleck(label = "end")] f32, #[yoleck(hidden)] f32);
+
+#[derive(
+    Default, Clone, PartialEq, Serialize, Deserialize, Component, YoleckComponent, YoleckAutoEdit,
+)]
+struct Connection(
+    YoleckEntityRef,
+    #[yoleck(entity_ref = "Planet")] YoleckEntityRef,
+);
+
+#[derive(
+    Default, Clone, PartialEq, Serialize, Deserialize, Component, YoleckComponent, YoleckAutoEdit,
+)]
+struct Character {
+    #[yoleck(readonly)]
+    health: Health,
+    span: Span,
+}
+
+#[test]
+fn derive_auto_edit_for_tuple_structs() {
+    let mut character = Character {
+        health: Health(5),
+        span: Span(1.0, 2.0, 3.0),
+    };
+    run_auto_edit(&mut character);
+    assert!(character.health == Health(5));
+    assert!(character.span == Span(1.0, 2.0, 3.0));
+
+    assert_eq!(
+        Connection::entity_ref_fields(),
+        &[("0", None), ("1", Some("Planet"))],
+    );
+    let mut connection = Connection::default();
+    connection.get_entity_ref_mut("1").set(uuid::Uuid::new_v4());
+    assert!(connection.0.is_none());
+    assert!(connection.1.is_some());
+    run_auto_edit(&mut connection);
+}
+
+#[derive(
+    Default, Clone, PartialEq, Serialize, Deserialize, Component, YoleckComponent, YoleckAutoEdit,
+)]
+struct Door {
+    #[yoleck(asset = "png, jpg")]
+    texture: String,
+    #[yoleck(asset = "ogg")]
+    sound: std::path::PathBuf,
+    #[yoleck(asset = "yol")]
+    target_level: YoleckAssetPath<YoleckRawLevel>,
+}
+
+#[test]
+fn asset_path_fields() {
+    let mut door = Door {
+        texture: "door.png".to_owned(),
+        sound: "sounds/creak.ogg".into(),
+        target_level: YoleckAssetPath::new("levels/next.yol"),
+    };
+    let original = door.clone();
+    run_auto_edit(&mut door);
+    assert!(door == original);
+    assert_eq!(
+        serde_json::to_value(&door.target_level).unwrap(),
+        serde_json::json!("levels/next.yol"),
+    );
+}
+
+#[derive(
+    Default, Clone, PartialEq, Serialize, Deserialize, Component, YoleckComponent, YoleckAutoEdit,
+)]
+struct Tint {
+    #[yoleck(color_picker)]
+    rgb: [f32; 3],
+    #[yoleck(color_picker)]
+    rgba: [u8; 4],
+    #[yoleck(color_picker)]
+    hex: u32,
+    #[yoleck(step = 0.25)]
+    intensity: f32,
+    #[yoleck(range(0..=10), step = 2)]
+    layers: u32,
+    #[yoleck(step = 0.5)]
+    offset: Vec2,
+}
+
+#[test]
+fn step_and_color_picker_fields() {
+    // The stored values are only quantized when they are edited.
+    let mut tint = Tint {
+        rgb: [0.1, 0.2, 0.3],
+        rgba: [1, 2, 3, 4],
+        hex: 0xff8800,
+        intensity: 0.3,
+        layers: 3,
+        offset: Vec2::new(0.1, 0.2),
+    };
+    let original = tint.clone();
+    run_auto_edit(&mut tint);
+    assert!(tint == original);
+}
+
+// `Transform` is not serializable without Bevy's `serialize` feature, so this is not a component.
+#[derive(Clone, PartialEq, YoleckAutoEdit)]
+struct Inventory {
+    counts: std::collections::HashMap<String, u16>,
+    tags: std::collections::BTreeSet<String>,
+    slots: [i8; 2],
+    hotkey: char,
+    owner: uuid::Uuid,
+    cooldown: std::time::Duration,
+    cell: IVec2,
+    bounds: Rect,
+    placement: Transform,
+    pickup_area: Circle,
+}
+
+#[test]
+fn std_and_bevy_types() {
+    let mut inventory = Inventory {
+        counts: [("apple".to_owned(), 3), ("pear".to_owned(), 5)].into(),
+        tags: ["food".to_owned(), "fruit".to_owned()].into(),
+        slots: [-1, 2],
+        hotkey: 'i',
+        owner: uuid::Uuid::new_v4(),
+        cooldown: std::time::Duration::from_millis(1500),
+        cell: IVec2::new(-3, 4),
+        bounds: Rect::new(0.0, 0.0, 2.0, 1.0),
+        placement: Transform::from_xyz(1.0, 2.0, 3.0),
+        pickup_area: Circle::new(0.5),
+    };
+    let original = inventory.clone();
+    run_auto_edit(&mut inventory);
+    assert!(inventory == original);
+}
+
+#[derive(Default, Clone, PartialEq, Serialize, Deserialize, YoleckAutoEdit)]
+#[yoleck(summary = Wave::summary)]
+struct Wave {
+    enemy: String,
+    count: u32,
+    behavior: EnemyBehavior,
+}
+
+impl Wave {
+    fn summary(&self) -> String {
+        format!("{} x{}", self.enemy, self.count)
+    }
+}
+
+#[derive(
+    Default, Clone, PartialEq, Serialize, Deserialize, Component, YoleckComponent, YoleckAutoEdit,
+)]
+struct SpawnTable {
+    #[yoleck(min_len = 1, max_len = 3)]
+    waves: Vec<Wave>,
+    delays: Vec<f32>,
+}
+
+#[test]
+fn list_element_summaries() {
+    let wave = Wave {
+        enemy: "goblin".to_owned(),
+        count: 4,
+        behavior: EnemyBehavior::Chase(1.0, 2),
+    };
+    assert_eq!(Wave::auto_edit_summary(&wave).as_deref(), Some("goblin x4"));
+    assert_eq!(
+        EnemyBehavior::auto_edit_summary(&wave.behavior).as_deref(),
+        Some("Chase")
+    );
+    assert_eq!(f32::auto_edit_summary(&1.0), None);
+
+    // Lists outside the length limits are only flagged, not fixed.
+    let mut table = SpawnTable {
+        waves: vec![wave; 4],
+        delays: vec![0.5, 1.0],
+    };
+    let original = table.clone();
+    run_auto_edit(&mut table);
+    assert!(table == original);
+}
+
+/// Not `Clone`, so it cannot be duplicated in lists.
+#[derive(Default, PartialEq, Debug, Serialize, Deserialize, YoleckAutoEdit)]
+struct Trigger {
+    radius: f32,
+}
+
+#[derive(Default, Serialize, Deserialize, YoleckAutoEdit)]
+struct Triggers {
+    triggers: Vec<Trigger>,
+}
+
+#[test]
+fn only_cloneable_list_elements_can_be_duplicated() {
+    let mut table = SpawnTable {
+        waves: Vec::new(),
+        delays: vec![0.5],
+    };
+    assert!(rendered_texts(&mut table).iter().any(|text| text == "🗐"));
+
+    let mut triggers = Triggers {
+        triggers: vec![Trigger { radius: 2.0 }],
+    };
+    let texts = rendered_texts(&mut triggers);
+    assert!(texts.iter().any(|text| text == "−"));
+    assert!(!texts.iter().any(|text| text == "🗐"));
+    assert_eq!(triggers.triggers, [Trigger { radius: 2.0 }]);
+}
+
+fn rendered_texts<T: YoleckAutoEdit>(value: &mut T) -> Vec<String> {
+    let ctx = egui::Context::default();
+    let mut output = ctx.run_ui(Default::default(), |ui| {
+        T::auto_edit(value, ui);
+    });
+    output.textures_delta.clear();
+    output
+        .shapes
+        .iter()
+        .filter_map(|clipped| match &clipped.shape {
+            egui::Shape::Text(text) => Some(text.galley.text().to_owned()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Serialize, Deserialize, YoleckAutoEdit)]
+enum GuardMode {
+    #[default]
+    Stand,
+    Patrol,
+}
+
+#[derive(
+    Default, Clone, PartialEq, Serialize, Deserialize, Component, YoleckComponent, YoleckAutoEdit,
+)]
+struct Guard {
+    mode: GuardMode,
+    #[yoleck(visible_if = "mode == GuardMode::Patrol")]
+    patrol_speed: f32,
+    frozen: bool,
+    #[yoleck(group = "Physics", enabled_if = "!frozen")]
+    mass: f32,
+    name: String,
+    #[yoleck(group = "Physics")]
+    friction: f32,
+}
+
+#[test]
+fn conditional_fields_and_groups() {
+    let mut guard = Guard::default();
+    let texts = rendered_texts(&mut guard);
+    assert!(!texts.iter().any(|text| text == "patrol speed"));
+    // Grouped fields are shown together, where the first field of the group is.
+    let position = |label: &str| texts.iter().position(|text| text == label).unwrap();
+    assert!(position("Physics") < position("mass"));
+    assert!(position("mass") < position("friction"));
+    assert!(position("friction") < position("name"));
+
+    guard.mode = GuardMode::Patrol;
+    let texts = rendered_texts(&mut guard);
+    assert!(texts.iter().any(|text| text == "patrol speed"));
+}
+
+fn validate_even(value: &u32) -> Result<(), String> {
+    if value.is_multiple_of(2) {
+        Ok(())
+    } else {
+        Err(format!("{value} is odd"))
+    }
+}
+
+#[derive(Default, Clone, PartialEq, Serialize, Deserialize, YoleckAutoEdit)]
+struct SpawnPoint {
+    #[yoleck(min = 1, max = 10, validate = validate_even)]
+    count: u32,
+}
+
+#[derive(
+    Default, Clone, PartialEq, Serialize, Deserialize, Component, YoleckComponent, YoleckAutoEdit,
+)]
+struct Spawner {
+    #[yoleck(non_empty)]
+    enemy: String,
+    points: Vec<SpawnPoint>,
+    enabled: bool,
+    #[yoleck(visible_if = "enabled", min = 0.5)]
+    interval: f32,
+}
+
+#[test]
+fn validation_attributes() {
+    use bevy_yoleck::validation::YoleckValidationError;
+
+    let mut spawner = Spawner {
+        enemy: "goblin_king".to_owned(),
+        points: vec![SpawnPoint { count: 2 }],
+        enabled: true,
+        interval: 1.0,
+    };
+    let mut errors = Vec::new();
+    Spawner::auto_edit_validate(&spawner, &Default::default(), &mut errors);
+    assert!(errors.is_empty());
+
+    spawner.points.push(SpawnPoint { count: 11 });
+    spawner.interval = 0.0;
+    let mut errors = Vec::new();
+    Spawner::auto_edit_validate(&spawner, &Default::default(), &mut errors);
+    let error = |field: &str, message: &str| YoleckValidationError {
+        field: field.to_owned(),
+        message: message.to_owned(),
+    };
+    assert_eq!(
+        errors,
+        [
+            error("points.1.count", "Must be at most 10"),
+            error("points.1.count", "11 is odd"),
+            error("interval", "Must be at least 0.5"),
+        ]
+    );
+
+    // Fields that are not visible are not validated.
+    spawner.enabled = false;
+    let mut errors = Vec::new();
+    Spawner::auto_edit_validate(&spawner, &Default::default(), &mut errors);
+    assert_eq!(errors.len(), 2);
+
+    spawner.enemy.clear();
+    let texts = rendered_texts(&mut spawner);
+    assert!(texts.iter().any(|text| text == "Must not be empty"));
+}
+
+#[cfg(feature = "regex")]
+#[derive(Default, Serialize, Deserialize, YoleckAutoEdit)]
+struct EnemyName {
+    #[yoleck(regex = "^[a-z_]*$")]
+    name: String,
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn regex_validation() {
+    use bevy_yoleck::validation::YoleckValidationError;
+
+    let mut enemy_name = EnemyName {
+        name: "goblin_king".to_owned(),
+    };
+    let mut errors = Vec::new();
+    EnemyName::auto_edit_validate(&enemy_name, &Default::default(), &mut errors);
+    assert!(errors.is_empty());
+
+    enemy_name.name = "Goblin".to_owned();
+    let mut errors = Vec::new();
+    EnemyName::auto_edit_validate(&enemy_name, &Default::default(), &mut errors);
+    assert_eq!(
+        errors,
+        [YoleckValidationError {
+            field: "name".to_owned(),
+            message: "Must match `^[a-z_]*$`".to_owned(),
+        }]
+    );
+}
+
+// Stands in for a type from another crate, which cannot implement `YoleckAutoEdit`.
+#[derive(Default, Clone, PartialEq, Serialize, Deserialize)]
+struct Easing {
+    exponent: f32,
+}
+
+fn easing_widget(
+    value: &mut Easing,
+    ui: &mut egui::Ui,
+    label: &str,
+    _attrs: &bevy_yoleck::auto_edit::FieldAttrs,
+) {
+    ui.label(format!("{label}: ease^{}", value.exponent));
+}
+
+fn percent_widget(
+    value: &mut f32,
+    ui: &mut egui::Ui,
+    label: &str,
+    _attrs: &bevy_yoleck::auto_edit::FieldAttrs,
+) {
+    ui.label(format!("{label}: {}%", *value * 100.0));
+}
+
+#[derive(
+    Default, Clone, PartialEq, Serialize, Deserialize, Component, YoleckComponent, YoleckAutoEdit,
+)]
+struct Fade {
+    #[yoleck(widget = percent_widget)]
+    opacity: f32,
+    #[yoleck(registered)]
+    easing: Easing,
+    #[yoleck(registered)]
+    stages: Vec<Easing>,
+    #[yoleck(registered)]
+    fallback: Option<Easing>,
+    #[yoleck(registered)]
+    axes: [Easing; 2],
+    #[yoleck(registered)]
+    named: std::collections::BTreeMap<String, Easing>,
+}
+
+#[test]
+fn custom_widgets() {
+    use bevy_yoleck::auto_edit::YoleckWidgetRegistry;
+
+    let mut fade = Fade {
+        opacity: 0.5,
+        easing: Easing { exponent: 2.0 },
+        stages: vec![Easing { exponent: 3.0 }],
+        fallback: Some(Easing { exponent: 4.0 }),
+        axes: [Easing { exponent: 5.0 }, Easing { exponent: 6.0 }],
+        named: [("slow".to_owned(), Easing { exponent: 7.0 })].into(),
+    };
+    let texts = rendered_texts(&mut fade);
+    assert!(texts.iter().any(|text| text == "opacity: 50%"));
+    // Without a registered widget, only the label is shown.
+    assert!(texts.iter().any(|text| text == "easing"));
+
+    let mut registry = YoleckWidgetRegistry::default();
+    registry.register(easing_widget);
+    assert!(registry.contains::<Easing>());
+    let ctx = egui::Context::default();
+    ctx.data_mut(|data| data.insert_temp(egui::Id::new("yoleck_widget_registry"), registry));
+    let mut output = ctx.run_ui(Default::default(), |ui| {
+        Fade::auto_edit(&mut fade, ui);
+    });
+    output.textures_delta.clear();
+    let shows_text = |expected: &str| {
+        output.shapes.iter().any(|clipped| {
+            matches!(
+                &clipped.shape,
+                egui::Shape::Text(text) if text.galley.text() == expected,
+            )
+        })
+    };
+    assert!(shows_text("easing: ease^2"));
+    // The elements of collections use the widget of their type too.
+    for exponent in 3..=6 {
+        assert!(shows_text(&format!(": ease^{exponent}")));
+    }
+    assert!(!shows_text("⚠"));
+}
+
+#[derive(
+    Default, Clone, PartialEq, Serialize, Deserialize, Component, YoleckComponent, YoleckAutoEdit,
+)]
+#[yoleck(button = Self::reset_path)]
+#[yoleck(button(Self::mark_entity, label = "Mark"))]
+struct PathFollower {
+    path: Vec<Vec2>,
+}
+
+impl PathFollower {
+    fn reset_path(&mut self) {
+        self.path.clear();
+    }
+
+    fn mark_entity(&mut self, ctx: &mut bevy_yoleck::auto_edit::YoleckButtonContext) {
+        ctx.commands.entity(ctx.entity).insert(Name::new("marked"));
+    }
+}
+
+#[test]
+fn button_methods() {
+    use bevy_yoleck::auto_edit::YoleckButtonAction;
+
+    let buttons = PathFollower::auto_edit_buttons();
+    let labels: Vec<&str> = buttons.iter().map(|button| button.label.as_str()).collect();
+    assert_eq!(labels, ["reset path", "Mark"]);
+
+    let mut follower = PathFollower {
+        path: vec![Vec2::ZERO, Vec2::ONE],
+    };
+    let YoleckButtonAction::Mutate(reset_path) = &buttons[0].action else {
+        panic!("`reset_path` does not need a context");
+    };
+    reset_path(&mut follower);
+    assert!(follower.path.is_empty());
+    assert!(matches!(
+        buttons[1].action,
+        YoleckButtonAction::WithContext(_)
+    ));
+    assert!(Health::auto_edit_buttons().is_empty());
+}
+
+#[derive(Default, Clone, PartialEq, Serialize, Deserialize, YoleckAutoEdit)]
+struct Portal {
+    target: YoleckLevelPath,
+}
+
+#[derive(Default, Clone, PartialEq, Serialize, Deserialize, YoleckAutoEdit)]
+enum Exit {
+    #[default]
+    Locked,
+    Stairs {
+        label: String,
+        portals: Vec<Portal>,
+        fallback: Option<YoleckLevelPath>,
+    },
+}
+
+#[test]
+fn level_path_fields() {
+    let mut portal = Portal {
+        target: YoleckLevelPath::new("cave.yol"),
+    };
+    assert_eq!(
+        serde_json::to_value(&portal).unwrap(),
+        serde_json::json!({"target": "cave.yol"}),
+    );
+    assert_eq!(portal.target.asset_path("levels/"), "levels/cave.yol");
+    assert_eq!(portal.target.asset_path(""), "cave.yol");
+
+    let texts = rendered_texts(&mut portal);
+    assert!(texts.iter().any(|text| text == "cave.yol"));
+    assert!(!texts.iter().any(|text| text == "⚠"));
+
+    // Only warns about a missing level when the levels directory is known.
+    let ctx = egui::Context::default();
+    ctx.data_mut(|data| {
+        data.insert_temp(
+            egui::Id::new("yoleck_editable_levels"),
+            std::sync::Arc::new(vec!["town.yol".to_owned()]),
+        )
+    });
+    let mut output = ctx.run_ui(Default::default(), |ui| {
+        Portal::auto_edit(&mut portal, ui);
+    });
+    output.textures_delta.clear();
+    assert!(output.shapes.iter().any(|clipped| matches!(
+        &clipped.shape,
+        egui::Shape::Text(text) if text.galley.text() == "⚠",
+    )));
+}
+
+#[test]
+fn level_paths_are_validated_against_the_known_levels() {
+    use bevy_yoleck::validation::YoleckValidationContext;
+
+    let portal = Portal {
+        target: YoleckLevelPath::new("cave.yol"),
+    };
+    let mut errors = Vec::new();
+    Portal::auto_edit_validate(&portal, &Default::default(), &mut errors);
+    assert!(errors.is_empty());
+
+    let known_levels = ["town.yol".to_owned()].into_iter().collect();
+    let ctx = YoleckValidationContext {
+        known_levels: Some(&known_levels),
+    };
+    Portal::auto_edit_validate(&portal, &ctx, &mut errors);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].field, "target");
+}
+
+#[test]
+fn level_paths_are_visited_by_type() {
+    let mut exit = Exit::Stairs {
+        label: "cave.yol".to_owned(),
+        portals: vec![
+            Portal {
+                target: YoleckLevelPath::new("cave.yol"),
+            },
+            Portal {
+                target: YoleckLevelPath::new("town.yol"),
+            },
+        ],
+        fallback: Some(YoleckLevelPath::new("cave.yol")),
+    };
+    let mut visited = Vec::new();
+    Exit::auto_edit_level_paths(&mut exit, &mut |level_path| {
+        visited.push(level_path.filename.clone());
+        if level_path.filename == "cave.yol" {
+            level_path.filename = "mine.yol".to_owned();
+        }
+    });
+    assert_eq!(visited, ["cave.yol", "town.yol", "cave.yol"]);
+    let Exit::Stairs {
+        label,
+        portals,
+        fallback,
+    } = &exit
+    else {
+        unreachable!();
+    };
+    // Strings that happen to be equal to the level's name are not level paths.
+    assert_eq!(label, "cave.yol");
+    assert_eq!(portals[0].target.filename, "mine.yol");
+    assert_eq!(fallback.as_ref().unwrap().filename, "mine.yol");
+}
+
+#[derive(Default, Clone, PartialEq, Serialize, Deserialize, YoleckAutoEdit)]
+struct Emitter {
+    #[yoleck(range(0..=5))]
+    size: bevy_yoleck::curve::YoleckCurve,
+    tint: bevy_yoleck::curve::YoleckGradient,
+}
+
+#[test]
+fn curves_and_gradients() {
+    use bevy_yoleck::curve::{YoleckCurve, YoleckCurveKey, YoleckGradient, YoleckInterpolation};
+
+    let curve = YoleckCurve::linear([(1.0, 4.0), (0.0, 2.0)]);
+    assert_eq!(curve.keys()[0].time, 0.0);
+    assert_eq!(curve.value_at(-1.0), 2.0);
+    assert_eq!(curve.value_at(0.5), 3.0);
+    assert_eq!(curve.value_at(2.0), 4.0);
+    let with_interpolation = |interpolation| {
+        YoleckCurve::from_keys(curve.keys().iter().map(|key| YoleckCurveKey {
+            interpolation,
+            ..*key
+        }))
+    };
+    assert_eq!(
+        with_interpolation(YoleckInterpolation::Constant).value_at(0.9),
+        2.0
+    );
+    assert!(with_interpolation(YoleckInterpolation::Smooth).value_at(0.25) < 2.5);
+    assert_eq!(YoleckCurve::default().value_at(1.0), 0.0);
+    assert_eq!(
+        serde_json::from_value::<YoleckCurve>(serde_json::json!([{"time": 0.0, "value": 1.0}]))
+            .unwrap(),
+        YoleckCurve::from_keys([YoleckCurveKey {
+            time: 0.0,
+            value: 1.0,
+            interpolation: YoleckInterpolation::Linear,
+        }]),
+    );
+    // Keys that were written out of order (e.g. by hand) are sorted when loaded.
+    let loaded: YoleckCurve = serde_json::from_value(serde_json::json!([
+        {"time": 1.0, "value": 4.0},
+        {"time": 0.0, "value": 2.0},
+    ]))
+    .unwrap();
+    assert_eq!(loaded, curve);
+    assert_eq!(
+        serde_json::to_value(&loaded).unwrap(),
+        serde_json::to_value(curve.keys()).unwrap(),
+    );
+
+    let gradient = YoleckGradient::linear([(0.0, Color::BLACK), (1.0, Color::WHITE)]);
+    assert_eq!(gradient.color_at(0.0), Color::BLACK);
+    let middle = gradient.color_at(0.5).to_srgba();
+    assert!(0.2 < middle.red && middle.red < 0.8);
+
+    let mut emitter = Emitter {
+        size: curve,
+        tint: gradient,
+    };
+    let texts = rendered_texts(&mut emitter);
+    assert!(texts.iter().any(|text| text == "size"));
+    assert!(texts.iter().any(|text| text == "tint"));
+    // The value axis of the plot follows the range of the field.
+    assert!(texts.iter().any(|text| text == "5.00"));
+}
@@ -0,0 +1,109 @@
+mod common;
+
+use std::collections::{BTreeMap, HashMap};
+
+use bevy::prelude::*;
+use bevy_yoleck::YoleckDirective;
+use bevy_yoleck::bevy_egui::egui;
+use bevy_yoleck::prelude::*;
+use bevy_yoleck::validation::YoleckValidationContext;
+use serde::{Deserialize, Serialize};
+
+use common::{EditorHarness, find_text};
+
+#[derive(
+    Default, Clone, PartialEq, Serialize, Deserialize, Component, YoleckComponent, YoleckAutoEdit,
+)]
+struct Inventory {
+    items: HashMap<String, i32>,
+}
+
+#[test]
+fn map_key_is_renamed_when_editing_is_done() {
+    let mut harness = EditorHarness::new(|app| {
+        app.add_yoleck_entity_type(YoleckEntityType::new("Chest").with::<Inventory>());
+        app.add_yoleck_auto_edit::<Inventory>();
+    });
+    let level = harness.app.world_mut().spawn(YoleckKeepLevel).id();
+    harness.app.world_mut().write_message::<YoleckDirective>(
+        YoleckDirective::spawn_entity(level, "Chest", false)
+            .with(Inventory {
+                items: HashMap::from([("apple".to_owned(), 1), ("pear".to_owned(), 2)]),
+            })
+            .into(),
+    );
+    harness.settle();
+    let chest = harness
+        .app
+        .world_mut()
+        .query_filtered::<Entity, With<Inventory>>()
+        .single(harness.app.world())
+        .unwrap();
+    harness
+        .app
+        .world_mut()
+        .write_message(YoleckDirective::set_selected(Some(chest)));
+
+    harness.click_text("items");
+    harness.click_text("apple");
+    for text in ["x", "y", "z"] {
+        harness.frame(vec![egui::Event::Text(text.to_owned())]);
+        let inventory = harness.app.world().get::<Inventory>(chest).unwrap();
+        assert_eq!(
+            inventory.items.get("apple"),
+            Some(&1),
+            "the key should not be renamed while it is being edited"
+        );
+    }
+    let output = harness.settle();
+    assert!(find_text(&output, "apple").is_none());
+    assert!(find_text(&output, "pear").is_some());
+
+    harness.frame(vec![egui::Event::Key {
+        key: egui::Key::Enter,
+        physical_key: None,
+        pressed: true,
+        repeat: false,
+        modifiers: Default::default(),
+    }]);
+    harness.settle();
+
+    let inventory = harness.app.world().get::<Inventory>(chest).unwrap();
+    assert_eq!(inventory.items.len(), 2);
+    assert_eq!(inventory.items.get("pear"), Some(&2));
+    let (renamed_key, value) = inventory
+        .items
+        .iter()
+        .find(|(key, _)| *key != "pear")
+        .unwrap();
+    assert_eq!(renamed_key.len(), "applexyz".len());
+    assert!(renamed_key.contains("xyz"));
+    assert_eq!(*value, 1);
+}
+
+#[derive(Default, Clone, PartialEq, Serialize, Deserialize, YoleckAutoEdit)]
+struct Wave {
+    #[yoleck(min = 1)]
+    count: u32,
+}
+
+#[derive(
+    Default, Clone, PartialEq, Serialize, Deserialize, Component, YoleckComponent, YoleckAutoEdit,
+)]
+struct Spawner {
+    waves: BTreeMap<String, Wave>,
+}
+
+#[test]
+fn map_values_are_validated() {
+    let spawner = Spawner {
+        waves: BTreeMap::from([
+            ("first".to_owned(), Wave { count: 3 }),
+            ("second".to_owned(), Wave { count: 0 }),
+        ]),
+    };
+    let mut errors = Vec::new();
+    Spawner::auto_edit_validate(&spawner, &YoleckValidationContext::default(), &mut errors);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].field, "waves.second.count");
+}
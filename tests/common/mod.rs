@@ -0,0 +1,118 @@
+//! A headless Yoleck editor for tests that need the editor's UI to run.
+#![allow(dead_code)]
+
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use bevy_yoleck::bevy_egui::{
+    EguiContext, EguiPrimaryContextPass, EguiUserTextures, PrimaryEguiContext, egui,
+};
+use bevy_yoleck::prelude::*;
+
+const SCREEN_SIZE: egui::Vec2 = egui::vec2(1280.0, 720.0);
+
+pub struct EditorHarness {
+    pub app: App,
+    ctx: egui::Context,
+    pointer: egui::Pos2,
+}
+
+impl EditorHarness {
+    pub fn new(setup: impl FnOnce(&mut App)) -> Self {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default(), StatesPlugin))
+            .add_plugins(YoleckPluginForEditor)
+            .init_resource::<ButtonInput<MouseButton>>()
+            .init_resource::<ButtonInput<KeyCode>>()
+            .init_resource::<EguiUserTextures>();
+        setup(&mut app);
+        let ctx = egui::Context::default();
+        app.world_mut()
+            .spawn((EguiContext::default(), PrimaryEguiContext));
+        let mut harness = Self {
+            app,
+            ctx,
+            pointer: egui::Pos2::ZERO,
+        };
+        harness.sync_context();
+        harness
+    }
+
+    fn sync_context(&mut self) {
+        let mut query = self
+            .app
+            .world_mut()
+            .query_filtered::<&mut EguiContext, With<PrimaryEguiContext>>();
+        let mut egui_context = query.single_mut(self.app.world_mut()).unwrap();
+        self.ctx = egui_context.get_mut().clone();
+    }
+
+    /// Run one frame of the app, with a full egui pass that receives `events`.
+    pub fn frame(&mut self, events: Vec<egui::Event>) -> egui::FullOutput {
+        self.ctx.begin_pass(egui::RawInput {
+            screen_rect: Some(egui::Rect::from_min_size(egui::Pos2::ZERO, SCREEN_SIZE)),
+            events,
+            ..Default::default()
+        });
+        self.app.update();
+        self.app.world_mut().run_schedule(EguiPrimaryContextPass);
+        let mut output = self.ctx.end_pass();
+        output.textures_delta.clear();
+        output
+    }
+
+    /// Run enough frames for the UI to settle, returning the output of the last one.
+    pub fn settle(&mut self) -> egui::FullOutput {
+        for _ in 0..3 {
+            self.frame(Vec::new());
+        }
+        self.frame(Vec::new())
+    }
+
+    /// Click the center of `rect` with the primary mouse button.
+    pub fn click(&mut self, rect: egui::Rect) {
+        self.pointer = rect.center();
+        self.frame(vec![egui::Event::PointerMoved(self.pointer)]);
+        for pressed in [true, false] {
+            self.frame(vec![egui::Event::PointerButton {
+                pos: self.pointer,
+                button: egui::PointerButton::Primary,
+                pressed,
+                modifiers: Default::default(),
+            }]);
+        }
+    }
+
+    /// Click the text that the UI currently shows. Panics if the text is not shown.
+    pub fn click_text(&mut self, text: &str) {
+        let output = self.settle();
+        let rect = find_text(&output, text)
+            .unwrap_or_else(|| panic!("{text:?} is not shown in the editor"));
+        self.click(rect);
+    }
+
+    /// Press a keyboard key for a single frame, as Bevy input (for Vpeol's keyboard shortcuts).
+    pub fn press_key(&mut self, key: KeyCode) {
+        self.app
+            .world_mut()
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(key);
+        self.frame(Vec::new());
+        let mut keyboard_input = self.app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+        keyboard_input.release(key);
+        keyboard_input.clear();
+    }
+}
+
+/// The rectangle of a text the UI painted, if it painted it.
+pub fn find_text(output: &egui::FullOutput, text: &str) -> Option<egui::Rect> {
+    output.shapes.iter().find_map(|clipped_shape| {
+        let egui::epaint::Shape::Text(text_shape) = &clipped_shape.shape else {
+            return None;
+        };
+        (text_shape.galley.text() == text).then(|| {
+            text_shape
+                .visual_bounding_rect()
+                .intersect(clipped_shape.clip_rect)
+        })
+    })
+}
@@ -0,0 +1,129 @@
+#![cfg(feature = "vpeol")]
+
+mod common;
+
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+use bevy_yoleck::YoleckDirective;
+use bevy_yoleck::prelude::*;
+use bevy_yoleck::vpeol::{VpeolBasePlugin, VpeolDragPlane};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use common::EditorHarness;
+
+#[derive(Default, Clone, PartialEq, Serialize, Deserialize, Component, YoleckComponent)]
+struct Planet;
+
+#[derive(
+    Default, Clone, PartialEq, Serialize, Deserialize, Component, YoleckComponent, YoleckAutoEdit,
+)]
+struct LaserPointer {
+    #[yoleck(entity_ref = "Planet")]
+    target: YoleckEntityRef,
+}
+
+#[test]
+fn duplicating_remaps_only_references_between_the_copies() {
+    let mut harness = EditorHarness::new(|app| {
+        app.insert_resource(VpeolDragPlane::XY)
+            .add_plugins(VpeolBasePlugin);
+        app.add_yoleck_entity_type(YoleckEntityType::new("Planet").with::<Planet>().with_uuid());
+        app.add_yoleck_entity_type(YoleckEntityType::new("LaserPointer").with::<LaserPointer>());
+        app.add_yoleck_auto_edit::<LaserPointer>();
+    });
+    let level = harness.app.world_mut().spawn(YoleckKeepLevel).id();
+    let copied_planet_uuid = Uuid::new_v4();
+    let other_planet_uuid = Uuid::new_v4();
+    for planet_uuid in [copied_planet_uuid, other_planet_uuid] {
+        harness.app.world_mut().write_message::<YoleckDirective>(
+            YoleckDirective::spawn_entity(level, "Planet", false)
+                .with_uuid(planet_uuid)
+                .into(),
+        );
+        harness.app.world_mut().write_message::<YoleckDirective>(
+            YoleckDirective::spawn_entity(level, "LaserPointer", false)
+                .with(LaserPointer {
+                    target: YoleckEntityRef::from_uuid(planet_uuid),
+                })
+                .into(),
+        );
+    }
+    harness.settle();
+
+    // Select one planet and both laser pointers - one of them points at the selected planet, and
+    // the other at a planet that is not copied.
+    let copied_planet = harness
+        .app
+        .world()
+        .resource::<YoleckUuidRegistry>()
+        .get(copied_planet_uuid)
+        .unwrap();
+    harness
+        .app
+        .world_mut()
+        .write_message(YoleckDirective::set_selected(Some(copied_planet)));
+    let laser_pointers = harness
+        .app
+        .world_mut()
+        .query_filtered::<Entity, With<LaserPointer>>()
+        .iter(harness.app.world())
+        .collect::<Vec<_>>();
+    for laser_pointer in laser_pointers {
+        harness
+            .app
+            .world_mut()
+            .write_message(YoleckDirective::toggle_selected(laser_pointer));
+    }
+    harness.settle();
+
+    harness
+        .app
+        .world_mut()
+        .resource_mut::<ButtonInput<KeyCode>>()
+        .press(KeyCode::ControlLeft);
+    harness.press_key(KeyCode::KeyD);
+    harness
+        .app
+        .world_mut()
+        .resource_mut::<ButtonInput<KeyCode>>()
+        .release(KeyCode::ControlLeft);
+    harness.settle();
+
+    let planet_uuids = harness
+        .app
+        .world_mut()
+        .query_filtered::<&YoleckEntityUuid, With<Planet>>()
+        .iter(harness.app.world())
+        .map(|entity_uuid| entity_uuid.get())
+        .collect::<HashSet<_>>();
+    assert_eq!(planet_uuids.len(), 3);
+    let [planet_copy_uuid] = planet_uuids
+        .iter()
+        .copied()
+        .filter(|uuid| ![copied_planet_uuid, other_planet_uuid].contains(uuid))
+        .collect::<Vec<_>>()[..]
+    else {
+        panic!("the copy of the planet should get a new UUID");
+    };
+
+    let mut targets = harness
+        .app
+        .world_mut()
+        .query::<&LaserPointer>()
+        .iter(harness.app.world())
+        .map(|laser_pointer| laser_pointer.target.uuid().unwrap())
+        .collect::<Vec<_>>();
+    targets.sort();
+    let mut expected_targets = vec![
+        copied_planet_uuid,
+        other_planet_uuid,
+        // The reference to the copied planet is remapped to its copy
+        planet_copy_uuid,
+        // The reference to the planet that was not copied is kept
+        other_planet_uuid,
+    ];
+    expected_targets.sort();
+    assert_eq!(targets, expected_targets);
+}
@@ -0,0 +1,111 @@
+#![cfg(feature = "vpeol")]
+
+mod common;
+
+use bevy::prelude::*;
+use bevy_yoleck::YoleckDirective;
+use bevy_yoleck::prelude::*;
+use bevy_yoleck::vpeol::{VpeolBasePlugin, VpeolDragPlane};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use common::{EditorHarness, find_text};
+
+#[derive(Default, Clone, PartialEq, Serialize, Deserialize, Component, YoleckComponent)]
+struct Planet;
+
+#[derive(
+    Default, Clone, PartialEq, Serialize, Deserialize, Component, YoleckComponent, YoleckAutoEdit,
+)]
+struct LaserPointer {
+    #[yoleck(entity_ref = "Planet")]
+    target: YoleckEntityRef,
+}
+
+/// Spawn a planet and a laser pointer that points at it, select the planet and press Delete.
+fn request_deleting_referenced_planet() -> (EditorHarness, Entity, Entity) {
+    let mut harness = EditorHarness::new(|app| {
+        app.insert_resource(VpeolDragPlane::XY)
+            .add_plugins(VpeolBasePlugin);
+        app.add_yoleck_entity_type(YoleckEntityType::new("Planet").with::<Planet>().with_uuid());
+        app.add_yoleck_entity_type(YoleckEntityType::new("LaserPointer").with::<LaserPointer>());
+        app.add_yoleck_auto_edit::<LaserPointer>();
+    });
+    let level = harness.app.world_mut().spawn(YoleckKeepLevel).id();
+    let planet_uuid = Uuid::new_v4();
+    harness.app.world_mut().write_message::<YoleckDirective>(
+        YoleckDirective::spawn_entity(level, "Planet", false)
+            .with_uuid(planet_uuid)
+            .into(),
+    );
+    harness.app.world_mut().write_message::<YoleckDirective>(
+        YoleckDirective::spawn_entity(level, "LaserPointer", false)
+            .with(LaserPointer {
+                target: YoleckEntityRef::from_uuid(planet_uuid),
+            })
+            .into(),
+    );
+    harness.settle();
+
+    let planet = harness
+        .app
+        .world()
+        .resource::<YoleckUuidRegistry>()
+        .get(planet_uuid)
+        .unwrap();
+    let laser_pointer = harness
+        .app
+        .world_mut()
+        .query_filtered::<Entity, With<LaserPointer>>()
+        .single(harness.app.world())
+        .unwrap();
+    harness
+        .app
+        .world_mut()
+        .write_message(YoleckDirective::set_selected(Some(planet)));
+    harness.settle();
+
+    harness.press_key(KeyCode::Delete);
+    let output = harness.settle();
+    assert!(
+        harness.app.world().get_entity(planet).is_ok(),
+        "the deletion should wait for a confirmation"
+    );
+    assert!(find_text(&output, "Delete Referenced Entities?").is_some());
+
+    (harness, planet, laser_pointer)
+}
+
+#[test]
+fn confirming_deletion_clears_references() {
+    let (mut harness, planet, laser_pointer) = request_deleting_referenced_planet();
+
+    harness.click_text("Delete and Clear References");
+    let output = harness.settle();
+
+    assert!(harness.app.world().get_entity(planet).is_err());
+    let laser_pointer = harness
+        .app
+        .world()
+        .get::<LaserPointer>(laser_pointer)
+        .unwrap();
+    assert!(laser_pointer.target.is_none());
+    assert!(find_text(&output, "Delete Referenced Entities?").is_none());
+}
+
+#[test]
+fn cancelling_deletion_keeps_entities_and_references() {
+    let (mut harness, planet, laser_pointer) = request_deleting_referenced_planet();
+
+    harness.click_text("Cancel");
+    let output = harness.settle();
+
+    assert!(harness.app.world().get_entity(planet).is_ok());
+    let laser_pointer = harness
+        .app
+        .world()
+        .get::<LaserPointer>(laser_pointer)
+        .unwrap();
+    assert!(laser_pointer.target.is_some());
+    assert!(find_text(&output, "Delete Referenced Entities?").is_none());
+}
@@ -0,0 +1,125 @@
+#![cfg(feature = "vpeol")]
+
+mod common;
+
+use bevy::camera::{RenderTargetInfo, Viewport};
+use bevy::prelude::*;
+use bevy_yoleck::YoleckDirective;
+use bevy_yoleck::bevy_egui::egui;
+use bevy_yoleck::prelude::*;
+use bevy_yoleck::vpeol::{
+    VpeolBasePlugin, VpeolCameraState, VpeolDragPlane, VpeolEntityRefLines,
+    VpeolEntityRefLinesPlugin,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use common::EditorHarness;
+
+#[derive(Default, Clone, PartialEq, Serialize, Deserialize, Component, YoleckComponent)]
+struct Planet;
+
+#[derive(
+    Default, Clone, PartialEq, Serialize, Deserialize, Component, YoleckComponent, YoleckAutoEdit,
+)]
+struct LaserPointer {
+    #[yoleck(entity_ref = "Planet")]
+    target: YoleckEntityRef,
+}
+
+#[test]
+fn arrow_points_at_target_in_offset_viewport() {
+    let mut harness = EditorHarness::new(|app| {
+        app.insert_resource(VpeolDragPlane::XY)
+            .add_plugins(VpeolBasePlugin)
+            .add_plugins(VpeolEntityRefLinesPlugin {
+                mode: VpeolEntityRefLines::All,
+            });
+        app.add_yoleck_entity_type(YoleckEntityType::new("Planet").with::<Planet>().with_uuid());
+        app.add_yoleck_entity_type(YoleckEntityType::new("LaserPointer").with::<LaserPointer>());
+        app.add_yoleck_auto_edit::<LaserPointer>();
+    });
+
+    // A 400x300 viewport whose origin is at (200, 100) of the window, showing the world from -200
+    // to 200 horizontally and from -150 to 150 vertically.
+    let mut camera = Camera {
+        viewport: Some(Viewport {
+            physical_position: UVec2::new(200, 100),
+            physical_size: UVec2::new(400, 300),
+            ..default()
+        }),
+        ..default()
+    };
+    camera.computed.target_info = Some(RenderTargetInfo {
+        physical_size: UVec2::new(1280, 720),
+        scale_factor: 1.0,
+    });
+    camera.computed.clip_from_view =
+        Mat4::orthographic_rh(-200.0, 200.0, -150.0, 150.0, -1000.0, 1000.0);
+    harness.app.world_mut().spawn((
+        camera,
+        GlobalTransform::IDENTITY,
+        VpeolCameraState::default(),
+    ));
+
+    let level = harness.app.world_mut().spawn(YoleckKeepLevel).id();
+    let planet_uuid = Uuid::new_v4();
+    harness.app.world_mut().write_message::<YoleckDirective>(
+        YoleckDirective::spawn_entity(level, "Planet", false)
+            .with_uuid(planet_uuid)
+            .into(),
+    );
+    harness.app.world_mut().write_message::<YoleckDirective>(
+        YoleckDirective::spawn_entity(level, "LaserPointer", false)
+            .with(LaserPointer {
+                target: YoleckEntityRef::from_uuid(planet_uuid),
+            })
+            .into(),
+    );
+    harness.settle();
+
+    let planet = harness
+        .app
+        .world()
+        .resource::<YoleckUuidRegistry>()
+        .get(planet_uuid)
+        .unwrap();
+    let laser_pointer = harness
+        .app
+        .world_mut()
+        .query_filtered::<Entity, With<LaserPointer>>()
+        .single(harness.app.world())
+        .unwrap();
+    harness
+        .app
+        .world_mut()
+        .entity_mut(planet)
+        .insert(GlobalTransform::from_xyz(100.0, 0.0, 0.0));
+    harness
+        .app
+        .world_mut()
+        .entity_mut(laser_pointer)
+        .insert(GlobalTransform::from_xyz(-100.0, 0.0, 0.0));
+
+    let output = harness.settle();
+    let [source, arrow_base] = output
+        .shapes
+        .iter()
+        .find_map(|clipped_shape| match &clipped_shape.shape {
+            egui::epaint::Shape::LineSegment { points, stroke } if stroke.width == 2.0 => {
+                Some(*points)
+            }
+            _ => None,
+        })
+        .expect("the arrow should be drawn");
+    // The viewport starts at (200, 100), and the entities are at 1/4 and 3/4 of its width and at
+    // half its height. The arrow's head is 10 points long.
+    assert!(
+        source.distance(egui::pos2(300.0, 250.0)) < 0.5,
+        "arrow starts at {source:?}"
+    );
+    assert!(
+        arrow_base.distance(egui::pos2(490.0, 250.0)) < 0.5,
+        "arrow's head starts at {arrow_base:?}"
+    );
+}
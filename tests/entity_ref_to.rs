@@ -0,0 +1,223 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use bevy_yoleck::bevy_egui::egui;
+use bevy_yoleck::prelude::*;
+use bevy_yoleck::{YoleckDirective, YoleckManaged};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use common::EditorHarness;
+
+#[derive(Default, Clone, PartialEq, Serialize, Deserialize, Component, YoleckComponent)]
+struct Planet;
+
+#[derive(Default, Clone, PartialEq, Serialize, Deserialize, Component, YoleckComponent)]
+struct Moon;
+
+#[derive(
+    Default, Clone, PartialEq, Serialize, Deserialize, Component, YoleckComponent, YoleckAutoEdit,
+)]
+struct LaserPointer {
+    target: YoleckEntityRefTo<Planet>,
+}
+
+#[derive(Component)]
+struct Comet;
+
+#[derive(Component, Default)]
+struct IsMoon;
+
+#[derive(
+    Default, Clone, PartialEq, Serialize, Deserialize, Component, YoleckComponent, YoleckAutoEdit,
+)]
+struct Telescope {
+    target: YoleckEntityRefTo<IsMoon>,
+}
+
+#[derive(
+    Default, Clone, PartialEq, Serialize, Deserialize, Component, YoleckComponent, YoleckAutoEdit,
+)]
+struct CometTracker {
+    target: YoleckEntityRefTo<Comet>,
+}
+
+#[derive(
+    Default, Clone, PartialEq, Serialize, Deserialize, Component, YoleckComponent, YoleckAutoEdit,
+)]
+struct OldCometTracker {
+    #[yoleck(entity_ref = "Comet")]
+    target: YoleckEntityRef,
+}
+
+fn add_entity_types(app: &mut App) {
+    app.add_yoleck_entity_type(YoleckEntityType::new("Planet").with::<Planet>().with_uuid());
+    app.add_yoleck_entity_type(YoleckEntityType::new("GasGiant").extends("Planet"));
+    app.add_yoleck_entity_type(
+        YoleckEntityType::new("Moon")
+            .with::<Moon>()
+            .with_marker::<IsMoon>()
+            .with_uuid(),
+    );
+    app.add_yoleck_entity_type(YoleckEntityType::new("LaserPointer").with::<LaserPointer>());
+    app.add_yoleck_auto_edit::<LaserPointer>();
+    app.add_yoleck_entity_type(YoleckEntityType::new("Telescope").with::<Telescope>());
+    app.add_yoleck_auto_edit::<Telescope>();
+}
+
+#[test]
+fn typed_reference_is_saved_like_untyped_reference() {
+    let uuid = Uuid::new_v4();
+    let typed = serde_json::to_value(YoleckEntityRefTo::<Planet>::from_uuid(uuid)).unwrap();
+    assert_eq!(typed, serde_json::json!({"uuid": uuid}));
+    assert_eq!(
+        typed,
+        serde_json::to_value(YoleckEntityRef::from_uuid(uuid)).unwrap()
+    );
+
+    let untyped_data = serde_json::to_value(YoleckEntityRef::from_uuid(uuid)).unwrap();
+    let typed: YoleckEntityRefTo<Planet> = serde_json::from_value(untyped_data).unwrap();
+    assert_eq!(typed.uuid(), Some(uuid));
+
+    let empty: YoleckEntityRefTo<Planet> = serde_json::from_value(serde_json::json!({})).unwrap();
+    assert!(empty.is_none());
+}
+
+#[test]
+fn typed_reference_only_resolves_to_entities_with_its_marker() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default(), StatesPlugin))
+        .add_plugins(YoleckPluginForGame);
+    add_entity_types(&mut app);
+
+    let planet_uuid = Uuid::new_v4();
+    let gas_giant_uuid = Uuid::new_v4();
+    let moon_uuid = Uuid::new_v4();
+    let level: YoleckRawLevel = serde_json::from_value(serde_json::json!([
+        {"format_version": 2, "app_format_version": 0},
+        {},
+        [
+            [{"type": "Planet", "name": "planet", "uuid": planet_uuid}, {}],
+            [{"type": "GasGiant", "name": "gas giant", "uuid": gas_giant_uuid}, {}],
+            [{"type": "Moon", "name": "moon", "uuid": moon_uuid}, {}],
+            [{"type": "LaserPointer", "name": "planet"}, {"LaserPointer": {"target": {"uuid": planet_uuid}}}],
+            [{"type": "LaserPointer", "name": "gas giant"}, {"LaserPointer": {"target": {"uuid": gas_giant_uuid}}}],
+            [{"type": "LaserPointer", "name": "moon"}, {"LaserPointer": {"target": {"uuid": moon_uuid}}}],
+            [{"type": "Telescope", "name": "moon"}, {"Telescope": {"target": {"uuid": moon_uuid}}}],
+            [{"type": "Telescope", "name": "planet"}, {"Telescope": {"target": {"uuid": planet_uuid}}}],
+        ],
+    ]))
+    .unwrap();
+    let level = app
+        .world_mut()
+        .resource_mut::<Assets<YoleckRawLevel>>()
+        .add(level);
+    app.world_mut().spawn(YoleckLoadLevel(level));
+    app.update();
+    app.update();
+
+    let entity_named = |app: &mut App, type_name: &str, name: &str| {
+        app.world_mut()
+            .query::<(Entity, &YoleckManaged)>()
+            .iter(app.world())
+            .find(|(_, managed)| managed.type_name == type_name && managed.name == name)
+            .map(|(entity, _)| entity)
+            .unwrap()
+    };
+    let target_of = |app: &mut App, name: &str| {
+        let laser_pointer = entity_named(app, "LaserPointer", name);
+        let laser_pointer = app.world().get::<LaserPointer>(laser_pointer).unwrap();
+        laser_pointer.target.entity()
+    };
+
+    let planet = entity_named(&mut app, "Planet", "planet");
+    assert_eq!(target_of(&mut app, "planet"), Some(planet));
+    let gas_giant = entity_named(&mut app, "GasGiant", "gas giant");
+    assert_eq!(target_of(&mut app, "gas giant"), Some(gas_giant));
+    assert_eq!(target_of(&mut app, "moon"), None);
+
+    let telescope_target_of = |app: &mut App, name: &str| {
+        let telescope = entity_named(app, "Telescope", name);
+        let telescope = app.world().get::<Telescope>(telescope).unwrap();
+        telescope.target.entity()
+    };
+    let moon = entity_named(&mut app, "Moon", "moon");
+    assert!(app.world().get::<IsMoon>(moon).is_some());
+    assert_eq!(telescope_target_of(&mut app, "moon"), Some(moon));
+    assert_eq!(telescope_target_of(&mut app, "planet"), None);
+}
+
+#[test]
+fn typed_reference_to_unused_marker_does_not_panic() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default(), StatesPlugin))
+        .add_plugins(YoleckPluginForGame);
+    app.add_yoleck_entity_type(YoleckEntityType::new("CometTracker").with::<CometTracker>());
+    app.add_yoleck_auto_edit::<CometTracker>();
+    app.update();
+}
+
+#[test]
+fn untyped_reference_to_unregistered_entity_type_does_not_panic() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default(), StatesPlugin))
+        .add_plugins(YoleckPluginForGame);
+    app.add_yoleck_entity_type(YoleckEntityType::new("CometTracker").with::<OldCometTracker>());
+    app.add_yoleck_auto_edit::<OldCometTracker>();
+    app.update();
+}
+
+/// Whether the UI painted a text that starts with `prefix`.
+fn shows_text_starting_with(output: &egui::FullOutput, prefix: &str) -> bool {
+    output.shapes.iter().any(|clipped_shape| {
+        matches!(
+            &clipped_shape.shape,
+            egui::epaint::Shape::Text(text_shape) if text_shape.galley.text().starts_with(prefix)
+        )
+    })
+}
+
+#[test]
+fn picker_only_offers_entities_of_the_target_type() {
+    let mut harness = EditorHarness::new(add_entity_types);
+    let level = harness.app.world_mut().spawn(YoleckKeepLevel).id();
+    let planet_uuid = Uuid::new_v4();
+    for (type_name, uuid) in [
+        ("Planet", planet_uuid),
+        ("GasGiant", Uuid::new_v4()),
+        ("Moon", Uuid::new_v4()),
+    ] {
+        harness.app.world_mut().write_message::<YoleckDirective>(
+            YoleckDirective::spawn_entity(level, type_name, false)
+                .with_uuid(uuid)
+                .into(),
+        );
+    }
+    harness.app.world_mut().write_message::<YoleckDirective>(
+        YoleckDirective::spawn_entity(level, "LaserPointer", false)
+            .with(LaserPointer {
+                target: YoleckEntityRefTo::from_uuid(planet_uuid),
+            })
+            .into(),
+    );
+    harness.settle();
+
+    let laser_pointer = harness
+        .app
+        .world_mut()
+        .query_filtered::<Entity, With<LaserPointer>>()
+        .single(harness.app.world())
+        .unwrap();
+    harness
+        .app
+        .world_mut()
+        .write_message(YoleckDirective::set_selected(Some(laser_pointer)));
+
+    // The combo box shows the current target.
+    harness.click_text(&format!("Planet ({})", &planet_uuid.to_string()[..8]));
+    let output = harness.settle();
+
+    assert!(shows_text_starting_with(&output, "GasGiant ("));
+    assert!(!shows_text_starting_with(&output, "Moon ("));
+}
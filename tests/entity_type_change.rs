@@ -0,0 +1,114 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_yoleck::prelude::*;
+use bevy_yoleck::{YoleckDirective, YoleckManaged};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use common::EditorHarness;
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize, Component, YoleckComponent)]
+struct Mass {
+    kg: f32,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize, Component, YoleckComponent)]
+struct Beacon {
+    frequency: u32,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize, Component, YoleckComponent)]
+struct Rings {
+    count: u32,
+}
+
+#[derive(
+    Default, Clone, PartialEq, Serialize, Deserialize, Component, YoleckComponent, YoleckAutoEdit,
+)]
+struct Telescope {
+    target: YoleckEntityRef,
+}
+
+#[test]
+fn changing_entity_type_keeps_identity_and_shared_data() {
+    let mut harness = EditorHarness::new(|app| {
+        app.add_yoleck_entity_type(
+            YoleckEntityType::new("Asteroid")
+                .with::<Mass>()
+                .with_optional::<Beacon>()
+                .with::<Rings>()
+                .with_uuid(),
+        );
+        app.add_yoleck_entity_type(
+            YoleckEntityType::new("Moon")
+                .with::<Mass>()
+                .with_optional::<Beacon>()
+                .with_uuid(),
+        );
+        app.add_yoleck_entity_type(YoleckEntityType::new("Telescope").with::<Telescope>());
+        app.add_yoleck_auto_edit::<Telescope>();
+    });
+    let level = harness.app.world_mut().spawn(YoleckKeepLevel).id();
+    let uuid = Uuid::new_v4();
+    harness.app.world_mut().write_message::<YoleckDirective>(
+        YoleckDirective::spawn_entity(level, "Asteroid", false)
+            .with(Mass { kg: 12.5 })
+            .with(Beacon { frequency: 7 })
+            .with(Rings { count: 3 })
+            .with_uuid(uuid)
+            .into(),
+    );
+    harness.app.world_mut().write_message::<YoleckDirective>(
+        YoleckDirective::spawn_entity(level, "Telescope", false)
+            .with(Telescope {
+                target: YoleckEntityRef::from_uuid(uuid),
+            })
+            .into(),
+    );
+    harness.settle();
+
+    let asteroid = harness
+        .app
+        .world()
+        .resource::<YoleckUuidRegistry>()
+        .get(uuid)
+        .unwrap();
+    harness
+        .app
+        .world_mut()
+        .get_mut::<YoleckManaged>(asteroid)
+        .unwrap()
+        .name = "Vesta".to_owned();
+    harness.settle();
+
+    harness
+        .app
+        .world_mut()
+        .write_message(YoleckDirective::change_entity_type(asteroid, "Moon"));
+    harness.settle();
+
+    assert!(harness.app.world().get_entity(asteroid).is_err());
+    let moon = harness
+        .app
+        .world()
+        .resource::<YoleckUuidRegistry>()
+        .get(uuid)
+        .unwrap();
+    let world = harness.app.world();
+    let yoleck_managed = world.get::<YoleckManaged>(moon).unwrap();
+    assert_eq!(yoleck_managed.type_name, "Moon");
+    assert_eq!(yoleck_managed.name, "Vesta");
+    assert_eq!(world.get::<YoleckEntityUuid>(moon).unwrap().get(), uuid);
+    assert_eq!(world.get::<Mass>(moon), Some(&Mass { kg: 12.5 }));
+    assert_eq!(world.get::<Beacon>(moon), Some(&Beacon { frequency: 7 }));
+    assert!(world.get::<Rings>(moon).is_none());
+
+    let telescope = harness
+        .app
+        .world_mut()
+        .query::<&Telescope>()
+        .single(harness.app.world())
+        .unwrap();
+    assert_eq!(telescope.target.entity(), Some(moon));
+}
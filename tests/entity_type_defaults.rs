@@ -0,0 +1,47 @@
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use bevy_yoleck::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Clone, PartialEq, Serialize, Deserialize, Component, YoleckComponent)]
+struct Speed(f32);
+
+#[test]
+fn missing_components_use_the_entity_type_defaults() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default(), StatesPlugin))
+        .add_plugins(YoleckPluginForGame);
+    app.add_yoleck_entity_type(YoleckEntityType::new("Walker").with::<Speed>());
+    app.add_yoleck_entity_type(
+        YoleckEntityType::new("Runner")
+            .with::<Speed>()
+            .with_default(Speed(5.0)),
+    );
+
+    let level: YoleckRawLevel = serde_json::from_value(serde_json::json!([
+        {"format_version": 2, "app_format_version": 0},
+        {},
+        [
+            [{"type": "Walker"}, {}],
+            [{"type": "Runner"}, {}],
+            [{"type": "Runner"}, {"Speed": 7.0}],
+        ],
+    ]))
+    .unwrap();
+    let level = app
+        .world_mut()
+        .resource_mut::<Assets<YoleckRawLevel>>()
+        .add(level);
+    app.world_mut().spawn(YoleckLoadLevel(level));
+    app.update();
+    app.update();
+
+    let mut speeds: Vec<f32> = app
+        .world_mut()
+        .query::<&Speed>()
+        .iter(app.world())
+        .map(|speed| speed.0)
+        .collect();
+    speeds.sort_by(f32::total_cmp);
+    assert_eq!(speeds, [0.0, 5.0, 7.0]);
+}
@@ -0,0 +1,197 @@
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use bevy_yoleck::prelude::*;
+use bevy_yoleck::{YoleckEntityTypeDefinitionsHandles, YoleckManaged};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Default, Clone, PartialEq, Serialize, Deserialize, Component, YoleckComponent)]
+struct Decoration {
+    sprite: String,
+}
+
+#[test]
+fn entity_types_are_created_from_definition_files() {
+    let assets_dir = std::env::temp_dir().join(format!(
+        "yoleck_entity_type_definitions_{}",
+        std::process::id()
+    ));
+    std::fs::create_dir_all(&assets_dir).unwrap();
+    std::fs::write(
+        assets_dir.join("entity_types.yolt"),
+        serde_json::json!([
+            {"format_version": 1},
+            [{
+                "name": "Bush",
+                "components": ["Decoration"],
+                "defaults": {"Decoration": {"sprite": "bush.png"}},
+                "category": "Decorations",
+            }],
+        ])
+        .to_string(),
+    )
+    .unwrap();
+
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        AssetPlugin {
+            file_path: assets_dir.to_string_lossy().into_owned(),
+            ..Default::default()
+        },
+        StatesPlugin,
+    ))
+    .add_plugins(YoleckPluginForGame);
+    app.add_yoleck_component::<Decoration>();
+    app.add_yoleck_entity_type_definitions("entity_types.yolt");
+
+    let handle = app
+        .world()
+        .resource::<YoleckEntityTypeDefinitionsHandles>()
+        .0[0]
+        .clone();
+    for _ in 0..1000 {
+        app.update();
+        if app.world().resource::<AssetServer>().is_loaded(&handle) {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(5));
+    }
+    app.update();
+
+    let level: YoleckRawLevel = serde_json::from_value(serde_json::json!([
+        {"format_version": 2, "app_format_version": 0},
+        {},
+        [
+            [{"type": "Bush"}, {}],
+            [{"type": "Bush"}, {"Decoration": {"sprite": "small_bush.png"}}],
+        ],
+    ]))
+    .unwrap();
+    let level = app
+        .world_mut()
+        .resource_mut::<Assets<YoleckRawLevel>>()
+        .add(level);
+    app.world_mut().spawn(YoleckLoadLevel(level));
+    app.update();
+    app.update();
+
+    let mut sprites: Vec<String> = app
+        .world_mut()
+        .query::<&Decoration>()
+        .iter(app.world())
+        .map(|decoration| decoration.sprite.clone())
+        .collect();
+    sprites.sort();
+    assert_eq!(sprites, ["bush.png", "small_bush.png"]);
+
+    std::fs::remove_dir_all(&assets_dir).unwrap();
+}
+
+#[derive(Default, Clone, PartialEq, Serialize, Deserialize, Component, YoleckComponent)]
+struct Glow {
+    brightness: f32,
+}
+
+fn definition(
+    name: &str,
+    extends: Option<&str>,
+    components: &[&str],
+) -> YoleckEntityTypeDefinition {
+    YoleckEntityTypeDefinition {
+        name: name.to_owned(),
+        extends: extends.map(str::to_owned),
+        components: components.iter().map(|key| (*key).to_owned()).collect(),
+        defaults: Default::default(),
+        category: None,
+        icon: None,
+        uuid: false,
+    }
+}
+
+#[test]
+fn existing_entities_are_updated_when_definitions_change() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default(), StatesPlugin))
+        .add_plugins(YoleckPluginForGame);
+    app.add_yoleck_component::<Glow>();
+    app.add_yoleck_entity_type(
+        YoleckEntityType::new("Plant")
+            .with::<Decoration>()
+            .with_uuid(),
+    );
+
+    // `Bush` extends `Shrub`, which is defined in a file that comes after it.
+    let mut definitions_assets = app
+        .world_mut()
+        .resource_mut::<Assets<YoleckEntityTypeDefinitions>>();
+    let bushes = definitions_assets.add(YoleckEntityTypeDefinitions::new([definition(
+        "Bush",
+        Some("Shrub"),
+        &[],
+    )]));
+    let shrubs = definitions_assets.add(YoleckEntityTypeDefinitions::new([definition(
+        "Shrub",
+        Some("Plant"),
+        &[],
+    )]));
+    app.world_mut()
+        .resource_mut::<YoleckEntityTypeDefinitionsHandles>()
+        .0
+        .extend([bushes.clone(), shrubs]);
+    let set_bushes = |app: &mut App, definitions: Vec<YoleckEntityTypeDefinition>| {
+        *app.world_mut()
+            .resource_mut::<Assets<YoleckEntityTypeDefinitions>>()
+            .get_mut(&bushes)
+            .unwrap() = YoleckEntityTypeDefinitions::new(definitions);
+        app.update();
+        app.update();
+    };
+    set_bushes(&mut app, vec![definition("Bush", Some("Shrub"), &[])]);
+
+    let uuid = Uuid::new_v4();
+    let level: YoleckRawLevel = serde_json::from_value(serde_json::json!([
+        {"format_version": 2, "app_format_version": 0},
+        {},
+        [
+            [{"type": "Bush", "name": "bush", "uuid": uuid}, {"Decoration": {"sprite": "bush.png"}}],
+        ],
+    ]))
+    .unwrap();
+    let level = app
+        .world_mut()
+        .resource_mut::<Assets<YoleckRawLevel>>()
+        .add(level);
+    app.world_mut().spawn(YoleckLoadLevel(level));
+    app.update();
+    app.update();
+
+    let registry = app.world().resource::<YoleckUuidRegistry>();
+    let bush = registry.get_of_type(uuid, "Plant").unwrap();
+    let yoleck_managed = app.world().get::<YoleckManaged>(bush).unwrap();
+    assert!(yoleck_managed.is_type("Shrub") && yoleck_managed.is_type("Plant"));
+
+    // Only the base types changed, so the entity is updated in place.
+    let mut bush_definition = definition("Bush", None, &["Decoration"]);
+    bush_definition.uuid = true;
+    set_bushes(&mut app, vec![bush_definition.clone()]);
+    let registry = app.world().resource::<YoleckUuidRegistry>();
+    assert_eq!(registry.get_of_type(uuid, "Plant"), None);
+    assert_eq!(registry.get_of_type(uuid, "Bush"), Some(bush));
+    let yoleck_managed = app.world().get::<YoleckManaged>(bush).unwrap();
+    assert!(!yoleck_managed.is_type("Plant"));
+
+    // The components changed, so the entity is respawned with them.
+    bush_definition.components.push("Glow".to_owned());
+    set_bushes(&mut app, vec![bush_definition]);
+    assert!(app.world().get_entity(bush).is_err());
+    let bush = app
+        .world()
+        .resource::<YoleckUuidRegistry>()
+        .get_of_type(uuid, "Bush")
+        .unwrap();
+    let bush = app.world().entity(bush);
+    assert_eq!(bush.get::<YoleckManaged>().unwrap().name, "bush");
+    assert_eq!(bush.get::<Decoration>().unwrap().sprite, "bush.png");
+    assert!(bush.contains::<Glow>());
+}
@@ -0,0 +1,121 @@
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use bevy_yoleck::YoleckManaged;
+use bevy_yoleck::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Clone, PartialEq, Serialize, Deserialize, Component, YoleckComponent)]
+struct Health(f32);
+
+#[derive(Default, Clone, PartialEq, Serialize, Deserialize, Component, YoleckComponent)]
+struct Wings {
+    span: f32,
+}
+
+#[derive(Component)]
+struct IsEnemy;
+
+#[test]
+fn entity_types_inherit_from_their_base_types() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default(), StatesPlugin))
+        .add_plugins(YoleckPluginForGame);
+    app.add_yoleck_entity_type(
+        YoleckEntityType::new("Enemy")
+            .with::<Health>()
+            .with_default(Health(10.0))
+            .insert_on_init(|| IsEnemy),
+    );
+    app.add_yoleck_entity_type(
+        YoleckEntityType::new("FlyingEnemy")
+            .extends("Enemy")
+            .with::<Wings>(),
+    );
+
+    let level: YoleckRawLevel = serde_json::from_value(serde_json::json!([
+        {"format_version": 2, "app_format_version": 0},
+        {},
+        [
+            [{"type": "Enemy"}, {}],
+            [{"type": "FlyingEnemy"}, {"Wings": {"span": 2.0}}],
+        ],
+    ]))
+    .unwrap();
+    let level = app
+        .world_mut()
+        .resource_mut::<Assets<YoleckRawLevel>>()
+        .add(level);
+    app.world_mut().spawn(YoleckLoadLevel(level));
+    app.update();
+    app.update();
+
+    let mut enemies: Vec<(String, f32, Option<f32>, bool)> = app
+        .world_mut()
+        .query_filtered::<(&YoleckManaged, &Health, Option<&Wings>), With<IsEnemy>>()
+        .iter(app.world())
+        .map(|(managed, health, wings)| {
+            assert!(managed.is_type("Enemy"));
+            (
+                managed.type_name.clone(),
+                health.0,
+                wings.map(|wings| wings.span),
+                managed.is_type("FlyingEnemy"),
+            )
+        })
+        .collect();
+    enemies.sort_by(|a, b| a.0.cmp(&b.0));
+    assert_eq!(
+        enemies,
+        [
+            ("Enemy".to_owned(), 10.0, None, false),
+            ("FlyingEnemy".to_owned(), 10.0, Some(2.0), true),
+        ]
+    );
+}
+
+#[test]
+fn entity_types_can_require_optional_components_of_their_base_types() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default(), StatesPlugin))
+        .add_plugins(YoleckPluginForGame);
+    app.add_yoleck_entity_type(
+        YoleckEntityType::new("Enemy")
+            .with::<Health>()
+            .with_optional::<Wings>()
+            .insert_on_init(|| IsEnemy),
+    );
+    app.add_yoleck_entity_type(
+        YoleckEntityType::new("Bird")
+            .extends("Enemy")
+            .with::<Wings>(),
+    );
+
+    let level: YoleckRawLevel = serde_json::from_value(serde_json::json!([
+        {"format_version": 2, "app_format_version": 0},
+        {},
+        [
+            [{"type": "Enemy"}, {}],
+            [{"type": "Bird"}, {}],
+        ],
+    ]))
+    .unwrap();
+    let level = app
+        .world_mut()
+        .resource_mut::<Assets<YoleckRawLevel>>()
+        .add(level);
+    app.world_mut().spawn(YoleckLoadLevel(level));
+    app.update();
+    app.update();
+
+    let mut enemies: Vec<(String, bool)> = app
+        .world_mut()
+        .query_filtered::<(&YoleckManaged, Has<Wings>), With<IsEnemy>>()
+        .iter(app.world())
+        .map(|(managed, has_wings)| (managed.type_name.clone(), has_wings))
+        .collect();
+    enemies.sort();
+    assert_eq!(
+        enemies,
+        [("Bird".to_owned(), true), ("Enemy".to_owned(), false)]
+    );
+}
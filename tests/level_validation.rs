@@ -0,0 +1,60 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_yoleck::YoleckDirective;
+use bevy_yoleck::prelude::*;
+use bevy_yoleck::validation::YoleckLevelValidation;
+use serde::{Deserialize, Serialize};
+
+use common::EditorHarness;
+
+#[derive(
+    Default, Clone, PartialEq, Serialize, Deserialize, Component, YoleckComponent, YoleckAutoEdit,
+)]
+struct Health {
+    #[yoleck(min = 0)]
+    hit_points: i32,
+}
+
+#[test]
+fn level_is_validated_again_when_entities_change() {
+    let mut harness = EditorHarness::new(|app| {
+        app.add_yoleck_entity_type(YoleckEntityType::new("Enemy").with::<Health>());
+        app.add_yoleck_auto_edit::<Health>();
+    });
+    let level = harness.app.world_mut().spawn(YoleckKeepLevel).id();
+    harness.app.world_mut().write_message::<YoleckDirective>(
+        YoleckDirective::spawn_entity(level, "Enemy", false)
+            .with(Health { hit_points: 10 })
+            .into(),
+    );
+    harness.settle();
+    let problems = |harness: &EditorHarness| {
+        harness
+            .app
+            .world()
+            .resource::<YoleckLevelValidation>()
+            .problems
+            .len()
+    };
+    assert_eq!(problems(&harness), 0);
+
+    let enemy = harness
+        .app
+        .world_mut()
+        .query_filtered::<Entity, With<Health>>()
+        .single(harness.app.world())
+        .unwrap();
+    harness
+        .app
+        .world_mut()
+        .get_mut::<Health>(enemy)
+        .unwrap()
+        .hit_points = -1;
+    harness.settle();
+    assert_eq!(problems(&harness), 1);
+
+    harness.app.world_mut().despawn(enemy);
+    harness.settle();
+    assert_eq!(problems(&harness), 0);
+}
@@ -0,0 +1,244 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_yoleck::YoleckDirective;
+use bevy_yoleck::bevy_egui::egui;
+use bevy_yoleck::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use common::EditorHarness;
+
+#[derive(
+    Default, Clone, PartialEq, Serialize, Deserialize, Component, YoleckComponent, YoleckAutoEdit,
+)]
+struct Thruster {
+    enabled: bool,
+    #[serde(rename = "power")]
+    strength: i32,
+    #[serde(skip)]
+    runtime_heat: u32,
+}
+
+fn count_mixed_markers(output: &egui::FullOutput) -> usize {
+    output
+        .shapes
+        .iter()
+        .filter(|clipped_shape| {
+            matches!(
+                &clipped_shape.shape,
+                egui::epaint::Shape::Text(text_shape) if text_shape.galley.text() == "≠"
+            )
+        })
+        .count()
+}
+
+/// Spawn ships with the given `enabled` and `strength` values and distinct `runtime_heat`s, and
+/// select all of them.
+fn select_ships(ships: &[(bool, i32)]) -> (EditorHarness, Vec<Entity>) {
+    let mut harness = EditorHarness::new(|app| {
+        app.add_yoleck_entity_type(YoleckEntityType::new("Ship").with::<Thruster>());
+        app.add_yoleck_auto_edit::<Thruster>();
+    });
+    let level = harness.app.world_mut().spawn(YoleckKeepLevel).id();
+    for (enabled, strength) in ships {
+        harness.app.world_mut().write_message::<YoleckDirective>(
+            YoleckDirective::spawn_entity(level, "Ship", false)
+                .with(Thruster {
+                    enabled: *enabled,
+                    strength: *strength,
+                    runtime_heat: 0,
+                })
+                .into(),
+        );
+    }
+    harness.settle();
+
+    let ships = harness
+        .app
+        .world_mut()
+        .query_filtered::<Entity, With<Thruster>>()
+        .iter(harness.app.world())
+        .collect::<Vec<_>>();
+    for (index, ship) in ships.iter().enumerate() {
+        harness
+            .app
+            .world_mut()
+            .get_mut::<Thruster>(*ship)
+            .unwrap()
+            .runtime_heat = 10 + index as u32;
+        harness
+            .app
+            .world_mut()
+            .write_message(YoleckDirective::toggle_selected(*ship));
+    }
+    (harness, ships)
+}
+
+#[test]
+fn editing_several_entities_marks_differing_fields() {
+    let (mut harness, _) = select_ships(&[(false, 1), (true, 2)]);
+    let output = harness.settle();
+    // Both `enabled` and the renamed `strength` differ between the ships.
+    assert_eq!(count_mixed_markers(&output), 2);
+}
+
+#[test]
+fn editing_several_entities_keeps_their_skipped_fields() {
+    let (mut harness, ships) = select_ships(&[(false, 3), (true, 3)]);
+    let output = harness.settle();
+    assert_eq!(count_mixed_markers(&output), 1);
+
+    harness.click_text("3");
+    harness.frame(vec![egui::Event::Text("7".to_owned())]);
+    harness.frame(vec![egui::Event::Key {
+        key: egui::Key::Enter,
+        physical_key: None,
+        pressed: true,
+        repeat: false,
+        modifiers: Default::default(),
+    }]);
+    harness.settle();
+
+    for (index, ship) in ships.iter().enumerate() {
+        let thruster = harness.app.world().get::<Thruster>(*ship).unwrap();
+        assert_eq!(thruster.strength, 7);
+        assert_eq!(thruster.runtime_heat, 10 + index as u32);
+    }
+    let enabled: Vec<bool> = ships
+        .iter()
+        .map(|ship| harness.app.world().get::<Thruster>(*ship).unwrap().enabled)
+        .collect();
+    assert_ne!(
+        enabled[0], enabled[1],
+        "fields that were not edited should keep their values"
+    );
+}
+
+#[derive(Default, Clone, PartialEq, Serialize, Deserialize, YoleckAutoEdit)]
+struct Engine {
+    #[serde(rename = "power")]
+    strength: i32,
+    #[serde(skip)]
+    runtime_heat: u32,
+}
+
+#[derive(Default, Clone, PartialEq, Serialize, Deserialize, YoleckAutoEdit)]
+struct Fleet {
+    engines: Vec<Engine>,
+    spare: Option<Engine>,
+    #[serde(skip)]
+    runtime_speed: f32,
+}
+
+#[test]
+fn changed_nested_fields_are_applied_in_place() {
+    use bevy_yoleck::auto_edit::{YoleckAutoEdit, YoleckSerializedPaths};
+
+    let engine = |strength, runtime_heat| Engine {
+        strength,
+        runtime_heat,
+    };
+    let mut fleet = Fleet {
+        engines: vec![engine(1, 10), engine(2, 20)],
+        spare: Some(engine(3, 30)),
+        runtime_speed: 1.5,
+    };
+    let json = serde_json::json!({
+        "engines": [{"power": 1}, {"power": 5}],
+        "spare": {"power": 6},
+    });
+    for path in [["engines", "1", "power"].as_slice(), &["spare", "power"]] {
+        let path: Vec<String> = path.iter().map(|key| key.to_string()).collect();
+        let paths = YoleckSerializedPaths::of(&fleet);
+        assert!(Fleet::auto_edit_apply_json(
+            &mut fleet, &json, &path, &paths
+        ));
+    }
+    assert!(fleet.engines == [engine(1, 10), engine(5, 20)]);
+    assert!(fleet.spare == Some(engine(6, 30)));
+    assert_eq!(fleet.runtime_speed, 1.5);
+
+    // Replacing a whole element requires deserializing the list, but the other fields are kept.
+    let paths = YoleckSerializedPaths::of(&fleet);
+    assert!(Fleet::auto_edit_apply_json(
+        &mut fleet,
+        &json,
+        &["engines".to_owned(), "1".to_owned()],
+        &paths,
+    ));
+    assert!(fleet.engines == [engine(1, 0), engine(5, 0)]);
+    assert_eq!(fleet.runtime_speed, 1.5);
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", content = "data")]
+enum NozzleMode {
+    Pulse { pulse_length: f32 },
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Nozzle {
+    spray_angle: f32,
+    #[serde(flatten)]
+    engine: Engine,
+    mode: NozzleMode,
+}
+
+#[test]
+fn serialized_paths_follow_serde_naming() {
+    use bevy_yoleck::auto_edit::{YoleckPartAddress, YoleckSerializedPaths};
+
+    let nozzle = Nozzle {
+        spray_angle: 30.0,
+        engine: Engine {
+            strength: 2,
+            runtime_heat: 0,
+        },
+        mode: NozzleMode::Pulse { pulse_length: 0.5 },
+    };
+    let paths = YoleckSerializedPaths::of(&nozzle);
+    let path_of = |part| paths.path_of(part).map(|path| path.join("/"));
+    assert_eq!(
+        path_of(YoleckPartAddress::of(&nozzle.spray_angle)).as_deref(),
+        Some("sprayAngle")
+    );
+    assert_eq!(path_of(YoleckPartAddress::of(&nozzle.engine)), None);
+    assert_eq!(
+        path_of(YoleckPartAddress::of(&nozzle.engine.strength)).as_deref(),
+        Some("power")
+    );
+    assert_eq!(
+        path_of(YoleckPartAddress::of(&nozzle.engine.runtime_heat)),
+        None
+    );
+    let NozzleMode::Pulse { pulse_length } = &nozzle.mode;
+    assert_eq!(
+        path_of(YoleckPartAddress::of(pulse_length)).as_deref(),
+        Some("mode/data/pulse_length")
+    );
+}
+
+#[derive(
+    Default, Clone, PartialEq, Serialize, Deserialize, Component, YoleckComponent, YoleckAutoEdit,
+)]
+struct Cargo {
+    crates: u32,
+}
+
+#[test]
+fn components_outside_of_panels_keep_their_header() {
+    let (mut harness, ships) = select_ships(&[(false, 1), (true, 2)]);
+    // `Ship` does not register `Cargo`, so it gets no component panel.
+    harness.app.add_yoleck_auto_edit::<Cargo>();
+    for ship in ships {
+        harness
+            .app
+            .world_mut()
+            .entity_mut(ship)
+            .insert(Cargo::default());
+    }
+    let output = harness.settle();
+    assert!(common::find_text(&output, "Thruster (2 entities)").is_some());
+    assert!(common::find_text(&output, "Cargo (2 entities)").is_some());
+}
@@ -0,0 +1,52 @@
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use bevy_yoleck::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Clone, PartialEq, Serialize, Deserialize, Component, YoleckComponent)]
+struct Crate {
+    size: f32,
+}
+
+#[derive(Default, Clone, PartialEq, Serialize, Deserialize, Component, YoleckComponent)]
+struct Breakable {
+    hits: u32,
+}
+
+#[test]
+fn optional_components_are_only_loaded_for_entities_that_have_them() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default(), StatesPlugin))
+        .add_plugins(YoleckPluginForGame);
+    app.add_yoleck_entity_type(
+        YoleckEntityType::new("Crate")
+            .with::<Crate>()
+            .with_optional::<Breakable>(),
+    );
+
+    let level: YoleckRawLevel = serde_json::from_value(serde_json::json!([
+        {"format_version": 2, "app_format_version": 0},
+        {},
+        [
+            [{"type": "Crate"}, {"Crate": {"size": 1.0}}],
+            [{"type": "Crate"}, {"Crate": {"size": 2.0}, "Breakable": {"hits": 3}}],
+        ],
+    ]))
+    .unwrap();
+    let level = app
+        .world_mut()
+        .resource_mut::<Assets<YoleckRawLevel>>()
+        .add(level);
+    app.world_mut().spawn(YoleckLoadLevel(level));
+    app.update();
+    app.update();
+
+    let mut crates: Vec<(f32, Option<u32>)> = app
+        .world_mut()
+        .query::<(&Crate, Option<&Breakable>)>()
+        .iter(app.world())
+        .map(|(crate_, breakable)| (crate_.size, breakable.map(|breakable| breakable.hits)))
+        .collect();
+    crates.sort_by(|a, b| a.0.total_cmp(&b.0));
+    assert_eq!(crates, [(1.0, None), (2.0, Some(3))]);
+}
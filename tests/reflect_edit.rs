@@ -0,0 +1,69 @@
+#![cfg(feature = "bevy_reflect")]
+
+use bevy::prelude::*;
+use bevy::reflect::TypeRegistry;
+use bevy_yoleck::auto_edit::FieldAttrs;
+use bevy_yoleck::egui;
+use bevy_yoleck::reflect_edit::reflect_edit;
+
+#[derive(Reflect, Default, Clone, PartialEq, Debug)]
+#[reflect(Default)]
+enum Weather {
+    #[default]
+    Clear,
+    Rain {
+        intensity: f32,
+    },
+    Fog(f32, u8),
+}
+
+#[derive(Reflect, Default, Clone, PartialEq, Debug)]
+#[reflect(Default)]
+struct Sky {
+    #[reflect(@FieldAttrs { label: Some("sun brightness".to_owned()), range: Some((0.0, 2.0)), ..Default::default() })]
+    brightness: f32,
+    weather: Weather,
+    cloud_heights: Vec<f32>,
+    moon: Option<Vec2>,
+    #[reflect(ignore)]
+    cached_color: u32,
+}
+
+fn rendered_texts(sky: &mut Sky, type_registry: &TypeRegistry) -> Vec<String> {
+    let ctx = egui::Context::default();
+    let mut output = ctx.run_ui(Default::default(), |ui| {
+        reflect_edit(sky, ui, "", &FieldAttrs::default(), type_registry);
+    });
+    output.textures_delta.clear();
+    output
+        .shapes
+        .iter()
+        .filter_map(|clipped| match &clipped.shape {
+            egui::Shape::Text(text) => Some(text.galley.text().to_owned()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn edit_with_reflection() {
+    let mut type_registry = TypeRegistry::default();
+    type_registry.register::<Sky>();
+
+    let mut sky = Sky {
+        brightness: 1.5,
+        weather: Weather::Fog(0.5, 3),
+        cloud_heights: vec![10.0, 20.0],
+        moon: Some(Vec2::new(1.0, 2.0)),
+        cached_color: 7,
+    };
+    let original = sky.clone();
+    let texts = rendered_texts(&mut sky, &type_registry);
+    assert_eq!(sky, original);
+
+    assert!(texts.iter().any(|text| text == "sun brightness"));
+    assert!(texts.iter().any(|text| text == "Fog"));
+    assert!(texts.iter().any(|text| text == "cloud heights (2)"));
+    assert!(texts.iter().any(|text| text == "Some"));
+    assert!(!texts.iter().any(|text| text.contains("cached")));
+}